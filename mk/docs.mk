@@ -202,6 +202,18 @@ endef
 
 $(foreach docname,$(DOCS),$(eval $(call DEF_DOC,$(docname))))
 
+# The error index is generated from the extended descriptions registered
+# in librustc/diagnostics.rs
+DOC_TARGETS += doc/diagnostics.html
+doc/diagnostics.md: $(S)src/librustc/diagnostics.rs \
+		$(S)src/etc/extract_diagnostics.py | doc/
+	@$(call E, extract_diagnostics: $@)
+	$(Q)$(CFG_PYTHON) $(S)src/etc/extract_diagnostics.py $< >$@
+
+doc/diagnostics.html: doc/diagnostics.md $(HTML_DEPS) | doc/
+	@$(call E, rustdoc: $@)
+	$(Q)$(RUSTDOC) $(RUSTDOC_HTML_OPTS) $<
+
 
 # Localized documentation
 
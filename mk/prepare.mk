@@ -141,7 +141,9 @@ prepare-target-$(2)-host-$(3)-$(1)-$(4): prepare-maybe-clean-$(4) \
             $$(foreach crate,$$(HOST_CRATES),\
               $$(call PREPARE_LIB,$$(call CFG_LIB_GLOB_$(2),$$(crate)))),)\
           $$(call PREPARE_LIB,libmorestack.a) \
-          $$(call PREPARE_LIB,libcompiler-rt.a),),),)
+          $$(call PREPARE_LIB,libcompiler-rt.a) \
+          $$(foreach rt,$$(RUNTIMES_$(2)),\
+            $$(call PREPARE_LIB,librustc_$$(rt).a)),),),)
 endef
 
 define DEF_PREPARE
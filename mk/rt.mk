@@ -357,6 +357,70 @@ $$(COMPRT_LIB_$(1)): $$(COMPRT_DEPS) $$(MKFILE_DEPS)
 		triple-builtins
 	$$(Q)cp $$(COMPRT_BUILD_DIR_$(1))/triple/builtins/libcompiler_rt.a $$(COMPRT_LIB_$(1))
 
+################################################################################
+# sanitizer runtimes
+#
+# The runtimes linked into executables built with -Z sanitizer. They come out
+# of compiler-rt's clang_linux configurations, and are only built when
+# configured with --enable-sanitizers, for x86_64 linux as that is the only
+# target the sanitizers are supported on.
+################################################################################
+
+SANITIZERS_$(1) :=
+ifdef CFG_ENABLE_SANITIZERS
+ifeq ($(1),x86_64-unknown-linux-gnu)
+SANITIZERS_$(1) := asan tsan msan
+endif
+endif
+
+$$(RT_OUTPUT_DIR_$(1))/$$(call CFG_STATIC_LIB_NAME_$(1),rustc_%san): \
+	    $$(COMPRT_DEPS) $$(MKFILE_DEPS)
+	@$$(call E, make: compiler-rt $$*san)
+	$$(Q)$$(MAKE) -C "$(S)src/compiler-rt" \
+		ProjSrcRoot="$(S)src/compiler-rt" \
+		ProjObjRoot="$$(abspath $$(COMPRT_BUILD_DIR_$(1)))" \
+		CC="$$(CC_$(1))" \
+		AR="$$(AR_$(1))" \
+		RANLIB="$$(AR_$(1)) s" \
+		clang_linux-$$*san-x86_64
+	$$(Q)cp $$(COMPRT_BUILD_DIR_$(1))/clang_linux/$$*san-x86_64/libcompiler_rt.a $$@
+
+################################################################################
+# profile runtime
+#
+# The gcov runtime linked into crates built with -Z profile, again from
+# compiler-rt's clang_linux configurations. Like the sanitizer runtimes, it is
+# only built when asked for, with --enable-profiler.
+################################################################################
+
+PROFILE_RT_$(1) :=
+ifdef CFG_ENABLE_PROFILER
+ifeq ($(1),x86_64-unknown-linux-gnu)
+PROFILE_CONFIG_$(1) := profile-x86_64
+PROFILE_RT_$(1) := profile
+endif
+ifeq ($(1),i686-unknown-linux-gnu)
+PROFILE_CONFIG_$(1) := profile-i386
+PROFILE_RT_$(1) := profile
+endif
+endif
+
+# Runtimes for instrumented code, copied into the target libdir as
+# librustc_$(name).a
+RUNTIMES_$(1) := $$(SANITIZERS_$(1)) $$(PROFILE_RT_$(1))
+
+$$(RT_OUTPUT_DIR_$(1))/$$(call CFG_STATIC_LIB_NAME_$(1),rustc_profile): \
+	    $$(COMPRT_DEPS) $$(MKFILE_DEPS)
+	@$$(call E, make: compiler-rt profile)
+	$$(Q)$$(MAKE) -C "$(S)src/compiler-rt" \
+		ProjSrcRoot="$(S)src/compiler-rt" \
+		ProjObjRoot="$$(abspath $$(COMPRT_BUILD_DIR_$(1)))" \
+		CC="$$(CC_$(1))" \
+		AR="$$(AR_$(1))" \
+		RANLIB="$$(AR_$(1)) s" \
+		clang_linux-$$(PROFILE_CONFIG_$(1))
+	$$(Q)cp $$(COMPRT_BUILD_DIR_$(1))/clang_linux/$$(PROFILE_CONFIG_$(1))/libcompiler_rt.a $$@
+
 ################################################################################
 # libbacktrace
 #
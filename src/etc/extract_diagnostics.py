@@ -0,0 +1,40 @@
+#!/usr/bin/env python
+#
+# Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+# file at the top-level directory of this distribution and at
+# http://rust-lang.org/COPYRIGHT.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+# This script extracts the extended error descriptions registered in
+# librustc/diagnostics.rs and writes them out as a markdown page, which
+# is then turned into doc/diagnostics.html by rustdoc.
+
+import re
+import sys
+import textwrap
+
+if len(sys.argv) < 2:
+    print("usage: extract_diagnostics.py <path to diagnostics.rs>")
+    sys.exit(1)
+
+source = open(sys.argv[1]).read()
+
+diag = re.compile(r'register_diagnostic!\((E\d{4}),\s*r##"(.*?)"##\)',
+                  re.DOTALL)
+
+print("% Rust Compiler Error Index")
+print("")
+print("This page lists the extended descriptions of the errors reported by")
+print("`rustc`. The description of a single error can also be printed with")
+print("`rustc --explain E0XXX`.")
+
+for (code, description) in diag.findall(source):
+    print("")
+    print("## " + code)
+    print("")
+    print(textwrap.dedent(description).strip("\n"))
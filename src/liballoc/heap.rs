@@ -12,6 +12,7 @@
 // FIXME: #13996: mark the `allocate` and `reallocate` return value as `noalias`
 //                and `nonnull`
 
+use core::option::Option;
 #[cfg(not(test))] use core::raw;
 #[cfg(not(test))] use util;
 
@@ -88,6 +89,20 @@ pub fn stats_print() {
     imp::stats_print();
 }
 
+/// Return the number of allocations made so far by the whole process, or
+/// `None` if the allocator does not keep count.
+///
+/// Like `stats_print`, the count may be inconsistent if other threads use
+/// the allocator during the call.
+///
+/// This only exists for the compiler's `-Z time-report`, and is not meant to
+/// be a part of the public interface.
+#[experimental = "only for rustc's time report; may be removed"]
+#[doc(hidden)]
+pub fn allocation_count() -> Option<u64> {
+    imp::allocation_count()
+}
+
 // The compiler never calls `exchange_free` on ~ZeroSizeType, so zero-size
 // allocations can point to this `static`. It would be incorrect to use a null
 // pointer, due to enums assuming types like unique pointers are never null.
@@ -130,10 +145,12 @@ unsafe fn closure_exchange_malloc(drop_glue: fn(*mut u8), size: uint,
 
 #[cfg(jemalloc)]
 mod imp {
-    use core::option::{None, Option};
+    use core::mem;
+    use core::option::{None, Option, Some};
     use core::ptr::{RawPtr, mut_null, null};
     use core::num::Int;
-    use libc::{c_char, c_int, c_void, size_t};
+    use core::str::StrSlice;
+    use libc::{c_char, c_int, c_uint, c_void, size_t};
 
     #[link(name = "jemalloc", kind = "static")]
     extern {
@@ -148,6 +165,13 @@ mod imp {
                                                                 *const c_char)>,
                                  cbopaque: *mut c_void,
                                  opts: *const c_char);
+        fn je_mallctl(name: *const c_char, oldp: *mut c_void, oldlenp: *mut size_t,
+                      newp: *mut c_void, newlen: size_t) -> c_int;
+        fn je_mallctlnametomib(name: *const c_char, mibp: *mut size_t,
+                               miblenp: *mut size_t) -> c_int;
+        fn je_mallctlbymib(mib: *const size_t, miblen: size_t, oldp: *mut c_void,
+                           oldlenp: *mut size_t, newp: *mut c_void,
+                           newlen: size_t) -> c_int;
     }
 
     // -lpthread needs to occur after -ljemalloc, the earlier argument isn't enough
@@ -201,11 +225,67 @@ mod imp {
             je_malloc_stats_print(None, mut_null(), null())
         }
     }
+
+    pub fn allocation_count() -> Option<u64> {
+        unsafe {
+            // The statistics are only refreshed when the epoch is advanced.
+            let mut epoch = 1u64;
+            let mut len = mem::size_of::<u64>() as size_t;
+            if je_mallctl("epoch\0".as_ptr() as *const c_char,
+                          &mut epoch as *mut u64 as *mut c_void, &mut len,
+                          &mut epoch as *mut u64 as *mut c_void, len) != 0 {
+                return None;
+            }
+
+            // Arena index `arenas.narenas` holds the sum over all arenas.
+            let mut narenas = 0 as c_uint;
+            let mut len = mem::size_of::<c_uint>() as size_t;
+            if je_mallctl("arenas.narenas\0".as_ptr() as *const c_char,
+                          &mut narenas as *mut c_uint as *mut c_void, &mut len,
+                          mut_null(), 0) != 0 {
+                return None;
+            }
+
+            let small = arena_stat("stats.arenas.0.small.nmalloc\0", narenas);
+            let large = arena_stat("stats.arenas.0.large.nmalloc\0", narenas);
+            let mut huge = 0u64;
+            let mut len = mem::size_of::<u64>() as size_t;
+            if je_mallctl("stats.huge.nmalloc\0".as_ptr() as *const c_char,
+                          &mut huge as *mut u64 as *mut c_void, &mut len,
+                          mut_null(), 0) != 0 {
+                return None;
+            }
+            match (small, large) {
+                (Some(small), Some(large)) => Some(small + large + huge),
+                _ => None
+            }
+        }
+    }
+
+    // Read a per-arena statistic, substituting `arena` for the `0` in `name`.
+    unsafe fn arena_stat(name: &str, arena: c_uint) -> Option<u64> {
+        let mut mib = [0 as size_t, ..5];
+        let mut miblen = 5 as size_t;
+        if je_mallctlnametomib(name.as_ptr() as *const c_char,
+                               &mut mib[0], &mut miblen) != 0 {
+            return None;
+        }
+        mib[2] = arena as size_t;
+        let mut value = 0u64;
+        let mut len = mem::size_of::<u64>() as size_t;
+        if je_mallctlbymib(&mib[0], miblen,
+                           &mut value as *mut u64 as *mut c_void, &mut len,
+                           mut_null(), 0) != 0 {
+            return None;
+        }
+        Some(value)
+    }
 }
 
 #[cfg(not(jemalloc), unix)]
 mod imp {
     use core::mem;
+    use core::option::{None, Option};
     use core::ptr;
     use libc;
     use libc_heap;
@@ -268,11 +348,16 @@ mod imp {
 
     pub fn stats_print() {
     }
+
+    pub fn allocation_count() -> Option<u64> {
+        None
+    }
 }
 
 #[cfg(not(jemalloc), windows)]
 mod imp {
     use libc::{c_void, size_t};
+    use core::option::{None, Option};
     use core::ptr::RawPtr;
 
     extern {
@@ -319,6 +404,8 @@ mod imp {
     }
 
     pub fn stats_print() {}
+
+    pub fn allocation_count() -> Option<u64> { None }
 }
 
 #[cfg(test)]
@@ -19,7 +19,7 @@ use driver::config;
 use llvm;
 use llvm::ModuleRef;
 use metadata::common::LinkMeta;
-use metadata::{encoder, cstore, filesearch, csearch, loader, creader};
+use metadata::{encoder, cstore, filesearch, csearch, creader};
 use middle::trans::context::CrateContext;
 use middle::trans::common::gensym_name;
 use middle::ty;
@@ -30,14 +30,13 @@ use util::sha2::{Digest, Sha256};
 use std::c_str::{ToCStr, CString};
 use std::char;
 use std::collections::HashSet;
-use std::io::{fs, TempDir, Command};
+use std::io::{fs, File, TempDir, Command};
 use std::io;
 use std::ptr;
 use std::str;
 use std::string::String;
 use flate;
 use serialize::hex::ToHex;
-use syntax::abi;
 use syntax::ast;
 use syntax::ast_map::{PathElem, PathElems, PathName};
 use syntax::ast_map;
@@ -52,6 +51,7 @@ pub enum OutputType {
     OutputTypeLlvmAssembly,
     OutputTypeObject,
     OutputTypeExe,
+    OutputTypeDebugInfo,
 }
 
 pub fn llvm_err(sess: &Session, msg: String) -> ! {
@@ -93,19 +93,25 @@ pub mod write {
     use super::{write_output_file, OutputType};
     use super::{OutputTypeAssembly, OutputTypeBitcode};
     use super::{OutputTypeExe, OutputTypeLlvmAssembly};
-    use super::{OutputTypeObject};
+    use super::{OutputTypeObject, OutputTypeDebugInfo};
     use driver::driver::{CrateTranslation, OutputFilenames};
     use driver::config::NoDebugInfo;
     use driver::session::Session;
     use driver::config;
     use llvm;
-    use llvm::{ModuleRef, TargetMachineRef, PassManagerRef};
-    use util::common::time;
-    use syntax::abi;
-
-    use std::c_str::ToCStr;
-    use std::io::{Command};
-    use libc::{c_uint, c_int};
+    use llvm::{ModuleRef, TargetMachineRef, PassManagerRef, ValueRef};
+    use util::common::{time, time_depth};
+    use util::time_report;
+
+    use std::c_str::{ToCStr, CString};
+    use std::collections::HashMap;
+    use std::io::{Command, File, fs};
+    use std::io;
+    use std::ptr;
+    use std::sync::Arc;
+    use std::task::TaskBuilder;
+    use libc;
+    use libc::{c_uint, c_int, c_char, c_void, size_t};
     use std::str;
 
     // On android, we by default compile for armv7 processors. This enables
@@ -114,25 +120,11 @@ pub mod write {
     // cases, so if any sort of target feature is specified we don't append v7
     // to the feature list.
     //
-    // On iOS only armv7 and newer are supported. So it is useful to
-    // get all hardware potential via VFP3 (hardware floating point)
-    // and NEON (SIMD) instructions supported by LLVM.
-    // Note that without those flags various linking errors might
-    // arise as some of intrinsics are converted into function calls
-    // and nobody provides implementations those functions
     fn target_feature<'a>(sess: &'a Session) -> &'a str {
-        match sess.targ_cfg.os {
-            abi::OsAndroid => {
-                if "" == sess.opts.cg.target_feature.as_slice() {
-                    "+v7"
-                } else {
-                    sess.opts.cg.target_feature.as_slice()
-                }
-            },
-            abi::OsiOS if sess.targ_cfg.arch == abi::Arm => {
-                "+v7,+thumb2,+vfp3,+neon"
-            },
-            _ => sess.opts.cg.target_feature.as_slice()
+        if "" == sess.opts.cg.target_feature.as_slice() {
+            sess.targ_cfg.target.options.features.as_slice()
+        } else {
+            sess.opts.cg.target_feature.as_slice()
         }
     }
 
@@ -160,15 +152,10 @@ pub mod write {
             let use_softfp = sess.opts.cg.soft_float;
 
             // FIXME: #11906: Omitting frame pointers breaks retrieving the value of a parameter.
-            // FIXME: #11954: mac64 unwinding may not work with fp elim
             let no_fp_elim = (sess.opts.debuginfo != NoDebugInfo) ||
-                             (sess.targ_cfg.os == abi::OsMacos &&
-                              sess.targ_cfg.arch == abi::X86_64);
+                             !sess.targ_cfg.target.options.eliminate_frame_pointer;
 
-            // OSX has -dead_strip, which doesn't rely on ffunction_sections
-            // FIXME(#13846) this should be enabled for windows
-            let ffunction_sections = sess.targ_cfg.os != abi::OsMacos &&
-                                     sess.targ_cfg.os != abi::OsWin32;
+            let ffunction_sections = sess.targ_cfg.target.options.function_sections;
             let fdata_sections = ffunction_sections;
 
             let reloc_model = match sess.opts.cg.relocation_model.as_slice() {
@@ -202,27 +189,26 @@ pub mod write {
                 }
             };
 
-            let tm = sess.targ_cfg
-                         .target_strs
-                         .target_triple
-                         .as_slice()
-                         .with_c_str(|t| {
-                sess.opts.cg.target_cpu.as_slice().with_c_str(|cpu| {
-                    target_feature(sess).with_c_str(|features| {
-                        llvm::LLVMRustCreateTargetMachine(
-                            t, cpu, features,
-                            code_model,
-                            reloc_model,
-                            opt_level,
-                            true /* EnableSegstk */,
-                            use_softfp,
-                            no_fp_elim,
-                            ffunction_sections,
-                            fdata_sections,
-                        )
-                    })
-                })
-            });
+            let tm_config = TargetMachineConfig {
+                triple: sess.targ_cfg.target.llvm_target.clone(),
+                cpu: sess.opts.cg.target_cpu.clone(),
+                features: target_feature(sess).to_string(),
+                code_model: code_model,
+                reloc_model: reloc_model,
+                opt_level: opt_level,
+                use_softfp: use_softfp,
+                no_fp_elim: no_fp_elim,
+                function_sections: ffunction_sections,
+                data_sections: fdata_sections,
+            };
+
+            let codegen_units = codegen_units(sess, output_types);
+            if codegen_units > 1 {
+                run_codegen_units(sess, trans, &tm_config, codegen_units, output);
+                return;
+            }
+
+            let tm = tm_config.create();
 
             // Create the two optimizing pass managers. These mirror what clang
             // does, and are by populated by LLVM's default PassManagerBuilder.
@@ -254,12 +240,45 @@ pub mod write {
                 })
             }
 
-            // Finally, run the actual optimization passes
-            time(sess.time_passes(), "llvm function passes", (), |()|
-                 llvm::LLVMRustRunFunctionPassManager(fpm, llmod));
+            for pass in instrumentation_passes(sess).iter() {
+                pass.with_c_str(|s| {
+                    if !llvm::LLVMRustAddPass(mpm, s) {
+                        sess.fatal(format!("LLVM lacks the {} instrumentation \
+                                            pass",
+                                           *pass).as_slice());
+                    }
+                })
+            }
+
+            // Finally, run the actual optimization passes. For -Z
+            // llvm-fn-stats the function passes are run one function at a
+            // time, to tell how long each takes. The module passes can't be
+            // split up by function, so their time isn't measured.
+            let insns_before = if sess.llvm_fn_stats() {
+                Some(function_insns(llmod))
+            } else {
+                None
+            };
+            let fn_times = time(sess.time_passes(), "llvm function passes", (), |()| {
+                if sess.llvm_fn_stats() {
+                    run_function_passes_timed(fpm, llmod)
+                } else {
+                    llvm::LLVMRustRunFunctionPassManager(fpm, llmod);
+                    HashMap::new()
+                }
+            });
             time(sess.time_passes(), "llvm module passes", (), |()|
                  llvm::LLVMRunPassManager(mpm, llmod));
 
+            match insns_before {
+                Some(before) => {
+                    let after: HashMap<String, uint> =
+                        function_insns(llmod).move_iter().collect();
+                    print_fn_stats(&trans.fn_items, before, &after, &fn_times);
+                }
+                None => {}
+            }
+
             // Deallocate managers that we're now done with
             llvm::LLVMDisposePassManager(fpm);
             llvm::LLVMDisposePassManager(mpm);
@@ -346,6 +365,8 @@ pub mod write {
                         object_file = Some(output.temp_path(OutputTypeObject));
                         needs_metadata = true;
                     }
+                    // Split off from the linked output after linking
+                    OutputTypeDebugInfo => {}
                 }
             }
 
@@ -375,7 +396,509 @@ pub mod write {
             llvm::LLVMDisposeModule(trans.metadata_module);
             llvm::LLVMDisposeModule(llmod);
             llvm::LLVMContextDispose(llcx);
-            if sess.time_llvm_passes() { llvm::LLVMRustPrintPassTimings(); }
+            report_pass_timings(sess);
+        }
+    }
+
+    /// Everything needed to create a target machine, which can be sent to the
+    /// tasks compiling codegen units.
+    #[deriving(Clone)]
+    struct TargetMachineConfig {
+        triple: String,
+        cpu: String,
+        features: String,
+        code_model: llvm::CodeGenModel,
+        reloc_model: llvm::RelocMode,
+        opt_level: llvm::CodeGenOptLevel,
+        use_softfp: bool,
+        no_fp_elim: bool,
+        function_sections: bool,
+        data_sections: bool,
+    }
+
+    impl TargetMachineConfig {
+        unsafe fn create(&self) -> TargetMachineRef {
+            self.triple.as_slice().with_c_str(|t| {
+                self.cpu.as_slice().with_c_str(|cpu| {
+                    self.features.as_slice().with_c_str(|features| {
+                        llvm::LLVMRustCreateTargetMachine(
+                            t, cpu, features,
+                            self.code_model,
+                            self.reloc_model,
+                            self.opt_level,
+                            true /* EnableSegstk */,
+                            self.use_softfp,
+                            self.no_fp_elim,
+                            self.function_sections,
+                            self.data_sections,
+                        )
+                    })
+                })
+            })
+        }
+    }
+
+    /// The passes instrumenting the crate for the requested sanitizer and
+    /// for gcov, run after the optimization passes like clang does.
+    fn instrumentation_passes(sess: &Session) -> Vec<String> {
+        let mut passes: Vec<String> = match sess.opts.sanitizer {
+            Some(sanitizer) => {
+                sanitizer.llvm_passes().iter().map(|p| p.to_string()).collect()
+            }
+            None => Vec::new(),
+        };
+        if sess.profile() {
+            passes.push("insert-gcov-profiling".to_string());
+        }
+        passes
+    }
+
+    /// The number of units to split the crate into. Splitting is only
+    /// supported when producing object code, and not together with LTO,
+    /// which needs the whole crate in one module, or with gcov profiling,
+    /// whose notes file is named after the crate's source file.
+    fn codegen_units(sess: &Session, output_types: &[OutputType]) -> uint {
+        let units = sess.opts.cg.codegen_units;
+        if units == 1 {
+            return 1;
+        }
+        if sess.lto() {
+            sess.warn("ignoring -C codegen-units, which is incompatible with LTO");
+            return 1;
+        }
+        if sess.profile() {
+            sess.warn("ignoring -C codegen-units, which is incompatible with -Z profile");
+            return 1;
+        }
+        if sess.llvm_fn_stats() {
+            sess.warn("ignoring -C codegen-units, which is incompatible with \
+                       -Z llvm-fn-stats");
+            return 1;
+        }
+        if output_types.iter().any(|&t| {
+            t != OutputTypeObject && t != OutputTypeExe && t != OutputTypeDebugInfo
+        }) {
+            sess.warn("ignoring -C codegen-units, which only applies when \
+                       emitting object files or linking");
+            return 1;
+        }
+        units
+    }
+
+    /// What a task needs to know to optimize and compile one codegen unit.
+    #[deriving(Clone)]
+    struct UnitConfig {
+        tm: TargetMachineConfig,
+        units: uint,
+        passes: Vec<String>,
+        prepopulate_passes: bool,
+        verify: bool,
+        no_builtins: bool,
+    }
+
+    /// Split the crate into `units` LLVM modules, and optimize and compile
+    /// each of them to an object file on a task of its own. The object files
+    /// are then combined into the single object file the linker expects.
+    unsafe fn run_codegen_units(sess: &Session,
+                                trans: &CrateTranslation,
+                                tm_config: &TargetMachineConfig,
+                                units: uint,
+                                output: &OutputFilenames) {
+        let llmod = trans.module;
+
+        externalize_symbols(llmod, trans.link.crate_hash.as_str());
+
+        // Every unit is created from the bitcode of the whole crate, which
+        // is also what goes into an rlib for LTO.
+        let bc = output.temp_path(OutputTypeBitcode);
+        bc.with_c_str(|buf| {
+            llvm::LLVMWriteBitcodeToFile(llmod, buf);
+        });
+        let bitcode = match File::open(&bc).read_to_end() {
+            Ok(bitcode) => Arc::new(bitcode),
+            Err(e) => {
+                sess.fatal(format!("failed to read bitcode from `{}`: {}",
+                                   bc.display(), e).as_slice())
+            }
+        };
+        if !sess.opts.cg.save_temps &&
+           !(sess.crate_types.borrow().contains(&config::CrateTypeRlib) &&
+             sess.opts.output_types.contains(&OutputTypeExe)) {
+            let _ = fs::unlink(&bc);
+        }
+
+        // The tasks can't report to the session, so check the extra passes
+        // up front.
+        let pm = llvm::LLVMCreatePassManager();
+        for pass in sess.opts.cg.passes.iter() {
+            pass.as_slice().with_c_str(|s| {
+                if !llvm::LLVMRustAddPass(pm, s) {
+                    sess.warn(format!("unknown pass {}, ignoring",
+                                      *pass).as_slice());
+                }
+            })
+        }
+        llvm::LLVMDisposePassManager(pm);
+
+        let unit_config = UnitConfig {
+            tm: tm_config.clone(),
+            units: units,
+            passes: sess.opts.cg.passes.clone()
+                        .append(instrumentation_passes(sess).as_slice()),
+            prepopulate_passes: !sess.opts.cg.no_prepopulate_passes,
+            verify: !sess.no_verify(),
+            no_builtins: trans.no_builtins,
+        };
+        let object = output.temp_path(OutputTypeObject);
+        let objects: Vec<Path> = range(0, units).map(|i| {
+            object.with_extension(format!("{}.o", i))
+        }).collect();
+
+        time(sess.time_passes(), "codegen units", (), |()| {
+            let (tx, rx) = channel();
+            for (i, path) in objects.iter().enumerate() {
+                let tx = tx.clone();
+                let bitcode = bitcode.clone();
+                let config = unit_config.clone();
+                let path = path.clone();
+                TaskBuilder::new().named(format!("codegen-unit-{}", i)).spawn(proc() {
+                    let result = unsafe {
+                        compile_unit(&config, bitcode.as_slice(), i, &path)
+                    };
+                    tx.send(result);
+                });
+            }
+            drop(tx);
+            let mut finished = 0u;
+            for result in rx.iter() {
+                finished += 1;
+                match result {
+                    Ok(()) => {}
+                    Err(e) => sess.err(e.as_slice()),
+                }
+            }
+            if finished != units {
+                sess.err("a codegen unit failed to compile");
+            }
+        });
+        sess.abort_if_errors();
+
+        let object_file = if sess.opts.output_types.contains(&OutputTypeExe) {
+            object.clone()
+        } else {
+            output.path(OutputTypeObject)
+        };
+        link_objects_together(sess, objects.as_slice(), &object_file);
+        if !sess.opts.cg.save_temps {
+            for path in objects.iter() {
+                let _ = fs::unlink(path);
+            }
+        }
+
+        if sess.opts.output_types.contains(&OutputTypeExe) {
+            let tm = tm_config.create();
+            let cpm = llvm::LLVMCreatePassManager();
+            llvm::LLVMRustAddAnalysisPasses(tm, cpm, trans.metadata_module);
+            llvm::LLVMRustAddLibraryInfo(cpm, trans.metadata_module, trans.no_builtins);
+            write_output_file(sess, tm, cpm, trans.metadata_module,
+                              &object.with_extension("metadata.o"),
+                              llvm::ObjectFile);
+            llvm::LLVMDisposePassManager(cpm);
+            llvm::LLVMRustDisposeTargetMachine(tm);
+        }
+
+        llvm::LLVMDisposeModule(trans.metadata_module);
+        llvm::LLVMDisposeModule(llmod);
+        llvm::LLVMContextDispose(trans.context);
+        report_pass_timings(sess);
+    }
+
+    /// Print LLVM's pass timings for `-Z time-llvm-passes` and add them to
+    /// the time report, if either was asked for.
+    fn report_pass_timings(sess: &Session) {
+        if !time_report::is_enabled() {
+            if sess.time_llvm_passes() {
+                unsafe { llvm::LLVMRustPrintPassTimings(); }
+            }
+            return
+        }
+
+        let timings = unsafe {
+            let s = llvm::LLVMRustPassTimingsToString();
+            let timings = str::raw::from_c_str(s);
+            libc::free(s as *mut c_void);
+            timings
+        };
+        if sess.time_llvm_passes() {
+            let _ = io::stderr().write_str(timings.as_slice());
+        }
+        time_report::record_llvm_passes(timings.as_slice(), time_depth());
+    }
+
+    fn module_values(llmod: ModuleRef) -> (Vec<ValueRef>, Vec<ValueRef>) {
+        let mut functions = Vec::new();
+        let mut globals = Vec::new();
+        unsafe {
+            let mut f = llvm::LLVMGetFirstFunction(llmod);
+            while f.is_not_null() {
+                functions.push(f);
+                f = llvm::LLVMGetNextFunction(f);
+            }
+            let mut g = llvm::LLVMGetFirstGlobal(llmod);
+            while g.is_not_null() {
+                globals.push(g);
+                g = llvm::LLVMGetNextGlobal(g);
+            }
+        }
+        (functions, globals)
+    }
+
+    unsafe fn value_name(value: ValueRef) -> String {
+        let name = CString::new(llvm::LLVMGetValueName(value), false);
+        name.as_str().unwrap_or("").to_string()
+    }
+
+    /// The names and instruction counts of the functions defined in
+    /// `llmod`, in module order.
+    fn function_insns(llmod: ModuleRef) -> Vec<(String, uint)> {
+        let (functions, _) = module_values(llmod);
+        let mut insns = Vec::new();
+        unsafe {
+            for &f in functions.iter() {
+                if llvm::LLVMIsDeclaration(f) != 0 {
+                    continue;
+                }
+                let mut n = 0u;
+                let mut bb = llvm::LLVMGetFirstBasicBlock(f);
+                while bb.is_not_null() {
+                    let mut insn = llvm::LLVMGetFirstInstruction(bb);
+                    while insn.is_not_null() {
+                        n += 1;
+                        insn = llvm::LLVMGetNextInstruction(insn);
+                    }
+                    bb = llvm::LLVMGetNextBasicBlock(bb);
+                }
+                insns.push((value_name(f), n));
+            }
+        }
+        insns
+    }
+
+    /// Run the function passes over each function defined in `llmod` like
+    /// `LLVMRustRunFunctionPassManager`, returning the nanoseconds spent on
+    /// each one.
+    unsafe fn run_function_passes_timed(fpm: PassManagerRef,
+                                        llmod: ModuleRef) -> HashMap<String, u64> {
+        let (functions, _) = module_values(llmod);
+        let mut times = HashMap::new();
+        llvm::LLVMInitializeFunctionPassManager(fpm);
+        for &f in functions.iter() {
+            if llvm::LLVMIsDeclaration(f) != 0 {
+                continue;
+            }
+            let start = ::time::precise_time_ns();
+            llvm::LLVMRunFunctionPassManager(fpm, f);
+            times.insert(value_name(f), ::time::precise_time_ns() - start);
+        }
+        llvm::LLVMFinalizeFunctionPassManager(fpm);
+        times
+    }
+
+    /// Print the `-Z llvm-fn-stats` report, the functions which took the
+    /// longest in the function passes first. The module passes, like the
+    /// inliner, work on the whole module at once, so their time isn't
+    /// attributed to any function and the column only covers the function
+    /// passes. The instruction counts after optimization do include the
+    /// effects of the module passes.
+    fn print_fn_stats(items: &HashMap<String, String>,
+                      before: Vec<(String, uint)>,
+                      after: &HashMap<String, uint>,
+                      times: &HashMap<String, u64>) {
+        let mut stats: Vec<(u64, uint, String)> = before.move_iter().map(|(name, insns)| {
+            (times.find(&name).map_or(0, |t| *t), insns, name)
+        }).collect();
+        stats.sort_by(|a, b| b.cmp(a));
+
+        println!("--- llvm fn stats ---");
+        println!("{:>8s} {:>8s} {:>12s}  function", "before", "after", "fn passes ms");
+        for &(ns, before, ref name) in stats.iter() {
+            // Functions which were inlined everywhere they were used are
+            // gone after optimization
+            let after = match after.find(name) {
+                Some(n) => n.to_string(),
+                None => "removed".to_string(),
+            };
+            let function = match items.find(name) {
+                Some(item) => format!("{} ({})", *item, *name),
+                None => name.clone(),
+            };
+            println!("{:8u} {:>8s} {:12.3f}  {}",
+                     before, after.as_slice(), ns as f64 / 1_000_000.0, function);
+        }
+    }
+
+    /// Make the internal definitions of the module visible to the other
+    /// codegen units. They keep hidden visibility, so they still don't
+    /// escape the crate's output, and `suffix` makes their names unique
+    /// among the crates a program is linked from.
+    unsafe fn externalize_symbols(llmod: ModuleRef, suffix: &str) {
+        let (functions, globals) = module_values(llmod);
+        for &value in functions.iter().chain(globals.iter()) {
+            if llvm::LLVMIsDeclaration(value) != 0 {
+                continue;
+            }
+            let linkage = llvm::LLVMGetLinkage(value);
+            if linkage != llvm::InternalLinkage as c_uint &&
+               linkage != llvm::PrivateLinkage as c_uint {
+                continue;
+            }
+            let name = CString::new(llvm::LLVMGetValueName(value), false);
+            let name = match name.as_str() {
+                Some(name) if !name.is_empty() => name.to_string(),
+                _ => "anon".to_string(),
+            };
+            format!("{}.{}", name, suffix).with_c_str(|buf| {
+                llvm::LLVMSetValueName(value, buf);
+            });
+            llvm::SetLinkage(value, llvm::ExternalLinkage);
+            llvm::LLVMSetVisibility(value, llvm::HiddenVisibility as c_uint);
+        }
+    }
+
+    /// Turn the definitions that belong to the other codegen units into
+    /// declarations. Definitions are dealt out to the units in the order in
+    /// which they appear in the module, so that all units agree on where
+    /// each one ends up. Definitions which may be duplicated, such as
+    /// `linkonce_odr` ones, are kept in every unit.
+    unsafe fn strip_other_units(llmod: ModuleRef, unit: uint, units: uint) {
+        let (functions, globals) = module_values(llmod);
+        let mut n = 0u;
+        for &f in functions.iter() {
+            if llvm::LLVMIsDeclaration(f) == 0 &&
+               llvm::LLVMGetLinkage(f) == llvm::ExternalLinkage as c_uint {
+                if n % units != unit {
+                    llvm::LLVMRustDeleteFunctionBody(f);
+                }
+                n += 1;
+            }
+        }
+        let mut n = 0u;
+        for &g in globals.iter() {
+            if llvm::LLVMIsDeclaration(g) != 0 {
+                continue;
+            }
+            let linkage = llvm::LLVMGetLinkage(g);
+            if linkage == llvm::AppendingLinkage as c_uint {
+                // Things like `llvm.global_ctors` must only be emitted once.
+                if unit != 0 {
+                    llvm::LLVMDeleteGlobal(g);
+                }
+            } else if linkage == llvm::ExternalLinkage as c_uint {
+                if n % units != unit {
+                    llvm::LLVMSetInitializer(g, ptr::mut_null());
+                }
+                n += 1;
+            }
+        }
+    }
+
+    unsafe fn llvm_error(msg: &str) -> String {
+        let cstr = llvm::LLVMRustGetLastError();
+        if cstr == ptr::null() {
+            msg.to_string()
+        } else {
+            let err = CString::new(cstr, true);
+            format!("{}: {}", msg, String::from_utf8_lossy(err.as_bytes()))
+        }
+    }
+
+    /// Optimize and compile the codegen unit `unit` of the crate's bitcode
+    /// into the object file `path`. This runs on a task of its own, with an
+    /// LLVM context of its own.
+    unsafe fn compile_unit(config: &UnitConfig,
+                           bitcode: &[u8],
+                           unit: uint,
+                           path: &Path) -> Result<(), String> {
+        let llcx = llvm::LLVMContextCreate();
+        let llmod = format!("unit{}", unit).with_c_str(|name| {
+            llvm::LLVMModuleCreateWithNameInContext(name, llcx)
+        });
+        let result = (|| {
+            if !llvm::LLVMRustLinkInExternalBitcode(llmod,
+                                                    bitcode.as_ptr() as *const c_char,
+                                                    bitcode.len() as size_t) {
+                return Err(llvm_error("failed to load bitcode for codegen unit"));
+            }
+            strip_other_units(llmod, unit, config.units);
+
+            let tm = config.tm.create();
+            let fpm = llvm::LLVMCreateFunctionPassManagerForModule(llmod);
+            let mpm = llvm::LLVMCreatePassManager();
+            if config.verify {
+                "verify".with_c_str(|s| llvm::LLVMRustAddPass(fpm, s));
+            }
+            if config.prepopulate_passes {
+                llvm::LLVMRustAddAnalysisPasses(tm, fpm, llmod);
+                llvm::LLVMRustAddAnalysisPasses(tm, mpm, llmod);
+                populate_llvm_passes(fpm, mpm, llmod, config.tm.opt_level,
+                                     config.no_builtins);
+            }
+            for pass in config.passes.iter() {
+                pass.as_slice().with_c_str(|s| llvm::LLVMRustAddPass(mpm, s));
+            }
+            llvm::LLVMRustRunFunctionPassManager(fpm, llmod);
+            llvm::LLVMRunPassManager(mpm, llmod);
+            llvm::LLVMDisposePassManager(fpm);
+            llvm::LLVMDisposePassManager(mpm);
+
+            let cpm = llvm::LLVMCreatePassManager();
+            llvm::LLVMRustAddAnalysisPasses(tm, cpm, llmod);
+            llvm::LLVMRustAddLibraryInfo(cpm, llmod, config.no_builtins);
+            let written = path.with_c_str(|output| {
+                llvm::LLVMRustWriteOutputFile(tm, cpm, llmod, output,
+                                              llvm::ObjectFile)
+            });
+            llvm::LLVMDisposePassManager(cpm);
+            llvm::LLVMRustDisposeTargetMachine(tm);
+            if written {
+                Ok(())
+            } else {
+                Err(llvm_error(format!("could not write codegen unit {}",
+                                       unit).as_slice()))
+            }
+        })();
+        llvm::LLVMDisposeModule(llmod);
+        llvm::LLVMContextDispose(llcx);
+        result
+    }
+
+    /// Combine the object files of the codegen units into one object file
+    /// with a relocatable link.
+    fn link_objects_together(sess: &Session, objects: &[Path], out: &Path) {
+        let pname = super::get_cc_prog(sess);
+        let mut cmd = Command::new(pname.as_slice());
+        cmd.args(["-nostdlib", "-Wl,-r"]).arg("-o").arg(out).args(objects);
+        debug!("{}", &cmd);
+
+        match cmd.output() {
+            Ok(prog) => {
+                if !prog.status.success() {
+                    sess.err(format!("linking codegen units with `{}` failed: {}",
+                                     pname,
+                                     prog.status).as_slice());
+                    sess.note(format!("{}", &cmd).as_slice());
+                    let mut note = prog.error.clone();
+                    note.push_all(prog.output.as_slice());
+                    sess.note(str::from_utf8(note.as_slice()).unwrap());
+                    sess.abort_if_errors();
+                }
+            },
+            Err(e) => {
+                sess.err(format!("could not exec the linker `{}`: {}",
+                                 pname,
+                                 e).as_slice());
+                sess.abort_if_errors();
+            }
         }
     }
 
@@ -432,7 +955,9 @@ pub mod write {
             add("rustc"); // fake program name
             if vectorize_loop { add("-vectorize-loops"); }
             if vectorize_slp  { add("-vectorize-slp");   }
-            if sess.time_llvm_passes() { add("-time-passes"); }
+            if sess.time_llvm_passes() || time_report::is_enabled() {
+                add("-time-passes");
+            }
             if sess.print_llvm_passes() { add("-debug-pass=Structure"); }
 
             for arg in sess.opts.cg.llvm_args.iter() {
@@ -805,18 +1330,9 @@ pub fn mangle_internal_name_by_path_and_seq(path: PathElems, flav: &str) -> Stri
 
 pub fn get_cc_prog(sess: &Session) -> String {
     match sess.opts.cg.linker {
-        Some(ref linker) => return linker.to_string(),
-        None => {}
+        Some(ref linker) => linker.to_string(),
+        None => sess.targ_cfg.target.options.linker.clone(),
     }
-
-    // In the future, FreeBSD will use clang as default compiler.
-    // It would be flexible to use cc (system's default C compiler)
-    // instead of hard-coded gcc.
-    // For win32, there is no cc command, so we add a condition to make it use gcc.
-    match sess.targ_cfg.os {
-        abi::OsWin32 => "gcc",
-        _ => "cc",
-    }.to_string()
 }
 
 pub fn get_ar_prog(sess: &Session) -> String {
@@ -826,6 +1342,13 @@ pub fn get_ar_prog(sess: &Session) -> String {
     }
 }
 
+pub fn get_objcopy_prog(sess: &Session) -> String {
+    match sess.opts.cg.objcopy {
+        Some(ref objcopy) => (*objcopy).clone(),
+        None => "objcopy".to_string()
+    }
+}
+
 fn remove(sess: &Session, path: &Path) {
     match fs::unlink(path) {
         Ok(..) => {}
@@ -847,7 +1370,7 @@ pub fn link_binary(sess: &Session,
     for &crate_type in sess.crate_types.borrow().iter() {
         if invalid_output_for_target(sess, crate_type) {
             sess.bug(format!("invalid output type `{}` for target os `{}`",
-                             crate_type, sess.targ_cfg.os).as_slice());
+                             crate_type, sess.targ_cfg.target.target_os).as_slice());
         }
         let out_file = link_binary_output(sess, trans, crate_type, outputs,
                                           crate_name);
@@ -877,17 +1400,20 @@ pub fn link_binary(sess: &Session,
 /// interaction with Rust code through static library is the only
 /// option for now
 pub fn default_output_for_target(sess: &Session) -> config::CrateType {
-    match sess.targ_cfg.os {
-        abi::OsiOS => config::CrateTypeStaticlib,
-        _ => config::CrateTypeExecutable
+    if sess.targ_cfg.target.options.executables {
+        config::CrateTypeExecutable
+    } else {
+        config::CrateTypeStaticlib
     }
 }
 
 /// Checks if target supports crate_type as output
 pub fn invalid_output_for_target(sess: &Session,
                                  crate_type: config::CrateType) -> bool {
-    match (sess.targ_cfg.os, crate_type) {
-        (abi::OsiOS, config::CrateTypeDylib) => true,
+    match crate_type {
+        config::CrateTypeDylib | config::CrateTypeCdylib => {
+            !sess.targ_cfg.target.options.dynamic_linking
+        }
         _ => false
     }
 }
@@ -908,27 +1434,51 @@ pub fn filename_for_input(sess: &Session,
         config::CrateTypeRlib => {
             out_filename.with_filename(format!("lib{}.rlib", libname))
         }
-        config::CrateTypeDylib => {
-            let (prefix, suffix) = match sess.targ_cfg.os {
-                abi::OsWin32 => (loader::WIN32_DLL_PREFIX, loader::WIN32_DLL_SUFFIX),
-                abi::OsMacos => (loader::MACOS_DLL_PREFIX, loader::MACOS_DLL_SUFFIX),
-                abi::OsLinux => (loader::LINUX_DLL_PREFIX, loader::LINUX_DLL_SUFFIX),
-                abi::OsAndroid => (loader::ANDROID_DLL_PREFIX, loader::ANDROID_DLL_SUFFIX),
-                abi::OsFreebsd => (loader::FREEBSD_DLL_PREFIX, loader::FREEBSD_DLL_SUFFIX),
-                abi::OsiOS => unreachable!(),
-            };
+        config::CrateTypeDylib | config::CrateTypeCdylib => {
+            let options = &sess.targ_cfg.target.options;
             out_filename.with_filename(format!("{}{}{}",
-                                               prefix,
+                                               options.dll_prefix,
                                                libname,
-                                               suffix))
+                                               options.dll_suffix))
         }
         config::CrateTypeStaticlib => {
             out_filename.with_filename(format!("lib{}.a", libname))
         }
-        config::CrateTypeExecutable => out_filename.clone(),
+        config::CrateTypeExecutable => {
+            let suffix = sess.targ_cfg.target.options.exe_suffix.as_slice();
+            if suffix.is_empty() {
+                out_filename.clone()
+            } else {
+                out_filename.with_filename(format!("{}{}",
+                                                   out_filename.filename_display(),
+                                                   suffix))
+            }
+        }
+    }
+}
+
+/// Whether outputs of the given crate type are produced by the system linker.
+pub fn links_natively(crate_type: config::CrateType) -> bool {
+    match crate_type {
+        config::CrateTypeExecutable | config::CrateTypeDylib |
+        config::CrateTypeCdylib => true,
+        config::CrateTypeRlib | config::CrateTypeStaticlib => false,
     }
 }
 
+/// The file the debuginfo of a linked output is moved into when it is split
+/// off: a .dSYM bundle on OSX and a .debug file elsewhere.
+pub fn debuginfo_filename(sess: &Session, out_filename: &Path) -> Path {
+    let extension = if sess.targ_cfg.target.options.is_like_osx {
+        "dSYM"
+    } else {
+        "debug"
+    };
+    out_filename.with_filename(format!("{}.{}",
+                                       out_filename.filename_display(),
+                                       extension))
+}
+
 fn link_binary_output(sess: &Session,
                       trans: &CrateTranslation,
                       crate_type: config::CrateType,
@@ -967,11 +1517,9 @@ fn link_binary_output(sess: &Session,
         config::CrateTypeStaticlib => {
             link_staticlib(sess, &obj_filename, &out_filename);
         }
-        config::CrateTypeExecutable => {
-            link_natively(sess, trans, false, &obj_filename, &out_filename);
-        }
-        config::CrateTypeDylib => {
-            link_natively(sess, trans, true, &obj_filename, &out_filename);
+        config::CrateTypeExecutable | config::CrateTypeDylib |
+        config::CrateTypeCdylib => {
+            link_natively(sess, trans, crate_type, &obj_filename, &out_filename);
         }
     }
 
@@ -1004,7 +1552,9 @@ fn link_rlib<'a>(sess: &'a Session,
         handler: handler,
         dst: out_filename.clone(),
         lib_search_paths: archive_search_paths(sess),
-        os: sess.targ_cfg.os,
+        slib_prefix: sess.targ_cfg.target.options.staticlib_prefix.clone(),
+        slib_suffix: sess.targ_cfg.target.options.staticlib_suffix.clone(),
+        is_like_osx: sess.targ_cfg.target.options.is_like_osx,
         maybe_ar_prog: sess.opts.cg.ar.clone()
     };
     let mut a = Archive::create(config, obj_filename);
@@ -1094,9 +1644,8 @@ fn link_rlib<'a>(sess: &'a Session,
             // After adding all files to the archive, we need to update the
             // symbol table of the archive. This currently dies on OSX (see
             // #11162), and isn't necessary there anyway
-            match sess.targ_cfg.os {
-                abi::OsMacos | abi::OsiOS => {}
-                _ => { a.update_symbols(); }
+            if !sess.targ_cfg.target.options.is_like_osx {
+                a.update_symbols();
             }
         }
 
@@ -1161,7 +1710,8 @@ fn link_staticlib(sess: &Session, obj_filename: &Path, out_filename: &Path) {
 //
 // This will invoke the system linker/cc to create the resulting file. This
 // links to all upstream files as well.
-fn link_natively(sess: &Session, trans: &CrateTranslation, dylib: bool,
+fn link_natively(sess: &Session, trans: &CrateTranslation,
+                 crate_type: config::CrateType,
                  obj_filename: &Path, out_filename: &Path) {
     let tmpdir = TempDir::new("rustc").expect("needs a temp dir");
 
@@ -1169,9 +1719,10 @@ fn link_natively(sess: &Session, trans: &CrateTranslation, dylib: bool,
     let pname = get_cc_prog(sess);
     let mut cmd = Command::new(pname.as_slice());
 
-    cmd.args(sess.targ_cfg.target_strs.cc_args.as_slice());
-    link_args(&mut cmd, sess, dylib, tmpdir.path(),
+    cmd.args(sess.targ_cfg.target.options.pre_link_args.as_slice());
+    link_args(&mut cmd, sess, crate_type, tmpdir.path(),
               trans, obj_filename, out_filename);
+    cmd.args(sess.targ_cfg.target.options.post_link_args.as_slice());
 
     if (sess.opts.debugging_opts & config::PRINT_LINK_ARGS) != 0 {
         println!("{}", &cmd);
@@ -1207,8 +1758,8 @@ fn link_natively(sess: &Session, trans: &CrateTranslation, dylib: bool,
 
     // On OSX, debuggers need this utility to get run to do some munging of
     // the symbols
-    if (sess.targ_cfg.os == abi::OsMacos || sess.targ_cfg.os == abi::OsiOS)
-        && (sess.opts.debuginfo != NoDebugInfo) {
+    if sess.targ_cfg.target.options.is_like_osx &&
+       sess.opts.debuginfo != NoDebugInfo {
             match Command::new("dsymutil").arg(out_filename).status() {
                 Ok(..) => {}
                 Err(e) => {
@@ -1217,15 +1768,72 @@ fn link_natively(sess: &Session, trans: &CrateTranslation, dylib: bool,
                 }
             }
         }
+
+    if sess.split_debuginfo() {
+        split_debuginfo(sess, out_filename);
+    }
+}
+
+// Move the debuginfo of a linked output into a separate file, leaving the
+// output itself stripped of it.
+//
+// On OSX dsymutil has already collected the debuginfo into a .dSYM bundle, so
+// the output only needs to be stripped. Elsewhere objcopy copies the DWARF
+// sections out, then strips the output and adds a .gnu_debuglink section
+// naming the debug file, which is how debuggers find it.
+fn split_debuginfo(sess: &Session, out_filename: &Path) {
+    let mut cmds = Vec::new();
+    if sess.targ_cfg.target.options.is_like_osx {
+        let mut strip = Command::new("strip");
+        strip.arg("-S").arg(out_filename);
+        cmds.push(strip);
+    } else {
+        let objcopy = get_objcopy_prog(sess);
+        let debug_filename = debuginfo_filename(sess, out_filename);
+
+        let mut extract = Command::new(objcopy.as_slice());
+        extract.arg("--only-keep-debug").arg(out_filename).arg(&debug_filename);
+        cmds.push(extract);
+
+        let mut debuglink = b"--add-gnu-debuglink=".to_vec();
+        debuglink.push_all(debug_filename.as_vec());
+        let mut strip = Command::new(objcopy.as_slice());
+        strip.arg("--strip-debug").arg(debuglink.as_slice()).arg(out_filename);
+        cmds.push(strip);
+    }
+
+    for cmd in cmds.iter() {
+        debug!("{}", cmd);
+        match cmd.output() {
+            Ok(prog) => {
+                if !prog.status.success() {
+                    sess.err(format!("splitting debuginfo failed: {}",
+                                     prog.status).as_slice());
+                    sess.note(format!("{}", cmd).as_slice());
+                    let mut output = prog.error.clone();
+                    output.push_all(prog.output.as_slice());
+                    sess.note(str::from_utf8(output.as_slice()).unwrap());
+                    sess.abort_if_errors();
+                }
+            }
+            Err(e) => {
+                sess.err(format!("could not exec `{}` to split debuginfo: {}",
+                                 cmd, e).as_slice());
+                sess.abort_if_errors();
+            }
+        }
+    }
 }
 
 fn link_args(cmd: &mut Command,
              sess: &Session,
-             dylib: bool,
+             crate_type: config::CrateType,
              tmpdir: &Path,
              trans: &CrateTranslation,
              obj_filename: &Path,
              out_filename: &Path) {
+    let dylib = crate_type == config::CrateTypeDylib ||
+                crate_type == config::CrateTypeCdylib;
 
     // The default library location, we need this to find the runtime.
     // The location of crates will be determined as needed.
@@ -1250,24 +1858,48 @@ fn link_args(cmd: &mut Command,
     // all contents of this library. This way we're guaranteed that the linker
     // will include the __morestack symbol 100% of the time, always resolving
     // references to it even if the object above didn't use it.
-    match sess.targ_cfg.os {
-        abi::OsMacos | abi::OsiOS => {
-            let morestack = lib_path.join("libmorestack.a");
+    if sess.targ_cfg.target.options.is_like_osx {
+        let morestack = lib_path.join("libmorestack.a");
 
-            let mut v = b"-Wl,-force_load,".to_vec();
-            v.push_all(morestack.as_vec());
-            cmd.arg(v.as_slice());
-        }
-        _ => {
-            cmd.args(["-Wl,--whole-archive", "-lmorestack",
-                      "-Wl,--no-whole-archive"]);
+        let mut v = b"-Wl,-force_load,".to_vec();
+        v.push_all(morestack.as_vec());
+        cmd.arg(v.as_slice());
+    } else {
+        cmd.args(["-Wl,--whole-archive", "-lmorestack",
+                  "-Wl,--no-whole-archive"]);
+    }
+
+    // Code instrumented for a sanitizer calls into its runtime, which is
+    // linked into executables only, just as clang does. The whole runtime is
+    // included because it interposes libc functions like malloc, and its
+    // symbols are exported so that dynamic libraries resolve them too. The
+    // thread and memory sanitizers require position independent executables
+    // to tell their shadow memory apart from the program.
+    match sess.opts.sanitizer {
+        Some(sanitizer) if crate_type == config::CrateTypeExecutable => {
+            let runtime = format!("lib{}.a", sanitizer.runtime());
+            if !sess.target_filesearch().get_lib_path().join(runtime).exists() {
+                sess.fatal(format!("the runtime for -Z sanitizer={} was not \
+                                    built; configure with --enable-sanitizers",
+                                   sanitizer).as_slice());
+            }
+            cmd.arg("-Wl,--whole-archive")
+               .arg(format!("-l{}", sanitizer.runtime()))
+               .arg("-Wl,--no-whole-archive")
+               .arg("-Wl,--export-dynamic");
+            if sanitizer != config::AddressSanitizer {
+                cmd.arg("-pie");
+            }
+            cmd.args(["-lpthread", "-ldl", "-lrt", "-lm"]);
         }
+        _ => {}
     }
 
-    // When linking a dynamic library, we put the metadata into a section of the
-    // executable. This metadata is in a separate object file from the main
-    // object file, so we link that in here.
-    if dylib {
+    // When linking a Rust dynamic library, we put the metadata into a section
+    // of the executable. This metadata is in a separate object file from the
+    // main object file, so we link that in here. C dynamic libraries are never
+    // linked against by Rust crates, so they go without.
+    if crate_type == config::CrateTypeDylib {
         cmd.arg(obj_filename.with_extension("metadata.o"));
     }
 
@@ -1278,19 +1910,22 @@ fn link_args(cmd: &mut Command,
     // subset we wanted.
     //
     // FIXME(#11937) we should invoke the system linker directly
-    if sess.targ_cfg.os != abi::OsWin32 {
+    if !sess.targ_cfg.target.options.is_like_windows {
         cmd.arg("-nodefaultlibs");
     }
 
     // If we're building a dylib, we don't use --gc-sections because LLVM has
     // already done the best it can do, and we also don't want to eliminate the
     // metadata. If we're building an executable, however, --gc-sections drops
-    // the size of hello world from 1.8MB to 597K, a 67% reduction.
-    if !dylib && sess.targ_cfg.os != abi::OsMacos && sess.targ_cfg.os != abi::OsiOS {
+    // the size of hello world from 1.8MB to 597K, a 67% reduction. The same
+    // goes for C dynamic libraries, which carry no metadata and only keep the
+    // parts of their statically linked dependencies they export.
+    if crate_type != config::CrateTypeDylib &&
+       !sess.targ_cfg.target.options.is_like_osx {
         cmd.arg("-Wl,--gc-sections");
     }
 
-    if sess.targ_cfg.os == abi::OsLinux {
+    if sess.targ_cfg.target.options.linker_is_gnu {
         // GNU-style linkers will use this to omit linking to libraries which
         // don't actually fulfill any relocations, but only for libraries which
         // follow this flag. Thus, use it before specifying libraries to link to.
@@ -1302,7 +1937,7 @@ fn link_args(cmd: &mut Command,
            sess.opts.optimize == config::Aggressive {
             cmd.arg("-Wl,-O1");
         }
-    } else if sess.targ_cfg.os == abi::OsMacos || sess.targ_cfg.os == abi::OsiOS {
+    } else if sess.targ_cfg.target.options.is_like_osx {
         // The dead_strip option to the linker specifies that functions and data
         // unreachable by the entry point will be removed. This is quite useful
         // with Rust's compilation model of compiling libraries at a time into
@@ -1316,7 +1951,7 @@ fn link_args(cmd: &mut Command,
         cmd.arg("-Wl,-dead_strip");
     }
 
-    if sess.targ_cfg.os == abi::OsWin32 {
+    if sess.targ_cfg.target.options.is_like_windows {
         // Make sure that we link to the dynamic libgcc, otherwise cross-module
         // DWARF stack unwinding will not work.
         // This behavior may be overridden by --link-args "-static-libgcc"
@@ -1350,12 +1985,6 @@ fn link_args(cmd: &mut Command,
         cmd.arg("-Wl,--enable-long-section-names");
     }
 
-    if sess.targ_cfg.os == abi::OsAndroid {
-        // Many of the symbols defined in compiler-rt are also defined in libgcc.
-        // Android linker doesn't like that by default.
-        cmd.arg("-Wl,--allow-multiple-definition");
-    }
-
     // Take careful note of the ordering of the arguments we pass to the linker
     // here. Linkers will assume that things on the left depend on things to the
     // right. Things on the right cannot depend on things on the left. This is
@@ -1389,15 +2018,32 @@ fn link_args(cmd: &mut Command,
     // this kind of behavior is pretty platform specific and generally not
     // recommended anyway, so I don't think we're shooting ourself in the foot
     // much with that.
-    add_upstream_rust_crates(cmd, sess, dylib, tmpdir, trans);
+    add_upstream_rust_crates(cmd, sess, crate_type, tmpdir, trans);
     add_local_native_libraries(cmd, sess);
     add_upstream_native_libraries(cmd, sess);
 
+    // The gcov profiling code in this crate and any upstream ones writes its
+    // counts through compiler-rt's profile runtime, which has to come after
+    // all of them.
+    if sess.profile() {
+        if !sess.target_filesearch().get_lib_path().join("librustc_profile.a").exists() {
+            sess.fatal("the runtime for -Z profile was not built; configure \
+                        with --enable-profiler");
+        }
+        cmd.arg("-lrustc_profile");
+    }
+
+    // Dynamic libraries only export the symbols computed during translation,
+    // which keeps everything else out of their dynamic symbol table.
+    if dylib {
+        add_exported_symbols(cmd, sess, crate_type, tmpdir, trans);
+    }
+
     // # Telling the linker what we're doing
 
     if dylib {
         // On mac we need to tell the linker to let this library be rpathed
-        if sess.targ_cfg.os == abi::OsMacos {
+        if sess.targ_cfg.target.options.is_like_osx {
             cmd.args(["-dynamiclib", "-Wl,-dylib"]);
 
             if sess.opts.cg.rpath {
@@ -1410,12 +2056,6 @@ fn link_args(cmd: &mut Command,
         }
     }
 
-    if sess.targ_cfg.os == abi::OsFreebsd {
-        cmd.args(["-L/usr/local/lib",
-                  "-L/usr/local/lib/gcc46",
-                  "-L/usr/local/lib/gcc44"]);
-    }
-
     // FIXME (#2397): At some point we want to rpath our guesses as to
     // where extern libraries might live, based on the
     // addl_lib_search_paths
@@ -1431,7 +2071,8 @@ fn link_args(cmd: &mut Command,
             path
         };
         let rpath_config = RPathConfig {
-            os: sess.targ_cfg.os,
+            has_rpath: sess.targ_cfg.target.options.has_rpath,
+            is_like_osx: sess.targ_cfg.target.options.is_like_osx,
             used_crates: sess.cstore.get_used_crates(cstore::RequireDynamic),
             out_filename: out_filename.clone(),
             get_install_prefix_lib_path: get_install_prefix_lib_path,
@@ -1482,7 +2123,7 @@ fn add_local_native_libraries(cmd: &mut Command, sess: &Session) {
     // For those that support this, we ensure we pass the option if the library
     // was flagged "static" (most defaults are dynamic) to ensure that if
     // libfoo.a and libfoo.so both exist that the right one is chosen.
-    let takes_hints = sess.targ_cfg.os != abi::OsMacos && sess.targ_cfg.os != abi::OsiOS;
+    let takes_hints = !sess.targ_cfg.target.options.is_like_osx;
 
     for &(ref l, kind) in sess.cstore.get_used_libraries().borrow().iter() {
         match kind {
@@ -1507,13 +2148,78 @@ fn add_local_native_libraries(cmd: &mut Command, sess: &Session) {
     }
 }
 
+// Restrict the dynamic symbol table of a dynamic library to the symbols it
+// exports. GNU-style linkers take a version script listing them and the Darwin
+// linker a plain list of symbols.
+//
+// A C dynamic library hides everything else. A Rust dynamic library only
+// hides the other Rust symbols, as downstream crates may still refer to the
+// native libraries linked into it. On Darwin, where the list is exhaustive,
+// this is done with patterns matching every symbol which isn't mangled.
+//
+// FIXME: on windows every symbol is still exported, this needs a .def file.
+fn add_exported_symbols(cmd: &mut Command, sess: &Session,
+                        crate_type: config::CrateType, tmpdir: &Path,
+                        trans: &CrateTranslation) {
+    let symbols = match trans.exported_symbols.find(&crate_type) {
+        Some(symbols) => symbols,
+        None => return,
+    };
+    let hide_all = crate_type == config::CrateTypeCdylib;
+
+    let options = &sess.targ_cfg.target.options;
+    if options.is_like_windows {
+        return
+    }
+    let (path, contents, flag) = if options.is_like_osx {
+        let mut contents = String::new();
+        for sym in symbols.iter() {
+            contents.push_str(format!("_{}\n", sym).as_slice());
+        }
+        if !hide_all {
+            contents.push_str("_[A-Za-z0-9$.]*\n\
+                               __[A-Ya-z0-9$._]*\n\
+                               __Z[A-MO-Za-z0-9$._]*\n");
+        }
+        (tmpdir.join("exported_symbols.list"), contents,
+         "-Wl,-exported_symbols_list,")
+    } else {
+        let mut contents = "{\n".to_string();
+        if !symbols.is_empty() {
+            contents.push_str("  global:\n");
+            for sym in symbols.iter() {
+                contents.push_str(format!("    {};\n", sym).as_slice());
+            }
+        }
+        contents.push_str(if hide_all {
+            "  local:\n    *;\n};\n"
+        } else {
+            "  local:\n    _ZN*;\n};\n"
+        });
+        (tmpdir.join("version_script.map"), contents,
+         "-Wl,--version-script=")
+    };
+
+    match File::create(&path).write_str(contents.as_slice()) {
+        Ok(()) => {}
+        Err(e) => {
+            sess.fatal(format!("failed to write {}: {}",
+                               path.display(), e).as_slice());
+        }
+    }
+
+    let mut v = flag.as_bytes().to_vec();
+    v.push_all(path.as_vec());
+    cmd.arg(v.as_slice());
+}
+
 // # Rust Crate linking
 //
 // Rust crates are not considered at all when creating an rlib output. All
 // dependencies will be linked when producing the final output (instead of
 // the intermediate rlib version)
 fn add_upstream_rust_crates(cmd: &mut Command, sess: &Session,
-                            dylib: bool, tmpdir: &Path,
+                            crate_type: config::CrateType, tmpdir: &Path,
                             trans: &CrateTranslation) {
     // All of the heavy lifting has previously been accomplished by the
     // dependency_format module of the compiler. This is just crawling the
@@ -1523,11 +2229,7 @@ fn add_upstream_rust_crates(cmd: &mut Command, sess: &Session,
     // will slurp up the object files inside), and linking to a dynamic library
     // involves just passing the right -l flag.
 
-    let data = if dylib {
-        trans.crate_formats.get(&config::CrateTypeDylib)
-    } else {
-        trans.crate_formats.get(&config::CrateTypeExecutable)
-    };
+    let data = trans.crate_formats.get(&crate_type);
 
     // Invoke get_used_crates to ensure that we get a topological sorting of
     // crates.
@@ -1555,7 +2257,7 @@ fn add_upstream_rust_crates(cmd: &mut Command, sess: &Session,
 
     // Converts a library file-stem into a cc -l argument
     fn unlib<'a>(config: &config::Config, stem: &'a [u8]) -> &'a [u8] {
-        if stem.starts_with("lib".as_bytes()) && config.os != abi::OsWin32 {
+        if stem.starts_with("lib".as_bytes()) && !config.target.options.is_like_windows {
             stem.tailn(3)
         } else {
             stem
@@ -1600,7 +2302,9 @@ fn add_upstream_rust_crates(cmd: &mut Command, sess: &Session,
                     handler: handler,
                     dst: dst.clone(),
                     lib_search_paths: archive_search_paths(sess),
-                    os: sess.targ_cfg.os,
+                    slib_prefix: sess.targ_cfg.target.options.staticlib_prefix.clone(),
+                    slib_suffix: sess.targ_cfg.target.options.staticlib_suffix.clone(),
+                    is_like_osx: sess.targ_cfg.target.options.is_like_osx,
                     maybe_ar_prog: sess.opts.cg.ar.clone()
                 };
                 let mut archive = Archive::open(config);
@@ -24,17 +24,19 @@ pub fn run(sess: &session::Session, llmod: ModuleRef,
            tm: TargetMachineRef, reachable: &[String]) {
     if sess.opts.cg.prefer_dynamic {
         sess.err("cannot prefer dynamic linking when performing LTO");
-        sess.note("only 'staticlib' and 'bin' outputs are supported with LTO");
+        sess.note("only 'staticlib', 'cdylib' and 'bin' outputs are supported \
+                   with LTO");
         sess.abort_if_errors();
     }
 
     // Make sure we actually can run LTO
     for crate_type in sess.crate_types.borrow().iter() {
         match *crate_type {
-            config::CrateTypeExecutable | config::CrateTypeStaticlib => {}
+            config::CrateTypeExecutable | config::CrateTypeStaticlib |
+            config::CrateTypeCdylib => {}
             _ => {
-                sess.fatal("lto can only be run for executables and \
-                            static library outputs");
+                sess.fatal("lto can only be run for executables, C dynamic \
+                            libraries and static library outputs");
             }
         }
     }
@@ -17,98 +17,840 @@ register_diagnostic!(E0001, r##"
     one is too specific or the ordering is incorrect.
 "##)
 
+register_diagnostic!(E0002, r##"
+    This error indicates that an empty match expression is illegal because the
+    type it is matching on is non-empty (there exist values of this type). In
+    safe code it is impossible to create an instance of an empty type, so empty
+    match expressions are almost never desired. This error is typically fixed by
+    adding one or more cases to the match expression.
+
+    An example of an empty type is `enum Empty { }`.
+"##)
+
+register_diagnostic!(E0003, r##"
+    Not-a-Number (NaN) values can not be compared for equality and hence can
+    never match the input to a match expression. To match against NaN values,
+    you should instead use the `is_nan` method in a guard, as in:
+
+        match x {
+            y if y.is_nan() => { ... }
+            _ => { ... }
+        }
+"##)
+
+register_diagnostic!(E0004, r##"
+    This error indicates that the compiler can not guarantee a matching pattern
+    for one or more possible inputs to a match expression. Guaranteed matches are
+    required in order to assign values to match expressions, or alternatively,
+    determine the flow of execution.
+
+    If you encounter this error you must alter your patterns so that every
+    possible value of the input type is matched. For types with a small number
+    of variants (like enums) you should probably cover all cases explicitly.
+    Alternatively, the underscore `_` wildcard pattern can be added after all
+    other patterns to match "anything else".
+"##)
+
+register_diagnostic!(E0005, r##"
+    Patterns used to bind names in `let` statements and `for` loops must be
+    irrefutable, that is, they must guarantee that a name will be extracted in
+    all cases. If you encounter this error you probably need to use a `match`
+    or `if let` style construct instead, to handle the cases the pattern does
+    not cover:
+
+        let Some(x) = opt;          // error: `None` not covered
+
+        match opt {
+            Some(x) => { ... }
+            None => { ... }
+        }
+"##)
+
+register_diagnostic!(E0006, r##"
+    Patterns used to bind names to function arguments must be irrefutable, that
+    is, they must match every possible value of the argument's type. Refutable
+    patterns, such as enum variants when the enum has more than one variant,
+    are not allowed here; take the argument as a plain binding and `match` on it
+    in the body of the function instead.
+"##)
+
+register_diagnostic!(E0007, r##"
+    This error indicates that the bindings in a match arm would require a value
+    to be moved into more than one location, thus violating unique ownership.
+    Code like the following is invalid as it requires the entire Option<String>
+    to be moved into a variable called `op_string` while simultaneously requiring
+    the inner String to be moved into a variable called `s`:
+
+        let x = Some("s".to_string());
+        match x {
+            op_string @ Some(s) => ...
+            None => ...
+        }
+"##)
+
+register_diagnostic!(E0008, r##"
+    Names bound in match arms retain their type in pattern guards. As such, if a
+    name is bound by move in a pattern, it should also be moved to wherever it is
+    referenced in the pattern guard code. Doing so however would prevent the name
+    from being available in the body of the match arm. Consider the following:
+
+        match Some("hi".to_string()) {
+            Some(s) if s.len() == 0 => // use s.
+            ...
+        }
+
+    The variable `s` has type String, and its use in the guard is as a variable
+    of type String. The guard code effectively executes in a separate scope to
+    the body of the arm, so the value would be moved into this anonymous scope
+    and therefore become unavailable in the body of the arm. Although this
+    example seems innocuous, the problem is most clear when considering
+    functions that take their argument by value. Binding the name by reference
+    instead, with `Some(ref s)`, fixes the error.
+"##)
+
+register_diagnostic!(E0009, r##"
+    In a pattern, all values that don't implement the `Copy` trait have to be
+    bound the same way. The goal here is to avoid binding simultaneously by-move
+    and by-ref.
+
+    This limitation may be removed in a future version of Rust.
+
+    Wrong example:
+
+        struct X { x: (), }
+
+        let x = Some((X { x: () }, X { x: () }));
+        match x {
+            Some((y, ref z)) => {},
+            None => fail!()
+        }
+
+    You have two solutions: bind the pattern's values the same way, by-move or
+    by-ref (`Some((ref y, ref z))`), or implement the `Copy` trait on the type.
+"##)
+
+register_diagnostic!(E0010, r##"
+    The value of statics and constants must be known at compile time, and they
+    live for the entire lifetime of a program. Creating a boxed value allocates
+    memory on the heap at runtime, and therefore cannot be done at compile time.
+    Use a plain value, or a reference to a value which is itself constant.
+"##)
+
+register_diagnostic!(E0011, r##"
+    Initializers for constants and statics are evaluated at compile time.
+    User-defined operators rely on user-defined functions, which cannot be
+    evaluated at compile time. Only the built-in operators on primitive types
+    may be used:
+
+        struct Foo { a: u8 }
+        impl Add<Foo, Foo> for Foo { ... }
+
+        static A: Foo = Foo { a: 1 } + Foo { a: 2 }; // error
+"##)
+
+register_diagnostic!(E0012, r##"
+    Only casts between primitive numeric types (and a few other casts which can
+    be evaluated at compile time) are allowed in constant expressions. The type
+    named in the error cannot be the target of a cast in a constant; compute the
+    value in a function at runtime instead.
+"##)
+
+register_diagnostic!(E0013, r##"
+    Paths in constant expressions may only refer to items whose value is fully
+    known at compile time. An item with type parameters, such as a generic
+    function, does not have a single value until it is instantiated, so it
+    cannot be referred to from a constant.
+"##)
+
+register_diagnostic!(E0014, r##"
+    Constants can only be initialized by a constant value or, in a future
+    version of Rust, a call to a const function. This error indicates the use
+    of a path (like a reference to a local variable or a static mutable item)
+    which is not a constant or function:
+
+        static FOO: int = 0;
+        static BAR: int = FOO; // ok, FOO is a constant
+
+        fn main() {
+            let x = 1;
+            static BAZ: int = x; // error, `x` is a local variable
+        }
+"##)
+
+register_diagnostic!(E0015, r##"
+    The only function calls allowed in static or constant expressions are
+    calls to struct and enum constructors, which are evaluated at compile time.
+    Calls to any other function would have to run at runtime, so they are not
+    allowed. Compute the value at runtime, or write it out as a literal.
+"##)
+
+register_diagnostic!(E0016, r##"
+    Blocks in constants may contain only items (such as constant declarations)
+    and a tail expression which gives the block its value. Statements such as
+    `let` bindings or expression statements would need to be executed at
+    runtime, so they are not allowed:
+
+        static FOO: int = { let x = 0; x }; // error
+        static BAR: int = { static X: int = 0; X }; // ok
+"##)
+
+register_diagnostic!(E0017, r##"
+    References in statics and constants may only refer to immutable values.
+    Taking a `&mut` reference, or a reference to a `static mut`, in a constant
+    expression would allow the referenced value to be mutated through the
+    constant, which is not allowed.
+"##)
+
+register_diagnostic!(E0018, r##"
+    Owned vectors, such as `box [1, 2, 3]` or `vec!(1, 2, 3)`, allocate their
+    contents on the heap at runtime, and therefore cannot be created in a
+    constant expression. Use a fixed-size array, or a slice of one, instead:
+
+        static V: &'static [int] = &[1, 2, 3];
+"##)
+
+register_diagnostic!(E0019, r##"
+    A static or constant contains an expression which cannot be evaluated at
+    compile time, such as a method call, a loop or a match expression. Constant
+    expressions are limited to literals, paths to other constants, built-in
+    operators, casts, struct and enum constructors, tuples, arrays and
+    references.
+"##)
+
+register_diagnostic!(E0020, r##"
+    Static items cannot contain mutable slices (`&mut [T]`): a mutable slice in
+    a static could be used to mutate its contents from any part of the program.
+    Use an immutable slice, or a `static mut` array accessed in `unsafe` code.
+"##)
+
+register_diagnostic!(E0021, r##"
+    Static items cannot contain managed pointers (`@T`). Managed boxes are
+    allocated at runtime and need to be tracked by the task that created them,
+    which a static, shared by the whole program, cannot provide.
+"##)
+
+register_diagnostic!(E0022, r##"
+    Static items cannot contain heap-allocated pointers such as `box` values,
+    since these are allocated at runtime and a static's value must be known at
+    compile time. Store the value directly in the static instead, or allocate it
+    at runtime.
+"##)
+
+register_diagnostic!(E0023, r##"
+    A pattern used to match against an enum variant or a tuple struct must
+    provide a sub-pattern for each field of the variant or struct, in order.
+    For example:
+
+        enum Fruit {
+            Apple(String, String),
+            Pear(u32)
+        }
+
+        match x {
+            Apple(a) => {}, // error: this pattern has 1 field, but the
+                            // corresponding variant has 2 fields
+            Apple(a, b) => {}, // ok
+            Pear(..) => {}, // ok, `..` matches any number of fields
+        }
+"##)
+
+register_diagnostic!(E0024, r##"
+    A pattern for an enum variant or struct which has no fields must not
+    provide any sub-patterns. For example, with `enum Number { Zero, One(u32) }`
+    the pattern `Zero(x)` is an error; use `Zero` instead.
+"##)
+
+register_diagnostic!(E0025, r##"
+    Each field of a struct can only be bound once in a pattern. Remove the
+    duplicate binding, or bind the field's sub-parts in a single nested pattern:
+
+        struct Foo { a: u8, b: u8 }
+
+        let Foo { a: x, a: y, .. } = foo; // error: field `a` bound twice
+"##)
+
+register_diagnostic!(E0026, r##"
+    This error indicates that a struct pattern attempted to extract a non-
+    existent field from a struct. Struct fields are identified by the name used
+    before the colon `:` so struct patterns should resemble the declaration of
+    the struct type being matched:
+
+        struct Thing { x: u32, y: u32 }
+
+        let thing = Thing { x: 1, y: 2 };
+        match thing {
+            Thing { x: xfield, y: yfield } => ...
+        }
+
+    If you are using shorthand field patterns but want to refer to the struct
+    field by a different name, you should rename it explicitly:
+
+        match thing {
+            Thing { x, z } => ... // error: no field `z`
+            Thing { x, y: z } => ... // ok
+        }
+"##)
+
+register_diagnostic!(E0027, r##"
+    This error indicates that a pattern for a struct fails to specify a sub-
+    pattern for every one of the struct's fields. Ensure that each field from
+    the struct's definition is mentioned in the pattern, or use `..` to ignore
+    unwanted fields:
+
+        struct Dog { name: String, age: u32 }
+
+        match d {
+            Dog { age: x } => ... // error: pattern does not mention `name`
+            Dog { age: x, .. } => ... // ok
+        }
+"##)
+
+register_diagnostic!(E0028, r##"
+    A struct pattern names a struct or enum variant whose type is not the type
+    of the value being matched. Struct patterns in a match must all name either
+    the struct being matched on, or variants of the enum being matched on.
+"##)
+
+register_diagnostic!(E0029, r##"
+    In a match expression, only numbers and characters can be matched against a
+    range. This is because the compiler checks that the range is non-empty at
+    compile-time, and is unable to evaluate arbitrary comparison functions. If
+    you want to capture values of an orderable type between two end-points, you
+    can use a guard:
+
+        match string {
+            "hello" ... "world" => ... // error
+            s if s >= "hello" && s <= "world" => ... // ok
+        }
+"##)
+
+register_diagnostic!(E0030, r##"
+    When matching against a range, the compiler verifies that the range is
+    non-empty. Range patterns include both end-points, so this is equivalent to
+    requiring the start of the range to be less than or equal to the end of the
+    range:
+
+        match 5u {
+            1 ... 1 => ... // ok, this range contains one value
+            1000 ... 5 => ... // error, this range is empty
+        }
+"##)
+
+register_diagnostic!(E0031, r##"
+    The two end-points of a range pattern must have the same type, and that type
+    must be the type of the value being matched: `1u ... 'z'` is an error.
+"##)
+
+register_diagnostic!(E0032, r##"
+    The path in a struct pattern does not name the struct or enum being
+    matched on. Check that the pattern refers to the type of the value being
+    matched, and not to some other type of the same shape.
+"##)
+
+register_diagnostic!(E0033, r##"
+    This error indicates that a pointer to a trait type cannot be implicitly
+    dereferenced by a pattern, or that the value being matched is not a pointer
+    at all. Every trait defines a type, but because the size of trait objects
+    isn't known at compile time, they can only be accessed through pointers:
+
+        let trait_obj: &SomeTrait = ...;
+        let &invalid = trait_obj; // error
+        let x = 5i;
+        let &y = x; // error, `int` is not a pointer
+
+    A `&` or `box` pattern can only be used on a value of a matching pointer
+    type, whose pointee has a statically known size.
+"##)
+
+register_diagnostic!(E0034, r##"
+    The method called is provided by more than one trait in scope, or by an
+    impl and a trait, and the compiler cannot tell which one was meant. Call the
+    method through the trait it comes from to disambiguate, for example
+    `Trait::method(&x)`, or remove one of the conflicting imports.
+"##)
+
+register_diagnostic!(E0035, r##"
+    Explicit type parameters were given to a method which is not generic, as in
+    `x.len::<int>()`. Remove the type parameters.
+"##)
+
+register_diagnostic!(E0036, r##"
+    A method was called with a different number of explicit type parameters
+    than it declares. Either supply exactly as many type parameters as the
+    method declares, or leave them all out and let them be inferred.
+"##)
+
+register_diagnostic!(E0038, r##"
+    Trait objects like `Box<Trait>` can only be used to call methods whose
+    signature does not mention `Self` other than as the receiver. The concrete
+    type behind a trait object is not known, so the compiler cannot check that
+    an argument of type `Self` matches it, nor say what type a returned `Self`
+    has. Such methods must be called on a value of a concrete type instead.
+"##)
+
+register_diagnostic!(E0039, r##"
+    Trait objects cannot be used to call generic methods. A generic method is
+    instantiated separately for each set of type parameters it is used with,
+    while a trait object only carries a single vtable entry per method. Call the
+    method on a value of a concrete type, or move the type parameter to the
+    trait itself.
+"##)
+
+register_diagnostic!(E0040, r##"
+    It is not allowed to manually call destructors in Rust, as in `x.drop()`.
+    The destructor of a value runs automatically when it goes out of scope. To
+    destroy a value early, use `std::mem::drop`, which takes the value by move:
+
+        drop(x);
+"##)
+
+register_diagnostic!(E0041, r##"
+    A struct which inherits from another struct may not declare a field with
+    the same name as a field of one of its super-structs. Rename the field.
+"##)
+
+register_diagnostic!(E0042, r##"
+    Dynamically sized types, such as `str`, `[T]` or a trait, may only appear
+    as the type of the last field of a struct: the offset of every other field
+    has to be known at compile time. Move the field to the end of the struct,
+    or store a pointer to the dynamically sized value instead.
+"##)
+
+register_diagnostic!(E0043, r##"
+    A dynamically sized type, such as `str`, `[T]` or a trait, was used as the
+    type of a field of a tuple struct other than the last one. The offset of
+    every field except the last must be known at compile time, so dynamically
+    sized types may only appear as the final field.
+"##)
+
+register_diagnostic!(E0044, r##"
+    Items declared in an `extern` block are implemented outside of Rust, and so
+    cannot be generic: there is no way to instantiate a foreign function for
+    each set of type parameters. Declare one foreign function per concrete type
+    instead.
+"##)
+
+register_diagnostic!(E0045, r##"
+    Variadic parameters are only allowed in foreign functions with the C calling
+    convention, since other ABIs do not define how a variable number of
+    arguments is passed:
+
+        extern "C" { fn printf(fmt: *const u8, ...); } // ok
+        extern "stdcall" { fn foo(x: u8, ...); } // error
+"##)
+
+register_diagnostic!(E0046, r##"
+    When trying to make some type implement a trait `Foo`, you must, at minimum,
+    provide implementations for all of `Foo`'s required methods (meaning the
+    methods that do not have default implementations), as well as any required
+    trait items. Add the missing methods listed in the error to the impl.
+"##)
+
+register_diagnostic!(E0049, r##"
+    A method in a trait impl has a different number of type parameters than the
+    corresponding method in the trait declaration. Each method of an impl must
+    declare exactly as many type parameters as the trait's version of the
+    method.
+"##)
+
+register_diagnostic!(E0050, r##"
+    A method in a trait impl takes a different number of parameters than the
+    corresponding method in the trait declaration. The signatures must match;
+    if the implementation does not use a parameter, name it `_`.
+"##)
+
+register_diagnostic!(E0051, r##"
+    A type parameter of a method in an impl requires a built-in bound, such as
+    `Copy`, `Send` or `Sized`, which the corresponding type parameter of the
+    trait's method does not require. An implementation cannot be more
+    restrictive than the trait it implements, since callers only know about the
+    trait's bounds. Remove the bound, or add it to the trait declaration.
+"##)
+
+register_diagnostic!(E0052, r##"
+    A type parameter of a method in an impl is bounded by a trait which the
+    corresponding type parameter of the trait's method is not bounded by. An
+    implementation cannot be more restrictive than the trait it implements,
+    since callers only know about the trait's bounds. Remove the bound, or add
+    it to the trait declaration.
+"##)
+
+register_diagnostic!(E0053, r##"
+    The type of a method in an impl does not match the type of the method in
+    the trait being implemented: the parameter types, return type or kind of
+    `self` differ. The error message details which part of the signature is
+    incompatible.
+"##)
+
+register_diagnostic!(E0054, r##"
+    It is not allowed to cast to a bool. If you are trying to cast a numeric
+    type to a bool, you can compare it with zero instead:
+
+        let x = 5i;
+        let x_is_nonzero = x as bool; // error
+        let x_is_nonzero = x != 0; // ok
+"##)
+
+register_diagnostic!(E0055, r##"
+    During a method call, a value is automatically dereferenced as many times
+    as needed to make the value's type match the method's receiver. This error
+    is reported when that automatic dereferencing reaches the recursion limit,
+    which usually means that the `Deref` implementations of the types involved
+    form a cycle. The limit can be raised with the `#![recursion_limit]`
+    crate attribute.
+"##)
+
+register_diagnostic!(E0056, r##"
+    Calling a value that is not a function, through the `Fn` family of traits,
+    is an experimental feature. Add `#![feature(overloaded_calls)]` to the crate
+    attributes to enable it.
+"##)
+
+register_diagnostic!(E0057, r##"
+    A value implementing one of the `Fn` traits was called with a different
+    number of arguments than its argument tuple type has elements. Pass one
+    argument per element of the tuple.
+"##)
+
+register_diagnostic!(E0058, r##"
+    A value implementing one of the `Fn` traits with `()` as its argument type
+    takes no arguments, but was called with some.
+"##)
+
+register_diagnostic!(E0059, r##"
+    The first type parameter of the `Fn` traits gives the types of the
+    arguments of a call, and must therefore be a tuple or `()`. A value whose
+    `Fn` implementation uses any other argument type cannot be called with the
+    call notation `f(...)`.
+"##)
+
+register_diagnostic!(E0060, r##"
+    External C functions are allowed to be variadic. However, a variadic
+    function takes a minimum number of arguments. For example, consider C's
+    variadic `printf` function:
+
+        extern { fn printf(fmt: *const u8, ...) -> libc::c_int; }
+
+    Using this declaration, it must be called with at least one argument, so
+    simply calling `printf()` is illegal. But the following uses are allowed:
+
+        printf(fmt);
+        printf(fmt, 1u);
+"##)
+
+register_diagnostic!(E0061, r##"
+    The number of arguments passed to a function must match the number of
+    parameters specified in the function signature. For example, a function
+    like
+
+        fn f(a: u16, b: &str) {}
+
+    must always be called with exactly two arguments, e.g. `f(2, "test")`.
+"##)
+
+register_diagnostic!(E0062, r##"
+    This error indicates that during an attempt to build a struct or struct-like
+    enum variant, one of the fields was specified more than once. Each field
+    should be specified exactly one time:
+
+        struct Foo { x: int }
+
+        let x = Foo { x: 0, x: 0 }; // error
+"##)
+
+register_diagnostic!(E0063, r##"
+    This error indicates that during an attempt to build a struct or struct-like
+    enum variant, one of the fields was not provided. Each field should be
+    specified exactly once, unless the struct expression uses the functional
+    record update syntax `..base` to take the remaining fields from another
+    value:
+
+        struct Foo { x: int, y: int }
+
+        let a = Foo { x: 0 }; // error: missing field `y`
+        let b = Foo { x: 0, ..a }; // ok
+"##)
+
+register_diagnostic!(E0064, r##"
+    The `&"string"` syntax for string slices has been removed, since a string
+    literal already has type `&'static str`. Write `"string"` instead.
+"##)
+
+register_diagnostic!(E0065, r##"
+    The `box "string"` syntax for owned strings has been removed. Write
+    `"string".to_string()` to create a `String` instead.
+"##)
+
+register_diagnostic!(E0066, r##"
+    Box placement expressions, `box (PLACE) EXPR`, currently only support the
+    exchange heap (`box (HEAP) x`, which is the same as `box x`) and the
+    managed heap (`box (GC) x`) as the place.
+"##)
+
+register_diagnostic!(E0067, r##"
+    The left-hand side of a compound assignment expression such as `+=` must be
+    an lvalue expression: a local variable, a field, an index or a dereference.
+    Something like `12 += 1` or `f() += 1` is an error.
+"##)
+
+register_diagnostic!(E0068, r##"
+    Single-field tuple structs (newtypes) used to be dereferenced with `*` to
+    get at their field. This has been removed; match on the struct, or use
+    tuple-struct destructuring, to get at the field instead:
+
+        struct Meters(int);
+
+        let m = Meters(3);
+        let Meters(n) = m;
+"##)
+
+register_diagnostic!(E0069, r##"
+    The compiler found a function whose body contains a `return;` statement but
+    whose return type is not `()`. A bare `return;` returns `()`, so it is only
+    allowed in functions returning `()`. Return a value of the function's return
+    type instead:
+
+        fn foo() -> u8 {
+            return; // error
+        }
+"##)
+
+register_diagnostic!(E0070, r##"
+    The left-hand side of an assignment operator must be an lvalue expression:
+    a local variable, a field, an index or a dereference. Assigning to anything
+    else, such as a literal or the result of a function call, is an error:
+
+        1 = 3; // error
+        some_func() = 4; // error
+"##)
+
+register_diagnostic!(E0071, r##"
+    The path used in a struct literal does not name a struct or a struct-like
+    enum variant. Struct literal syntax, `Foo { field: value }`, can only be
+    used to construct structs and enum variants with named fields.
+"##)
+
+register_diagnostic!(E0072, r##"
+    When defining a recursive struct or enum, any use of the type being defined
+    from inside the definition must occur behind a pointer (like `Box` or `&`).
+    This is because structs and enums must have a well-defined size, and without
+    the pointer the size of the type would need to be unbounded:
+
+        struct ListNode {
+            head: u8,
+            tail: Option<ListNode>, // error
+        }
+
+        struct ListNode {
+            head: u8,
+            tail: Option<Box<ListNode>>, // ok
+        }
+"##)
+
+register_diagnostic!(E0073, r##"
+    You cannot define a struct (or enum) `Foo` that requires an instance of
+    `Foo` in order to make a new `Foo` value. This is because there would be no
+    way to create the first instance of `Foo`, as every instance would require
+    an existing one. Make the self-reference optional, for example by wrapping
+    it in an `Option`:
+
+        struct Foo { x: Box<Foo> } // error
+        struct Foo { x: Option<Box<Foo>> } // ok
+"##)
+
+register_diagnostic!(E0074, r##"
+    When using the `#[simd]` attribute on a tuple struct, the components of the
+    tuple struct must all be of a concrete, nongeneric type so the compiler can
+    reason about how to use SIMD with them. This error will occur if the types
+    are generic:
+
+        #[simd]
+        struct Bad<T>(T, T, T); // error
+"##)
+
+register_diagnostic!(E0075, r##"
+    The `#[simd]` attribute can only be applied to non-empty tuple structs,
+    because it doesn't make sense to try to use SIMD operations when there are
+    no values to operate on:
+
+        #[simd]
+        struct Bad; // error
+"##)
+
+register_diagnostic!(E0076, r##"
+    When using the `#[simd]` attribute to automatically use SIMD operations in a
+    tuple struct, the types in the struct must all be of the same type, or the
+    compiler will trigger this error:
+
+        #[simd]
+        struct Bad(u16, u32, u32); // error
+        #[simd]
+        struct Good(u32, u32, u32); // ok
+"##)
+
+register_diagnostic!(E0077, r##"
+    When using the `#[simd]` attribute on a tuple struct, the elements in the
+    tuple must be machine types, that is, primitive integers or floats, so SIMD
+    operations can be applied to them:
+
+        #[simd]
+        struct Bad(String); // error
+"##)
+
+register_diagnostic!(E0078, r##"
+    Dynamically sized types, such as `str`, `[T]` or a trait, may only appear
+    as the type of the final field of an enum variant: the offset of every other
+    field has to be known at compile time. Move the field to the end of the
+    variant, or store a pointer to the dynamically sized value instead.
+"##)
+
+register_diagnostic!(E0079, r##"
+    The discriminant of a variant of an enum with a signed integer
+    representation, such as `#[repr(i8)]`, must be a constant of a signed
+    integer type.
+"##)
+
+register_diagnostic!(E0080, r##"
+    The value given for an enum discriminant could not be evaluated as a
+    constant integer. Enum discriminants must be constant expressions of an
+    integer type, such as literals, constants, and arithmetic on them:
+
+        enum Enum {
+            X = (1 << 500), // error: overflowing shift
+            Y = (1 / 0) // error: division by zero
+        }
+"##)
+
+register_diagnostic!(E0081, r##"
+    Enum discriminants are used to differentiate enum variants stored in memory.
+    This error indicates that the same value was used for two or more variants,
+    making them impossible to tell apart:
+
+        enum Enum {
+            P = 3,
+            X = 3, // error
+            Y = 5
+        }
+
+    Note that variants without a manually specified discriminant are numbered
+    from the previous variant's discriminant plus one.
+"##)
+
+register_diagnostic!(E0082, r##"
+    The discriminant of an enum variant does not fit in the integer type given
+    by the enum's `#[repr]` attribute:
+
+        #[repr(u8)]
+        enum Thing {
+            A = 1024, // error: 1024 does not fit in a u8
+            B = 5
+        }
+
+    Use a representation type large enough for all discriminants, or change the
+    discriminant values.
+"##)
+
+register_diagnostic!(E0083, r##"
+    The representation requested with `#[repr]` is not supported for an enum
+    with a single variant. Remove the attribute, or use `#[repr(C)]`.
+"##)
+
+register_diagnostic!(E0084, r##"
+    An unsupported representation was requested with `#[repr]` on an enum with
+    no variants. Such an enum has no values, so there is no discriminant whose
+    representation could be chosen:
+
+        #[repr(i32)]
+        enum NightWatch {} // error
+"##)
+
+register_diagnostic!(E0085, r##"
+    Type parameters were given on a segment of a path which does not accept
+    them, for example on a module name as in `mymod::<int>::foo()`. Type
+    parameters may only be given on the segments naming a generic type or
+    function.
+"##)
+
+register_diagnostic!(E0086, r##"
+    Lifetime parameters were given on a segment of a path which does not accept
+    them, for example on a module name. Lifetime parameters may only be given on
+    the segments naming a type or function with lifetime parameters.
+"##)
+
+register_diagnostic!(E0087, r##"
+    Too many type parameters were supplied for a function. For example:
+
+        fn foo<T>() {}
+
+        fn main() {
+            foo::<bool, i32>(); // error: expected 1 parameter, found 2
+        }
+
+    The number of supplied parameters must exactly match the number of defined
+    type parameters, minus those which have defaults.
+"##)
+
+register_diagnostic!(E0088, r##"
+    More lifetime parameters were supplied for a path than the item it names
+    declares. Supply at most as many lifetime parameters as the definition has,
+    or leave them out to have them inferred.
+"##)
+
+register_diagnostic!(E0089, r##"
+    Not enough type parameters were supplied for a function. For example:
+
+        fn foo<T, U>() {}
+
+        fn main() {
+            foo::<f64>(); // error: expected 2 parameters, found 1
+        }
+
+    Note that if a function takes multiple type parameters but you want the
+    compiler to infer some of them, you can use type placeholders:
+
+        foo::<f64, _>(); // ok
+"##)
+
+register_diagnostic!(E0090, r##"
+    Fewer lifetime parameters were supplied for a path than the item it names
+    declares. Either supply all of the lifetime parameters, or none of them and
+    let them be inferred.
+"##)
+
+register_diagnostic!(E0091, r##"
+    A type alias declared a type parameter which its definition does not use.
+    Unused type parameters in a type alias have no effect, so they are not
+    allowed:
+
+        type Foo<T> = u32; // error: type parameter `T` is unused
+        type Foo<T> = Box<T>; // ok
+"##)
+
+register_diagnostic!(E0092, r##"
+    An `extern "rust-intrinsic"` function named `atomic_*` does not correspond
+    to any atomic operation the compiler knows about. Check the spelling of the
+    operation and its ordering suffix, such as `atomic_xadd_relaxed`.
+"##)
+
+register_diagnostic!(E0093, r##"
+    A function declared in an `extern "rust-intrinsic"` block is not an
+    intrinsic the compiler knows about. Intrinsics are implemented by the
+    compiler itself, so only the names it recognizes may be declared. Check the
+    name against the declarations in `core::intrinsics`.
+"##)
+
+register_diagnostic!(E0094, r##"
+    An intrinsic was declared with a different number of type parameters than
+    the compiler expects for it. Check the declaration against the one in
+    `core::intrinsics`:
+
+        extern "rust-intrinsic" {
+            fn size_of<T, U>() -> uint; // error: expected 1 type parameter
+        }
+"##)
+
 register_diagnostics!(
-    E0002,
-    E0003,
-    E0004,
-    E0005,
-    E0006,
-    E0007,
-    E0008,
-    E0009,
-    E0010,
-    E0011,
-    E0012,
-    E0013,
-    E0014,
-    E0015,
-    E0016,
-    E0017,
-    E0018,
-    E0019,
-    E0020,
-    E0021,
-    E0022,
-    E0023,
-    E0024,
-    E0025,
-    E0026,
-    E0027,
-    E0028,
-    E0029,
-    E0030,
-    E0031,
-    E0032,
-    E0033,
-    E0034,
-    E0035,
-    E0036,
     E0037,
-    E0038,
-    E0039,
-    E0040,
-    E0041,
-    E0042,
-    E0043,
-    E0044,
-    E0045,
-    E0046,
     E0047,
-    E0048,
-    E0049,
-    E0050,
-    E0051,
-    E0052,
-    E0053,
-    E0054,
-    E0055,
-    E0056,
-    E0057,
-    E0058,
-    E0059,
-    E0060,
-    E0061,
-    E0062,
-    E0063,
-    E0064,
-    E0065,
-    E0066,
-    E0067,
-    E0068,
-    E0069,
-    E0070,
-    E0071,
-    E0072,
-    E0073,
-    E0074,
-    E0075,
-    E0076,
-    E0077,
-    E0078,
-    E0079,
-    E0080,
-    E0081,
-    E0082,
-    E0083,
-    E0084,
-    E0085,
-    E0086,
-    E0087,
-    E0088,
-    E0089,
-    E0090,
-    E0091,
-    E0092,
-    E0093,
-    E0094
+    E0048
 )
@@ -17,8 +17,7 @@ use driver::session::Session;
 
 use back;
 use back::link;
-use back::target_strs;
-use back::{arm, x86, x86_64, mips, mipsel};
+use back::target::Target;
 use lint;
 
 use syntax::abi;
@@ -28,6 +27,7 @@ use syntax::attr;
 use syntax::attr::AttrMetaMethods;
 use syntax::diagnostic::{ColorConfig, Auto, Always, Never};
 use syntax::parse;
+use syntax::parse::token;
 use syntax::parse::token::InternedString;
 
 use std::collections::{HashSet, HashMap};
@@ -39,9 +39,8 @@ use std::fmt;
 use llvm;
 
 pub struct Config {
-    pub os: abi::Os,
+    pub target: Target,
     pub arch: abi::Architecture,
-    pub target_strs: target_strs::t,
     pub int_type: IntTy,
     pub uint_type: UintTy,
 }
@@ -61,6 +60,40 @@ pub enum DebugInfoLevel {
     FullDebugInfo,
 }
 
+#[deriving(Clone, PartialEq)]
+pub enum ErrorOutputType {
+    HumanReadable, // --error-format=human
+    Json, // --error-format=json
+}
+
+#[deriving(Clone, PartialEq)]
+pub enum FailStrategy {
+    FailUnwind, // -C fail-strategy=unwind
+    FailAbort, // -C fail-strategy=abort
+}
+
+/// The sanitizers `-Z sanitizer` can instrument a crate for. Only the crates
+/// compiled with the option are instrumented: the standard library shipped
+/// with rustc isn't, so bugs in the memory it manages, such as the contents
+/// of a `Vec` allocated by jemalloc, go unnoticed. The memory sanitizer in
+/// particular reports false positives for memory initialized by
+/// uninstrumented code.
+#[deriving(Clone, PartialEq)]
+pub enum Sanitizer {
+    AddressSanitizer, // -Z sanitizer=address
+    ThreadSanitizer, // -Z sanitizer=thread
+    MemorySanitizer, // -Z sanitizer=memory
+}
+
+#[deriving(Clone, PartialEq)]
+pub enum PrintRequest {
+    PrintCrateName, // --print crate-name
+    PrintFileNames, // --print file-names
+    PrintSysroot, // --print sysroot
+    PrintCfg, // --print cfg
+    PrintTargetList, // --print target-list
+}
+
 #[deriving(Clone)]
 pub struct Options {
     // The crate config requested for the session, which may be combined
@@ -71,6 +104,8 @@ pub struct Options {
     pub optimize: OptLevel,
     pub debuginfo: DebugInfoLevel,
     pub lint_opts: Vec<(String, lint::Level)>,
+    /// The lint policy file given with `--lint-config`.
+    pub lint_config: Option<Path>,
     pub describe_lints: bool,
     pub output_types: Vec<back::link::OutputType> ,
     // This was mutable for rustpkg, which updates search paths based on the
@@ -89,14 +124,23 @@ pub struct Options {
     pub no_trans: bool,
     pub no_analysis: bool,
     pub debugging_opts: u64,
+    /// The sanitizer to instrument the crate for, with `-Z sanitizer=...`.
+    pub sanitizer: Option<Sanitizer>,
     /// Whether to write dependency files. It's (enabled, optional filename).
     pub write_dependency_info: (bool, Option<Path>),
-    /// Crate id-related things to maybe print. It's (crate_name, crate_file_name).
-    pub print_metas: (bool, bool),
+    /// Information to print instead of compiling, in the order requested.
+    pub prints: Vec<PrintRequest>,
     pub cg: CodegenOptions,
     pub color: ColorConfig,
+    pub error_format: ErrorOutputType,
     pub externs: HashMap<String, Vec<String>>,
     pub crate_name: Option<String>,
+    /// The `(from, to)` path prefixes given with `--remap-path-prefix`, to
+    /// rewrite in the paths recorded in the output.
+    pub remap_path_prefix: Vec<(String, String)>,
+    /// The save-analysis dumps of the crates depending on this one, or the
+    /// directories holding them, given with `--downstream-analysis`.
+    pub downstream_analysis: Vec<Path>,
 }
 
 /// Some reasonable defaults
@@ -107,6 +151,7 @@ pub fn basic_options() -> Options {
         optimize: No,
         debuginfo: NoDebugInfo,
         lint_opts: Vec::new(),
+        lint_config: None,
         describe_lints: false,
         output_types: Vec::new(),
         addl_lib_search_paths: RefCell::new(HashSet::new()),
@@ -118,12 +163,16 @@ pub fn basic_options() -> Options {
         no_trans: false,
         no_analysis: false,
         debugging_opts: 0,
+        sanitizer: None,
         write_dependency_info: (false, None),
-        print_metas: (false, false),
+        prints: Vec::new(),
         cg: basic_codegen_options(),
         color: Auto,
+        error_format: HumanReadable,
         externs: HashMap::new(),
         crate_name: None,
+        remap_path_prefix: Vec::new(),
+        downstream_analysis: Vec::new(),
     }
 }
 
@@ -144,6 +193,7 @@ pub enum CrateType {
     CrateTypeDylib,
     CrateTypeRlib,
     CrateTypeStaticlib,
+    CrateTypeCdylib,
 }
 
 macro_rules! debugging_opts(
@@ -180,10 +230,15 @@ debugging_opts!(
         AST_JSON_NOEXPAND,
         LS,
         SAVE_ANALYSIS,
+        SAVE_ANALYSIS_JSON,
         FLOWGRAPH_PRINT_LOANS,
         FLOWGRAPH_PRINT_MOVES,
         FLOWGRAPH_PRINT_ASSIGNS,
-        FLOWGRAPH_PRINT_ALL
+        FLOWGRAPH_PRINT_ALL,
+        APPLY_SUGGESTIONS,
+        INCREMENTAL_INFO,
+        PROFILE,
+        LLVM_FN_STATS
     ]
     0
 )
@@ -220,6 +275,8 @@ pub fn debugging_opts_map() -> Vec<(&'static str, &'static str, u64)> {
      ("ls", "List the symbols defined by a library crate", LS),
      ("save-analysis", "Write syntax and type analysis information \
                         in addition to normal output", SAVE_ANALYSIS),
+     ("save-analysis-json", "Write syntax and type analysis information \
+                             as JSON in addition to normal output", SAVE_ANALYSIS_JSON),
      ("flowgraph-print-loans", "Include loan analysis data in \
                        --pretty flowgraph output", FLOWGRAPH_PRINT_LOANS),
      ("flowgraph-print-moves", "Include move analysis data in \
@@ -227,7 +284,18 @@ pub fn debugging_opts_map() -> Vec<(&'static str, &'static str, u64)> {
      ("flowgraph-print-assigns", "Include assignment analysis data in \
                        --pretty flowgraph output", FLOWGRAPH_PRINT_ASSIGNS),
      ("flowgraph-print-all", "Include all dataflow analysis data in \
-                       --pretty flowgraph output", FLOWGRAPH_PRINT_ALL))
+                       --pretty flowgraph output", FLOWGRAPH_PRINT_ALL),
+     ("apply-suggestions", "Rewrite the source files, applying the fixes \
+                            suggested by diagnostics", APPLY_SUGGESTIONS),
+     ("incremental-info", "Print the items invalidated since the last \
+                           incremental build", INCREMENTAL_INFO),
+     ("profile", "Insert gcov profiling code, writing .gcno files when \
+                  compiling and .gcda files when run", PROFILE),
+     ("llvm-fn-stats", "Print the LLVM instructions of each function before \
+                        and after optimization, and the time the function \
+                        passes spent on it. The module passes, which do most \
+                        of the optimization at -O, are not timed",
+      LLVM_FN_STATS))
 }
 
 /// Declare a macro that will define all CodegenOptions fields and parsers all
@@ -256,7 +324,7 @@ macro_rules! cgoptions(
         &[ $( (stringify!($opt), cgsetters::$opt, $desc) ),* ];
 
     mod cgsetters {
-        use super::CodegenOptions;
+        use super::{CodegenOptions, FailStrategy, FailUnwind, FailAbort};
 
         $(
             pub fn $opt(cg: &mut CodegenOptions, v: Option<&str>) -> bool {
@@ -285,6 +353,21 @@ macro_rules! cgoptions(
             }
         }
 
+        fn parse_uint(slot: &mut uint, v: Option<&str>) -> bool {
+            match v.and_then(from_str) {
+                Some(i) => { *slot = i; true },
+                None => false,
+            }
+        }
+
+        fn parse_fail_strategy(slot: &mut FailStrategy, v: Option<&str>) -> bool {
+            match v {
+                Some("unwind") => { *slot = FailUnwind; true },
+                Some("abort") => { *slot = FailAbort; true },
+                _ => false,
+            }
+        }
+
         fn parse_list(slot: &mut Vec<String>, v: Option<&str>)
                       -> bool {
             match v {
@@ -306,6 +389,8 @@ cgoptions!(
         "tool to assemble archives with"),
     linker: Option<String> = (None, parse_opt_string,
         "system linker to link outputs with"),
+    objcopy: Option<String> = (None, parse_opt_string,
+        "system objcopy utility to split debuginfo with"),
     link_args: Vec<String> = (Vec::new(), parse_list,
         "extra arguments to pass to the linker (space separated)"),
     target_cpu: String = ("generic".to_string(), parse_string,
@@ -318,6 +403,8 @@ cgoptions!(
         "a list of arguments to pass to llvm (space separated)"),
     save_temps: bool = (false, parse_bool,
         "save all temporary output files during compilation"),
+    split_debuginfo: bool = (false, parse_bool,
+        "move the debuginfo of linked outputs into a separate file"),
     rpath: bool = (false, parse_bool,
         "set rpath values in libs/exes"),
     no_prepopulate_passes: bool = (false, parse_bool,
@@ -342,6 +429,22 @@ cgoptions!(
          "metadata to mangle symbol names with"),
     extra_filename: String = ("".to_string(), parse_string,
          "extra data to put in each output filename"),
+    incremental: Option<String> = (None, parse_opt_string,
+         "directory to cache the build in, to reuse it when the crate is unchanged"),
+    codegen_units: uint = (1, parse_uint,
+         "divide crate into N units to optimize and compile in parallel"),
+    fail_strategy: FailStrategy = (FailUnwind, parse_fail_strategy,
+         "what task failure does: `unwind` the stack, or `abort` by calling the \
+          `abort` lang item without landing pads"),
+    overflow_checks: bool = (false, parse_bool,
+         "check integer `+`, `-` and `*` for overflow at runtime, failing the \
+          task when it happens"),
+    time_report: Option<String> = (None, parse_opt_string,
+         "write the time and memory used by each pass to this file, as JSON if \
+          it ends in .json and as CSV otherwise"),
+    time_report_append: bool = (false, parse_bool,
+         "append to the time report rather than replacing it, to collect \
+          the reports of several crates, even if they are built in parallel"),
 )
 
 pub fn build_codegen_options(matches: &getopts::Matches) -> CodegenOptions
@@ -384,40 +487,27 @@ pub fn default_lib_output() -> CrateType {
 }
 
 pub fn default_configuration(sess: &Session) -> ast::CrateConfig {
-    let tos = match sess.targ_cfg.os {
-        abi::OsWin32 =>   InternedString::new("win32"),
-        abi::OsMacos =>   InternedString::new("macos"),
-        abi::OsLinux =>   InternedString::new("linux"),
-        abi::OsAndroid => InternedString::new("android"),
-        abi::OsFreebsd => InternedString::new("freebsd"),
-        abi::OsiOS =>     InternedString::new("ios"),
-    };
-
-    // ARM is bi-endian, however using NDK seems to default
-    // to little-endian unless a flag is provided.
-    let (end,arch,wordsz) = match sess.targ_cfg.arch {
-        abi::X86 =>    ("little", "x86",    "32"),
-        abi::X86_64 => ("little", "x86_64", "64"),
-        abi::Arm =>    ("little", "arm",    "32"),
-        abi::Mips =>   ("big",    "mips",   "32"),
-        abi::Mipsel => ("little", "mipsel", "32")
-    };
-
-    let fam = match sess.targ_cfg.os {
-        abi::OsWin32 => InternedString::new("windows"),
-        _ => InternedString::new("unix")
-    };
-
+    let target = &sess.targ_cfg.target;
     let mk = attr::mk_name_value_item_str;
-    return vec!(// Target bindings.
-         attr::mk_word_item(fam.clone()),
-         mk(InternedString::new("target_os"), tos),
-         mk(InternedString::new("target_family"), fam),
-         mk(InternedString::new("target_arch"), InternedString::new(arch)),
-         mk(InternedString::new("target_endian"), InternedString::new(end)),
+    let mut cfg = vec!(// Target bindings.
+         mk(InternedString::new("target_os"),
+            token::intern_and_get_ident(target.target_os.as_slice())),
+         mk(InternedString::new("target_arch"),
+            token::intern_and_get_ident(target.arch.as_slice())),
+         mk(InternedString::new("target_endian"),
+            token::intern_and_get_ident(target.target_endian.as_slice())),
          mk(InternedString::new("target_word_size"),
-            InternedString::new(wordsz))
+            token::intern_and_get_ident(target.target_word_size.as_slice()))
     );
+    match target.options.target_family {
+        Some(ref family) => {
+            let fam = token::intern_and_get_ident(family.as_slice());
+            cfg.insert(0, mk(InternedString::new("target_family"), fam.clone()));
+            cfg.insert(0, attr::mk_word_item(fam));
+        }
+        None => {}
+    }
+    cfg
 }
 
 pub fn append_configuration(cfg: &mut ast::CrateConfig,
@@ -445,74 +535,32 @@ pub fn build_configuration(sess: &Session) -> ast::CrateConfig {
     user_cfg.move_iter().collect::<Vec<_>>().append(default_cfg.as_slice())
 }
 
-pub fn get_os(triple: &str) -> Option<abi::Os> {
-    for &(name, os) in os_names.iter() {
-        if triple.contains(name) { return Some(os) }
-    }
-    None
-}
-static os_names : &'static [(&'static str, abi::Os)] = &[
-    ("mingw32", abi::OsWin32),
-    ("win32",   abi::OsWin32),
-    ("darwin",  abi::OsMacos),
-    ("android", abi::OsAndroid),
-    ("linux",   abi::OsLinux),
-    ("freebsd", abi::OsFreebsd),
-    ("ios",     abi::OsiOS)];
-
-pub fn get_arch(triple: &str) -> Option<abi::Architecture> {
-    for &(arch, abi) in architecture_abis.iter() {
-        if triple.contains(arch) { return Some(abi) }
-    }
-    None
-}
-static architecture_abis : &'static [(&'static str, abi::Architecture)] = &[
-    ("i386",   abi::X86),
-    ("i486",   abi::X86),
-    ("i586",   abi::X86),
-    ("i686",   abi::X86),
-    ("i786",   abi::X86),
-
-    ("x86_64", abi::X86_64),
-
-    ("arm",    abi::Arm),
-    ("xscale", abi::Arm),
-    ("thumb",  abi::Arm),
-
-    ("mipsel", abi::Mipsel),
-    ("mips",   abi::Mips)];
-
 pub fn build_target_config(sopts: &Options) -> Config {
-    let os = match get_os(sopts.target_triple.as_slice()) {
-      Some(os) => os,
-      None => early_error("unknown operating system")
-    };
-    let arch = match get_arch(sopts.target_triple.as_slice()) {
-      Some(arch) => arch,
-      None => {
-          early_error(format!("unknown architecture: {}",
-                              sopts.target_triple.as_slice()).as_slice())
-      }
+    let target = match Target::search(sopts.target_triple.as_slice()) {
+        Ok(target) => target,
+        Err(e) => early_error(e.as_slice())
     };
-    let (int_type, uint_type) = match arch {
-      abi::X86 => (ast::TyI32, ast::TyU32),
-      abi::X86_64 => (ast::TyI64, ast::TyU64),
-      abi::Arm => (ast::TyI32, ast::TyU32),
-      abi::Mips => (ast::TyI32, ast::TyU32),
-      abi::Mipsel => (ast::TyI32, ast::TyU32)
-    };
-    let target_triple = sopts.target_triple.clone();
-    let target_strs = match arch {
-      abi::X86 => x86::get_target_strs(target_triple, os),
-      abi::X86_64 => x86_64::get_target_strs(target_triple, os),
-      abi::Arm => arm::get_target_strs(target_triple, os),
-      abi::Mips => mips::get_target_strs(target_triple, os),
-      abi::Mipsel => mipsel::get_target_strs(target_triple, os)
+    match sopts.sanitizer {
+        Some(sanitizer) if target.target_os.as_slice() != "linux" ||
+                           target.arch.as_slice() != "x86_64" => {
+            early_error(format!("-Z sanitizer={} is only supported on x86_64 \
+                                 linux", sanitizer).as_slice())
+        }
+        _ => {}
+    }
+    if sopts.debugging_opts & PROFILE != 0 &&
+       (target.target_os.as_slice() != "linux" ||
+        (target.arch.as_slice() != "x86_64" && target.arch.as_slice() != "x86")) {
+        early_error("-Z profile is only supported on x86 and x86_64 linux");
+    }
+    let (int_type, uint_type) = match target.target_word_size.as_slice() {
+        "32" => (ast::TyI32, ast::TyU32),
+        "64" => (ast::TyI64, ast::TyU64),
+        w => early_error(format!("unsupported target word size: {}", w).as_slice())
     };
     Config {
-        os: os,
-        arch: arch,
-        target_strs: target_strs,
+        arch: target.architecture(),
+        target: target,
         int_type: int_type,
         uint_type: uint_type,
     }
@@ -526,11 +574,14 @@ pub fn optgroups() -> Vec<getopts::OptGroup> {
         optmulti("L", "",   "Add a directory to the library search path", "PATH"),
         optmulti("", "crate-type", "Comma separated list of types of crates
                                     for the compiler to emit",
-                 "[bin|lib|rlib|dylib|staticlib]"),
+                 "[bin|lib|rlib|dylib|cdylib|staticlib]"),
         optmulti("", "emit", "Comma separated list of types of output for the compiler to emit",
-                 "[asm|bc|ir|obj|link]"),
+                 "[asm|bc|ir|obj|link|debuginfo]"),
         optopt("", "crate-name", "Specify the name of the crate being built",
                "NAME"),
+        optmulti("", "print", "Comma separated list of compiler information to \
+                               print on stdout instead of compiling",
+                 "[crate-name|file-names|sysroot|cfg|target-list]"),
         optflag("", "print-crate-name", "Output the crate name and exit"),
         optflag("", "print-file-name", "Output the file(s) that would be written if compilation \
               continued and exit"),
@@ -569,6 +620,7 @@ pub fn optgroups() -> Vec<getopts::OptGroup> {
         optmulti("A", "allow", "Set lint allowed", "OPT"),
         optmulti("D", "deny", "Set lint denied", "OPT"),
         optmulti("F", "forbid", "Set lint forbidden", "OPT"),
+        optopt("", "lint-config", "Read lint levels from a policy file", "FILE"),
         optmulti("C", "codegen", "Set a codegen option", "OPT[=VALUE]"),
         optmulti("Z", "", "Set internal debugging options", "FLAG"),
         optflagopt("v", "version", "Print version info and exit", "verbose"),
@@ -576,8 +628,18 @@ pub fn optgroups() -> Vec<getopts::OptGroup> {
             auto   = colorize, if output goes to a tty (default);
             always = always colorize output;
             never  = never colorize output", "auto|always|never"),
+        optopt("", "error-format", "How errors and other messages are produced:
+            human = human readable text (default);
+            json  = one JSON object per diagnostic", "human|json"),
         optmulti("", "extern", "Specify where an external rust library is located",
                  "PATH"),
+        optflag("", "coverage", "Instrument the code for gcov, same as -Z profile"),
+        optmulti("", "remap-path-prefix", "Remap source paths starting with FROM to \
+                                           start with TO in the output",
+                 "FROM=TO"),
+        optmulti("", "downstream-analysis", "Report the public items which none of the \
+                                             crates in the given save-analysis output use",
+                 "PATH"),
     )
 }
 
@@ -602,6 +664,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
                 "rlib"      => CrateTypeRlib,
                 "staticlib" => CrateTypeStaticlib,
                 "dylib"     => CrateTypeDylib,
+                "cdylib"    => CrateTypeCdylib,
                 "bin"       => CrateTypeExecutable,
                 _ => {
                     early_error(format!("unknown crate type: `{}`",
@@ -629,10 +692,28 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         }
     }
 
+    let lint_config = matches.opt_str("lint-config").map(|s| Path::new(s));
+
     let mut debugging_opts = 0;
+    let mut sanitizer = None;
     let debug_flags = matches.opt_strs("Z");
     let debug_map = debugging_opts_map();
     for debug_flag in debug_flags.iter() {
+        // The only debug option which takes a value
+        if debug_flag.as_slice().starts_with("sanitizer=") {
+            let name = debug_flag.as_slice().slice_from("sanitizer=".len());
+            sanitizer = Some(match name {
+                "address" => AddressSanitizer,
+                "thread" => ThreadSanitizer,
+                "memory" => MemorySanitizer,
+                _ => {
+                    early_error(format!("unknown sanitizer: `{}`, expected one \
+                                         of `address`, `thread` or `memory`",
+                                        name).as_slice())
+                }
+            });
+            continue
+        }
         let mut this_bit = 0;
         for tuple in debug_map.iter() {
             let (name, bit) = match *tuple { (ref a, _, b) => (a, b) };
@@ -648,6 +729,11 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         debugging_opts |= this_bit;
     }
 
+    // --coverage is the gcc spelling of -Z profile
+    if matches.opt_present("coverage") {
+        debugging_opts |= PROFILE;
+    }
+
     if debugging_opts & DEBUG_LLVM != 0 {
         unsafe { llvm::LLVMSetDebug(1); }
     }
@@ -663,6 +749,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
                     "bc"   => link::OutputTypeBitcode,
                     "obj"  => link::OutputTypeObject,
                     "link" => link::OutputTypeExe,
+                    "debuginfo" => link::OutputTypeDebugInfo,
                     _ => {
                         early_error(format!("unknown emission type: `{}`",
                                             part).as_slice())
@@ -672,6 +759,10 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
             }
         }
     };
+    // The split debuginfo is taken from the linked output
+    if output_types.contains(&link::OutputTypeDebugInfo) {
+        output_types.push(link::OutputTypeExe);
+    }
     output_types.as_mut_slice().sort();
     output_types.dedup();
     if output_types.len() == 0 {
@@ -724,6 +815,9 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
                                     arg).as_slice());
             }
         }
+    } else if debugging_opts & PROFILE != 0 {
+        // The profiling code finds the lines it counts from debuginfo
+        LimitedDebugInfo
     } else {
         NoDebugInfo
     };
@@ -738,14 +832,45 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
                                  matches.opt_str("dep-info")
                                         .map(|p| Path::new(p)));
 
-    let print_metas = (matches.opt_present("print-crate-name"),
-                       matches.opt_present("print-file-name") ||
-                       matches.opt_present("crate-file-name"));
+    let mut prints = Vec::new();
+    if matches.opt_present("print-crate-name") {
+        prints.push(PrintCrateName);
+    }
+    if matches.opt_present("print-file-name") ||
+       matches.opt_present("crate-file-name") {
+        prints.push(PrintFileNames);
+    }
     if matches.opt_present("crate-file-name") {
         early_warn("the --crate-file-name argument has been renamed to \
                     --print-file-name");
     }
+    for unparsed_print in matches.opt_strs("print").iter() {
+        for part in unparsed_print.as_slice().split(',') {
+            let print = match part {
+                "crate-name"  => PrintCrateName,
+                "file-names"  => PrintFileNames,
+                "sysroot"     => PrintSysroot,
+                "cfg"         => PrintCfg,
+                "target-list" => PrintTargetList,
+                _ => {
+                    early_error(format!("unknown print request: `{}`",
+                                        part).as_slice())
+                }
+            };
+            if !prints.contains(&print) {
+                prints.push(print);
+            }
+        }
+    }
     let cg = build_codegen_options(matches);
+    if cg.codegen_units == 0 {
+        early_error("value for codegen units must be a positive nonzero integer");
+    }
+    if (cg.split_debuginfo || output_types.contains(&link::OutputTypeDebugInfo)) &&
+       debuginfo == NoDebugInfo {
+        early_error("splitting debuginfo requires debuginfo to be emitted, \
+                     use -g or --debuginfo");
+    }
 
     let color = match matches.opt_str("color").as_ref().map(|s| s.as_slice()) {
         Some("auto")   => Auto,
@@ -761,6 +886,19 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         }
     };
 
+    let error_format = match matches.opt_str("error-format").as_ref().map(|s| s.as_slice()) {
+        Some("human") => HumanReadable,
+        Some("json")  => Json,
+
+        None => HumanReadable,
+
+        Some(arg) => {
+            early_error(format!("argument for --error-format must be human or \
+                                 json (instead was `{}`)",
+                                arg).as_slice())
+        }
+    };
+
     let mut externs = HashMap::new();
     for arg in matches.opt_strs("extern").iter() {
         let mut parts = arg.as_slice().splitn('=', 1);
@@ -778,12 +916,25 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
 
     let crate_name = matches.opt_str("crate-name");
 
+    let remap_path_prefix = matches.opt_strs("remap-path-prefix").move_iter().map(|arg| {
+        match arg.as_slice().rfind('=') {
+            Some(i) => (arg.as_slice().slice_to(i).to_string(),
+                        arg.as_slice().slice_from(i + 1).to_string()),
+            None => early_error("--remap-path-prefix value must be of the format `FROM=TO`"),
+        }
+    }).collect();
+
+    let downstream_analysis = matches.opt_strs("downstream-analysis").move_iter().map(|s| {
+        Path::new(s)
+    }).collect();
+
     Options {
         crate_types: crate_types,
         gc: gc,
         optimize: opt_level,
         debuginfo: debuginfo,
         lint_opts: lint_opts,
+        lint_config: lint_config,
         describe_lints: describe_lints,
         output_types: output_types,
         addl_lib_search_paths: RefCell::new(addl_lib_search_paths),
@@ -795,12 +946,16 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         no_trans: no_trans,
         no_analysis: no_analysis,
         debugging_opts: debugging_opts,
+        sanitizer: sanitizer,
         write_dependency_info: write_dependency_info,
-        print_metas: print_metas,
+        prints: prints,
         cg: cg,
         color: color,
+        error_format: error_format,
         externs: externs,
         crate_name: crate_name,
+        remap_path_prefix: remap_path_prefix,
+        downstream_analysis: downstream_analysis,
     }
 }
 
@@ -810,7 +965,47 @@ impl fmt::Show for CrateType {
             CrateTypeExecutable => "bin".fmt(f),
             CrateTypeDylib => "dylib".fmt(f),
             CrateTypeRlib => "rlib".fmt(f),
-            CrateTypeStaticlib => "staticlib".fmt(f)
+            CrateTypeStaticlib => "staticlib".fmt(f),
+            CrateTypeCdylib => "cdylib".fmt(f)
+        }
+    }
+}
+
+impl fmt::Show for FailStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FailUnwind => "unwind".fmt(f),
+            FailAbort => "abort".fmt(f)
+        }
+    }
+}
+
+impl fmt::Show for Sanitizer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AddressSanitizer => "address".fmt(f),
+            ThreadSanitizer => "thread".fmt(f),
+            MemorySanitizer => "memory".fmt(f)
+        }
+    }
+}
+
+impl Sanitizer {
+    /// The LLVM passes instrumenting a module for the sanitizer.
+    pub fn llvm_passes(&self) -> Vec<&'static str> {
+        match *self {
+            AddressSanitizer => vec!("asan", "asan-module"),
+            ThreadSanitizer => vec!("tsan"),
+            MemorySanitizer => vec!("msan"),
+        }
+    }
+
+    /// The name of the sanitizer's runtime library from compiler-rt.
+    pub fn runtime(&self) -> &'static str {
+        match *self {
+            AddressSanitizer => "rustc_asan",
+            ThreadSanitizer => "rustc_tsan",
+            MemorySanitizer => "rustc_msan",
         }
     }
 }
@@ -33,12 +33,14 @@ use plugin;
 
 use util::common::time;
 use util::ppaux;
+use util::time_report;
 use util::nodemap::{NodeSet};
 
 use dot = graphviz;
 
 use serialize::{json, Encodable};
 
+use std::collections::HashMap;
 use std::io;
 use std::io::fs;
 use std::io::MemReader;
@@ -73,7 +75,7 @@ pub fn compile_input(sess: Session,
     // We need nested scopes here, because the intermediate results can keep
     // large chunks of memory alive and we want to free them as soon as
     // possible to keep the peak memory usage low
-    let (outputs, trans, sess) = {
+    let (outputs, trans, sess, incremental_state) = {
         let (outputs, expanded_crate, ast_map, id) = {
             let krate = phase_1_parse_input(&sess, cfg, input);
             if stop_after_phase_1(&sess) { return; }
@@ -84,6 +86,7 @@ pub fn compile_input(sess: Session,
                                                  &sess);
             let id = link::find_crate_name(Some(&sess), krate.attrs.as_slice(),
                                            input);
+            time_report::set_crate_name(id.as_slice());
             let (expanded_crate, ast_map)
                 = match phase_2_configure_and_expand(&sess, krate, id.as_slice()) {
                     None => return,
@@ -96,20 +99,54 @@ pub fn compile_input(sess: Session,
 
         if stop_after_phase_2(&sess) { return; }
 
+        let fingerprints = if sess.opts.cg.incremental.is_some() {
+            let fingerprints = time(sess.time_passes(), "incremental fingerprinting", (), |_|
+                middle::incremental::fingerprint_crate(&sess, &expanded_crate, &ast_map));
+            let artifacts = output_files(&sess, &outputs, id.as_slice());
+            if middle::incremental::reuse_artifacts(&sess, id.as_slice(), &fingerprints,
+                                                    artifacts.as_slice()) {
+                return;
+            }
+            Some(fingerprints)
+        } else {
+            None
+        };
+
         let analysis = phase_3_run_analysis_passes(sess, &expanded_crate,
-                                                   ast_map, id);
+                                                   ast_map, id.clone());
         phase_save_analysis(&analysis.ty_cx.sess, &expanded_crate, &analysis, outdir);
-        if stop_after_phase_3(&analysis.ty_cx.sess) { return; }
+        if stop_after_phase_3(&analysis.ty_cx.sess) {
+            apply_suggestions(&analysis.ty_cx.sess);
+            return;
+        }
+        let incremental_state = fingerprints.map(|fingerprints| {
+            let graph = time(analysis.ty_cx.sess.time_passes(), "incremental dependency graph",
+                             (), |_| middle::incremental::build_dep_graph(&analysis.ty_cx,
+                                                                          &fingerprints));
+            (id, fingerprints, graph)
+        });
         let (tcx, trans) = phase_4_translate_to_llvm(expanded_crate, analysis);
 
         // Discard interned strings as they are no longer required.
         token::get_ident_interner().clear();
 
-        (outputs, trans, tcx.sess)
+        (outputs, trans, tcx.sess, incremental_state)
     };
     phase_5_run_llvm_passes(&sess, &trans, &outputs);
-    if stop_after_phase_5(&sess) { return; }
-    phase_6_link_output(&sess, &trans, &outputs);
+    if !stop_after_phase_5(&sess) {
+        phase_6_link_output(&sess, &trans, &outputs);
+    }
+
+    match incremental_state {
+        Some((id, fingerprints, graph)) => {
+            let artifacts = output_files(&sess, &outputs, id.as_slice());
+            middle::incremental::save(&sess, id.as_slice(), &fingerprints, &graph,
+                                      artifacts.as_slice());
+        }
+        None => {}
+    }
+
+    apply_suggestions(&sess);
 }
 
 /**
@@ -233,13 +270,17 @@ pub fn phase_2_configure_and_expand(sess: &Session,
         }
     });
 
-    let Registry { syntax_exts, lint_passes, .. } = registry;
+    let Registry { syntax_exts, lint_passes, lint_groups, .. } = registry;
 
     {
         let mut ls = sess.lint_store.borrow_mut();
         for pass in lint_passes.move_iter() {
             ls.register_pass(Some(sess), true, pass);
         }
+
+        for (name, to) in lint_groups.move_iter() {
+            ls.register_group(Some(sess), true, name, to);
+        }
     }
 
     // Lint plugins are registered; now we can process command line flags.
@@ -265,6 +306,7 @@ pub fn phase_2_configure_and_expand(sess: &Session,
             let cfg = syntax::ext::expand::ExpansionConfig {
                 deriving_hash_type_parameter: sess.features.default_type_params.get(),
                 crate_name: crate_name.to_string(),
+                remap_path_prefix: sess.opts.remap_path_prefix.clone(),
             };
             syntax::ext::expand::expand_crate(&sess.parse_sess,
                                               cfg,
@@ -413,6 +455,13 @@ pub fn phase_3_run_analysis_passes(sess: Session,
                                   krate)
     });
 
+    if !ty_cx.sess.opts.downstream_analysis.is_empty() {
+        time(time_passes, "unused pub item checking", (), |_| {
+            middle::save::unused_pub::check_crate(&ty_cx, krate, &exported_items,
+                                                  name.as_slice())
+        });
+    }
+
     time(time_passes, "lint checking", (), |_|
          lint::check_crate(&ty_cx, krate, &exported_items));
 
@@ -430,7 +479,7 @@ pub fn phase_save_analysis(sess: &Session,
                            krate: &ast::Crate,
                            analysis: &CrateAnalysis,
                            odir: &Option<Path>) {
-    if (sess.opts.debugging_opts & config::SAVE_ANALYSIS) == 0 {
+    if (sess.opts.debugging_opts & (config::SAVE_ANALYSIS | config::SAVE_ANALYSIS_JSON)) == 0 {
         return;
     }
     time(sess.time_passes(), "save analysis", krate, |krate|
@@ -444,8 +493,12 @@ pub struct CrateTranslation {
     pub link: LinkMeta,
     pub metadata: Vec<u8>,
     pub reachable: Vec<String>,
+    pub exported_symbols: HashMap<config::CrateType, Vec<String>>,
     pub crate_formats: dependency_format::Dependencies,
     pub no_builtins: bool,
+    /// The item each function symbol was translated from, with the type
+    /// arguments of monomorphizations. Only kept for `-Z llvm-fn-stats`.
+    pub fn_items: HashMap<String, String>,
 }
 
 /// Run the translation phase to LLVM, after which the AST and analysis can
@@ -500,6 +553,19 @@ pub fn phase_6_link_output(sess: &Session,
                            trans.link.crate_name.as_slice()));
 }
 
+/// Rewrite the source files with the suggestions made by diagnostics, if
+/// `-Z apply-suggestions` was given. This is only done once compilation has
+/// succeeded, so that nothing is rewritten for a crate which has errors.
+pub fn apply_suggestions(sess: &Session) {
+    if !sess.apply_suggestions() {
+        return;
+    }
+    match sess.diagnostic().apply_suggestions() {
+        Ok(_) => {}
+        Err(e) => sess.warn(format!("failed to apply suggestions: {}", e).as_slice()),
+    }
+}
+
 pub fn stop_after_phase_3(sess: &Session) -> bool {
    if sess.opts.no_trans {
         debug!("invoked with --no-trans, returning early from compile_input");
@@ -535,11 +601,8 @@ pub fn stop_after_phase_5(sess: &Session) -> bool {
     return false;
 }
 
-fn write_out_deps(sess: &Session,
-                  input: &Input,
-                  outputs: &OutputFilenames,
-                  id: &str) {
-
+/// The files produced for the requested output types.
+fn output_files(sess: &Session, outputs: &OutputFilenames, id: &str) -> Vec<Path> {
     let mut out_filenames = Vec::new();
     for output_type in sess.opts.output_types.iter() {
         let file = outputs.path(*output_type);
@@ -548,12 +611,26 @@ fn write_out_deps(sess: &Session,
                 for output in sess.crate_types.borrow().iter() {
                     let p = link::filename_for_input(sess, *output,
                                                      id, &file);
+                    if sess.split_debuginfo() && link::links_natively(*output) {
+                        out_filenames.push(link::debuginfo_filename(sess, &p));
+                    }
                     out_filenames.push(p);
                 }
             }
+            // Listed along with the linked outputs
+            link::OutputTypeDebugInfo => {}
             _ => { out_filenames.push(file); }
         }
     }
+    out_filenames
+}
+
+fn write_out_deps(sess: &Session,
+                  input: &Input,
+                  outputs: &OutputFilenames,
+                  id: &str) {
+
+    let out_filenames = output_files(sess, outputs, id);
 
     // Write out dependency rules to the dep-info file if requested with
     // --dep-info
@@ -866,6 +943,9 @@ pub fn collect_crate_types(session: &Session,
                 Some(ref n) if n.equiv(&("dylib")) => {
                     Some(config::CrateTypeDylib)
                 }
+                Some(ref n) if n.equiv(&("cdylib")) => {
+                    Some(config::CrateTypeCdylib)
+                }
                 Some(ref n) if n.equiv(&("lib")) => {
                     Some(config::default_lib_output())
                 }
@@ -920,7 +1000,8 @@ pub fn collect_crate_types(session: &Session,
         if !res {
             session.warn(format!("dropping unsupported crate type `{}` \
                                    for target os `{}`",
-                                 *crate_type, session.targ_cfg.os).as_slice());
+                                 *crate_type,
+                                 session.targ_cfg.target.target_os).as_slice());
         }
 
         res
@@ -955,6 +1036,7 @@ impl OutputFilenames {
             link::OutputTypeLlvmAssembly => base.with_extension("ll"),
             link::OutputTypeObject => base.with_extension("o"),
             link::OutputTypeExe => base,
+            link::OutputTypeDebugInfo => base.with_extension("debug"),
         }
     }
 
@@ -1004,7 +1086,11 @@ pub fn build_output_filenames(input: &Input,
         }
 
         Some(ref out_file) => {
-            let ofile = if sess.opts.output_types.len() > 1 {
+            // The split debuginfo file is named after the linked output
+            let outputs = sess.opts.output_types.iter().filter(|&&t| {
+                t != link::OutputTypeDebugInfo
+            }).count();
+            let ofile = if outputs > 1 {
                 sess.warn("ignoring specified output filename because multiple \
                            outputs were requested");
                 None
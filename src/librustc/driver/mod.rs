@@ -11,11 +11,13 @@
 pub use syntax::diagnostic;
 
 use back::link;
+use back::target::Target;
 use driver::driver::{Input, FileInput, StrInput};
 use driver::session::{Session, build_session};
 use lint::Lint;
 use lint;
 use metadata;
+use util::time_report;
 
 use std::any::AnyRefExt;
 use std::io;
@@ -23,6 +25,7 @@ use std::os;
 use std::task::TaskBuilder;
 
 use syntax::ast;
+use syntax::attr::AttrMetaMethods;
 use syntax::parse;
 use syntax::diagnostic::Emitter;
 use syntax::diagnostics;
@@ -75,6 +78,11 @@ fn run_compiler(args: &[String]) {
                 describe_lints(&ls, false);
                 return;
             }
+            if !sopts.prints.is_empty() {
+                let sess = build_session(sopts, None, descriptions);
+                print_crate_info(&sess, None, &None, &None);
+                return;
+            }
             early_error("no input filename given");
         }
         1u => {
@@ -120,11 +128,26 @@ fn run_compiler(args: &[String]) {
         return;
     }
 
-    if print_crate_info(&sess, &input, &odir, &ofile) {
+    if print_crate_info(&sess, Some(&input), &odir, &ofile) {
         return;
     }
 
+    let time_report = sess.opts.cg.time_report.as_ref().map(|p| Path::new(p.as_slice()));
+    let time_report_append = sess.opts.cg.time_report_append;
+    if time_report.is_some() {
+        time_report::start();
+    }
+
     driver::compile_input(sess, cfg, &input, &odir, &ofile);
+
+    match time_report {
+        Some(path) => match time_report::finish(&path, time_report_append) {
+            Ok(()) => {}
+            Err(e) => early_error(format!("failed to write time report `{}`: {}",
+                                          path.display(), e).as_slice()),
+        },
+        None => {}
+    }
 }
 
 /// Prints version information and returns None on success or an error
@@ -208,16 +231,47 @@ Available lint options:
 
     print_lints(builtin);
 
-    match (loaded_plugins, plugin.len()) {
-        (false, 0) => {
-            println!("Compiler plugins can provide additional lints. To see a listing of these, \
-                      re-run `rustc -W help` with a crate filename.");
+    let (plugin_groups, builtin_groups) = lint_store.get_lint_groups().partitioned(|&(_, _, p)| p);
+
+    let max_name_len = plugin_groups.iter().chain(builtin_groups.iter())
+        .map(|&(name, _, _)| name.char_len())
+        .max().unwrap_or(0);
+    let padded = |x: &str| {
+        " ".repeat(max_name_len - x.char_len()).append(x)
+    };
+
+    let print_lint_groups = |groups: Vec<(&str, &[lint::LintId], bool)>| {
+        println!("    {}  {}", padded("name"), "sub-lints");
+        println!("    {}  {}", padded("----"), "---------");
+        for &(name, lints, _) in groups.iter() {
+            let sub_lints: Vec<String> = lints.iter().map(|lint_id| {
+                lint_id.as_str().replace("_", "-")
+            }).collect();
+            println!("    {}  {}",
+                     padded(name.replace("_", "-").as_slice()), sub_lints.connect(", "));
         }
-        (false, _) => fail!("didn't load lint plugins but got them anyway!"),
-        (true, 0) => println!("This crate does not load any lint plugins."),
-        (true, _) => {
-            println!("Lint checks provided by plugins loaded by this crate:\n");
-            print_lints(plugin);
+        println!("\n");
+    };
+
+    println!("Lint groups provided by rustc:\n");
+    print_lint_groups(builtin_groups);
+
+    match (loaded_plugins, plugin.len(), plugin_groups.len()) {
+        (false, 0, 0) => {
+            println!("Compiler plugins can provide additional lints and lint groups. To see a \
+                      listing of these, re-run `rustc -W help` with a crate filename.");
+        }
+        (false, _, _) => fail!("didn't load lint plugins but got them anyway!"),
+        (true, 0, 0) => println!("This crate does not load any lint plugins or lint groups."),
+        (true, _, _) => {
+            if plugin.len() > 0 {
+                println!("Lint checks provided by plugins loaded by this crate:\n");
+                print_lints(plugin);
+            }
+            if plugin_groups.len() > 0 {
+                println!("Lint groups provided by plugins loaded by this crate:\n");
+                print_lint_groups(plugin_groups);
+            }
         }
     }
 }
@@ -232,6 +286,9 @@ fn describe_debug_flags() {
             }
         }
     }
+    println!("    -Z {:>20s} -- {}", "sanitizer=val",
+             "instrument for a sanitizer (address, thread or memory); \
+              the standard library is not instrumented");
 }
 
 fn describe_codegen_flags() {
@@ -305,39 +362,61 @@ pub fn handle_options(mut args: Vec<String>) -> Option<getopts::Matches> {
 }
 
 fn print_crate_info(sess: &Session,
-                    input: &Input,
+                    input: Option<&Input>,
                     odir: &Option<Path>,
                     ofile: &Option<Path>)
                     -> bool {
-    let (crate_name, crate_file_name) = sess.opts.print_metas;
-    // these nasty nested conditions are to avoid doing extra work
-    if crate_name || crate_file_name {
-        let attrs = parse_crate_attrs(sess, input);
-        let t_outputs = driver::build_output_filenames(input,
-                                                       odir,
-                                                       ofile,
-                                                       attrs.as_slice(),
-                                                       sess);
-        let id = link::find_crate_name(Some(sess), attrs.as_slice(), input);
-
-        if crate_name {
-            println!("{}", id);
-        }
-        if crate_file_name {
-            let crate_types = driver::collect_crate_types(sess, attrs.as_slice());
-            let metadata = driver::collect_crate_metadata(sess, attrs.as_slice());
-            *sess.crate_metadata.borrow_mut() = metadata;
-            for &style in crate_types.iter() {
-                let fname = link::filename_for_input(sess, style, id.as_slice(),
-                                                     &t_outputs.with_extension(""));
-                println!("{}", fname.filename_display());
+    if sess.opts.prints.is_empty() {
+        return false;
+    }
+
+    // Only parse the crate attributes if something needs them
+    let attrs = input.map(|input| parse_crate_attrs(sess, input));
+    for print in sess.opts.prints.iter() {
+        match *print {
+            config::PrintSysroot => println!("{}", sess.sysroot().display()),
+            config::PrintTargetList => {
+                for target in Target::builtin_triples().iter() {
+                    println!("{}", target);
+                }
             }
-        }
+            config::PrintCfg => {
+                for cfg in config::build_configuration(sess).iter() {
+                    match cfg.value_str() {
+                        Some(value) => println!("{}=\"{}\"", cfg.name(), value),
+                        None => println!("{}", cfg.name()),
+                    }
+                }
+            }
+            config::PrintCrateName | config::PrintFileNames => {
+                let (input, attrs) = match (input, attrs.as_ref()) {
+                    (Some(input), Some(attrs)) => (input, attrs),
+                    _ => early_error("no input filename given"),
+                };
+                let id = link::find_crate_name(Some(sess), attrs.as_slice(), input);
+                if *print == config::PrintCrateName {
+                    println!("{}", id);
+                    continue;
+                }
 
-        true
-    } else {
-        false
+                let t_outputs = driver::build_output_filenames(input,
+                                                               odir,
+                                                               ofile,
+                                                               attrs.as_slice(),
+                                                               sess);
+                let crate_types = driver::collect_crate_types(sess, attrs.as_slice());
+                let metadata = driver::collect_crate_metadata(sess, attrs.as_slice());
+                *sess.crate_metadata.borrow_mut() = metadata;
+                for &style in crate_types.iter() {
+                    let fname = link::filename_for_input(sess, style, id.as_slice(),
+                                                         &t_outputs.with_extension(""));
+                    println!("{}", fname.filename_display());
+                }
+            }
+        }
     }
+
+    true
 }
 
 pub enum PpMode {
@@ -410,7 +489,7 @@ pub fn early_warn(msg: &str) {
 
 pub fn list_metadata(sess: &Session, path: &Path,
                      out: &mut io::Writer) -> io::IoResult<()> {
-    metadata::loader::list_file_metadata(sess.targ_cfg.os, path, out)
+    metadata::loader::list_file_metadata(sess.targ_cfg.target.options.is_like_osx, path, out)
 }
 
 /// Run a procedure which will detect failures in the compiler and print nicer
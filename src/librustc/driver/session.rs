@@ -9,6 +9,7 @@
 // except according to those terms.
 
 
+use back::link;
 use driver::config;
 use driver::driver;
 use front;
@@ -20,6 +21,7 @@ use util::nodemap::NodeMap;
 use syntax::ast::NodeId;
 use syntax::codemap::Span;
 use syntax::diagnostic;
+use syntax::diagnostic::{Applicability, CodeSuggestion};
 use syntax::diagnostics;
 use syntax::parse;
 use syntax::parse::token;
@@ -46,7 +48,8 @@ pub struct Session {
     pub local_crate_source_file: Option<Path>,
     pub working_dir: Path,
     pub lint_store: RefCell<lint::LintStore>,
-    pub lints: RefCell<NodeMap<Vec<(lint::LintId, codemap::Span, String)>>>,
+    pub lints: RefCell<NodeMap<Vec<(lint::LintId, codemap::Span, String,
+                                    Vec<CodeSuggestion>)>>>,
     pub node_id: Cell<ast::NodeId>,
     pub crate_types: RefCell<Vec<config::CrateType>>,
     pub crate_metadata: RefCell<Vec<String>>,
@@ -103,6 +106,13 @@ impl Session {
     pub fn note(&self, msg: &str) {
         self.diagnostic().handler().note(msg)
     }
+    pub fn span_help(&self, sp: Span, msg: &str) {
+        self.diagnostic().span_help(sp, msg)
+    }
+    pub fn span_suggestion(&self, sp: Span, msg: &str, suggestion: String,
+                           applicability: Applicability) {
+        self.diagnostic().span_suggestion(sp, msg, suggestion, applicability)
+    }
     pub fn span_bug(&self, sp: Span, msg: &str) -> ! {
         self.diagnostic().span_bug(sp, msg)
     }
@@ -120,13 +130,21 @@ impl Session {
                     id: ast::NodeId,
                     sp: Span,
                     msg: String) {
+        self.add_lint_with_suggestions(lint, id, sp, msg, Vec::new())
+    }
+    pub fn add_lint_with_suggestions(&self,
+                                     lint: &'static lint::Lint,
+                                     id: ast::NodeId,
+                                     sp: Span,
+                                     msg: String,
+                                     suggestions: Vec<CodeSuggestion>) {
         let lint_id = lint::LintId::of(lint);
         let mut lints = self.lints.borrow_mut();
         match lints.find_mut(&id) {
-            Some(arr) => { arr.push((lint_id, sp, msg)); return; }
+            Some(arr) => { arr.push((lint_id, sp, msg, suggestions)); return; }
             None => {}
         }
-        lints.insert(id, vec!((lint_id, sp, msg)));
+        lints.insert(id, vec!((lint_id, sp, msg, suggestions)));
     }
     pub fn next_node_id(&self) -> ast::NodeId {
         self.reserve_node_ids(1)
@@ -178,12 +196,33 @@ impl Session {
     pub fn lto(&self) -> bool {
         self.debugging_opt(config::LTO)
     }
+    pub fn split_debuginfo(&self) -> bool {
+        self.opts.cg.split_debuginfo ||
+            self.opts.output_types.contains(&link::OutputTypeDebugInfo)
+    }
     pub fn no_landing_pads(&self) -> bool {
-        self.debugging_opt(config::NO_LANDING_PADS)
+        self.debugging_opt(config::NO_LANDING_PADS) ||
+            self.opts.cg.fail_strategy == config::FailAbort ||
+            !self.targ_cfg.target.options.unwinding
     }
     pub fn show_span(&self) -> bool {
         self.debugging_opt(config::SHOW_SPAN)
     }
+    pub fn apply_suggestions(&self) -> bool {
+        self.debugging_opt(config::APPLY_SUGGESTIONS)
+    }
+    pub fn incremental_info(&self) -> bool {
+        self.debugging_opt(config::INCREMENTAL_INFO)
+    }
+    pub fn profile(&self) -> bool {
+        self.debugging_opt(config::PROFILE)
+    }
+    pub fn llvm_fn_stats(&self) -> bool {
+        self.debugging_opt(config::LLVM_FN_STATS)
+    }
+    pub fn remap_path(&self, path: &str) -> String {
+        codemap::remap_path_prefix(self.opts.remap_path_prefix.as_slice(), path)
+    }
     pub fn sysroot<'a>(&'a self) -> &'a Path {
         match self.opts.maybe_sysroot {
             Some (ref sysroot) => sysroot,
@@ -209,8 +248,14 @@ pub fn build_session(sopts: config::Options,
                      registry: diagnostics::registry::Registry)
                      -> Session {
     let codemap = codemap::CodeMap::new();
-    let diagnostic_handler =
-        diagnostic::default_handler(sopts.color, Some(registry));
+    let diagnostic_handler = match sopts.error_format {
+        config::HumanReadable => {
+            diagnostic::default_handler(sopts.color, Some(registry))
+        }
+        config::Json => {
+            diagnostic::mk_handler(box diagnostic::JsonEmitter::stderr(Some(registry)))
+        }
+    };
     let span_diagnostic_handler =
         diagnostic::mk_span_handler(diagnostic_handler, codemap);
 
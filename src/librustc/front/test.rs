@@ -154,6 +154,7 @@ fn generate_test_harness(sess: &Session, krate: ast::Crate)
                              ExpansionConfig {
                                  deriving_hash_type_parameter: false,
                                  crate_name: "test".to_string(),
+                                 remap_path_prefix: sess.opts.remap_path_prefix.clone(),
                              }),
         path: RefCell::new(Vec::new()),
         testfns: RefCell::new(Vec::new()),
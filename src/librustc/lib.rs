@@ -58,7 +58,7 @@ pub mod back {
     pub use rustc_back::mipsel;
     pub use rustc_back::rpath;
     pub use rustc_back::svh;
-    pub use rustc_back::target_strs;
+    pub use rustc_back::target;
     pub use rustc_back::x86;
     pub use rustc_back::x86_64;
 
@@ -104,6 +104,7 @@ pub mod middle {
     pub mod save;
     pub mod intrinsicck;
     pub mod stability;
+    pub mod incremental;
 }
 
 pub mod front {
@@ -130,6 +131,7 @@ pub mod util {
     pub mod common;
     pub mod ppaux;
     pub mod nodemap;
+    pub mod time_report;
 }
 
 pub mod lib {
@@ -24,13 +24,17 @@
 //! `add_builtin!` or `add_builtin_with_new!` invocation in `context.rs`.
 //! Use the former for unit-like structs and the latter for structs with
 //! a `pub fn new()`.
+//!
+//! A lint which belongs to a category, such as the naming or "unused"
+//! lints, should also be added to the matching `add_lint_group!`
+//! invocation there.
 
 use metadata::csearch;
 use middle::def::*;
 use middle::trans::adt; // for `adt::is_ffi_safe`
 use middle::typeck::astconv::ast_ty_to_ty;
 use middle::typeck::infer;
-use middle::{typeck, ty, def, pat_util, stability};
+use middle::{const_eval, typeck, ty, def, pat_util, stability};
 use util::ppaux::{ty_to_string};
 use util::nodemap::NodeSet;
 use lint::{Context, LintPass, LintArray};
@@ -44,6 +48,7 @@ use syntax::ast_map;
 use syntax::attr::AttrMetaMethods;
 use syntax::attr;
 use syntax::codemap::Span;
+use syntax::diagnostic::{CodeSuggestion, MachineApplicable, MaybeIncorrect};
 use syntax::parse::token;
 use syntax::{ast, ast_util, visit};
 
@@ -102,6 +107,120 @@ impl LintPass for UnusedCasts {
     }
 }
 
+declare_lint!(LOSSY_CAST, Allow,
+              "detects integer casts which may truncate or change the sign of a value")
+
+pub struct LossyCasts;
+
+impl LintPass for LossyCasts {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(LOSSY_CAST)
+    }
+
+    fn check_expr(&mut self, cx: &Context, e: &ast::Expr) {
+        let (expr, ty) = match e.node {
+            ast::ExprCast(expr, ty) => (expr, ty),
+            _ => return
+        };
+        let t_e = ty::expr_ty(cx.tcx, &*expr);
+        let t_t = ast_ty_to_ty(cx, &infer::new_infer_ctxt(cx.tcx), &*ty);
+        let (e_signed, e_bits, t_signed, t_bits) = match (int_ty_info(cx, t_e),
+                                                          int_ty_info(cx, t_t)) {
+            (Some((es, eb)), Some((ts, tb))) => (es, eb, ts, tb),
+            _ => return
+        };
+
+        // Widening to a type of the same signedness, or to a strictly wider
+        // signed type, keeps every value.
+        if (e_signed == t_signed && t_bits >= e_bits) ||
+           (!e_signed && t_signed && t_bits > e_bits) {
+            return;
+        }
+
+        // Constants which are known to fit are fine as well.
+        match const_eval::eval_const_expr_partial(cx.tcx, &*expr) {
+            Ok(const_eval::const_int(v)) if fits(v < 0, v as u64, t_signed, t_bits) => return,
+            Ok(const_eval::const_uint(v)) if fits(false, v, t_signed, t_bits) => return,
+            _ => {}
+        }
+
+        let t_name = ty_to_string(cx.tcx, t_t);
+        let msg = format!("cast from `{}` to `{}` may {} the value",
+                          ty_to_string(cx.tcx, t_e), t_name,
+                          if t_bits < e_bits { "truncate" } else { "change the sign of" });
+        // `to_*()` comes from `ToPrimitive` in the prelude, which `#![no_std]`
+        // crates don't have. It also makes the program fail where the cast
+        // didn't, so it is only ever offered, never applied automatically.
+        let has_prelude = !attr::contains_name(cx.krate.attrs.as_slice(), "no_std");
+        let snippet = cx.tcx.sess.codemap().span_to_snippet(expr.span);
+        let suggestions = match snippet {
+            Some(snippet) if has_prelude => {
+                let receiver = match expr.node {
+                    ast::ExprPath(..) | ast::ExprField(..) | ast::ExprMethodCall(..) |
+                    ast::ExprCall(..) | ast::ExprIndex(..) | ast::ExprParen(..) |
+                    ast::ExprLit(..) => snippet,
+                    _ => format!("({})", snippet)
+                };
+                vec!(CodeSuggestion {
+                    span: e.span,
+                    msg: format!("if the value must fit, `to_{}()` fails where \
+                                  the cast would lose it", t_name),
+                    replacement: format!("{}.to_{}().unwrap()", receiver, t_name),
+                    applicability: MaybeIncorrect,
+                })
+            }
+            _ => Vec::new()
+        };
+        cx.span_lint_with_suggestions(LOSSY_CAST, e.span, msg.as_slice(),
+                                      suggestions.as_slice());
+
+        /// The signedness and width in bits of an integer type.
+        fn int_ty_info(cx: &Context, t: ty::t) -> Option<(bool, uint)> {
+            match ty::get(t).sty {
+                ty::ty_int(t) => Some((true, match t {
+                    ast::TyI => int_ty_bits(cx.sess().targ_cfg.int_type),
+                    t => int_ty_bits(t)
+                })),
+                ty::ty_uint(t) => Some((false, match t {
+                    ast::TyU => uint_ty_bits(cx.sess().targ_cfg.uint_type),
+                    t => uint_ty_bits(t)
+                })),
+                _ => None
+            }
+        }
+
+        fn int_ty_bits(t: ast::IntTy) -> uint {
+            match t {
+                ast::TyI8 => 8,
+                ast::TyI16 => 16,
+                ast::TyI32 => 32,
+                ast::TyI | ast::TyI64 => 64
+            }
+        }
+
+        fn uint_ty_bits(t: ast::UintTy) -> uint {
+            match t {
+                ast::TyU8 => 8,
+                ast::TyU16 => 16,
+                ast::TyU32 => 32,
+                ast::TyU | ast::TyU64 => 64
+            }
+        }
+
+        /// Whether a value, given as its sign and its bits, fits in the
+        /// target type.
+        fn fits(negative: bool, bits: u64, signed: bool, width: uint) -> bool {
+            if negative {
+                signed && (width == 64 || (bits as i64) >= -(1i64 << (width - 1)))
+            } else if signed {
+                bits <= (1u64 << (width - 1)) - 1
+            } else {
+                width == 64 || bits < (1u64 << width)
+            }
+        }
+    }
+}
+
 declare_lint!(UNSIGNED_NEGATE, Warn,
               "using an unary minus operator on unsigned type")
 
@@ -600,6 +719,7 @@ impl LintPass for UnusedAttribute {
             "license",
             "copyright",
             "no_builtins",
+            "export",
         ];
 
         for &name in ATTRIBUTE_WHITELIST.iter() {
@@ -1138,13 +1258,15 @@ impl UnusedMut {
         // avoid false warnings in match arms with multiple patterns
         let mut mutables = HashMap::new();
         for &p in pats.iter() {
-            pat_util::pat_bindings(&cx.tcx.def_map, &*p, |mode, id, _, path1| {
+            pat_util::pat_bindings(&cx.tcx.def_map, &*p, |mode, id, sp, path1| {
                 let ident = path1.node;
                 match mode {
                     ast::BindByValue(ast::MutMutable) => {
                         if !token::get_ident(ident).get().starts_with("_") {
+                            // The span of the `mut ` in front of the identifier.
+                            let mut_sp = Span { hi: path1.span.lo, ..sp };
                             mutables.insert_or_update_with(ident.name.uint(),
-                                vec!(id), |_, old| { old.push(id); });
+                                vec!((id, mut_sp)), |_, old| { old.push((id, mut_sp)); });
                         }
                     }
                     _ => {
@@ -1155,9 +1277,19 @@ impl UnusedMut {
 
         let used_mutables = cx.tcx.used_mut_nodes.borrow();
         for (_, v) in mutables.iter() {
-            if !v.iter().any(|e| used_mutables.contains(e)) {
-                cx.span_lint(UNUSED_MUT, cx.tcx.map.span(*v.get(0)),
-                             "variable does not need to be mutable");
+            if !v.iter().any(|&(e, _)| used_mutables.contains(&e)) {
+                let suggestions: Vec<CodeSuggestion> = v.iter().map(|&(_, mut_sp)| {
+                    CodeSuggestion {
+                        span: mut_sp,
+                        msg: "remove this `mut`".to_string(),
+                        replacement: "".to_string(),
+                        applicability: MachineApplicable,
+                    }
+                }).collect();
+                let (first, _) = *v.get(0);
+                cx.span_lint_with_suggestions(UNUSED_MUT, cx.tcx.map.span(first),
+                                              "variable does not need to be mutable",
+                                              suggestions.as_slice());
             }
         }
     }
@@ -1533,6 +1665,9 @@ declare_lint!(pub UNKNOWN_CRATE_TYPE, Deny,
 declare_lint!(pub VARIANT_SIZE_DIFFERENCE, Allow,
               "detects enums with widely varying variant sizes")
 
+declare_lint!(pub UNUSED_PUB_ITEMS, Warn,
+              "detects public items which no crate given with --downstream-analysis uses")
+
 /// Does nothing as a lint pass, but registers some `Lint`s
 /// which are used by other parts of the compiler.
 pub struct HardwiredLints;
@@ -1551,7 +1686,8 @@ impl LintPass for HardwiredLints {
             WARNINGS,
             UNKNOWN_FEATURES,
             UNKNOWN_CRATE_TYPE,
-            VARIANT_SIZE_DIFFERENCE
+            VARIANT_SIZE_DIFFERENCE,
+            UNUSED_PUB_ITEMS
         )
     }
 }
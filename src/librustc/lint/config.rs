@@ -0,0 +1,169 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Lint policy files, given with `--lint-config FILE`.
+//!
+//! A policy file sets lint levels for every crate compiled with it, so that
+//! the crates of a project can share one policy. It is written in a subset
+//! of TOML:
+//!
+//! ```toml
+//! # Levels for the whole crate.
+//! [lints]
+//! unsafe_block = "deny"
+//! missing-doc = "warn"
+//!
+//! # Levels for the source files under a directory.
+//! [[paths]]
+//! path = "src/ffi"
+//! [paths.lints]
+//! unsafe_block = "allow"
+//! ```
+//!
+//! Lint names may be written with dashes or underscores and may name lint
+//! groups, as on the command line. The levels in `[lints]` are set before
+//! those given with `-A`, `-W`, `-D` and `-F`, so the command line takes
+//! precedence over the file.
+//!
+//! The levels of a `[[paths]]` entry act as if they were given in attributes
+//! on each item in the files under `path`, which is relative to the directory
+//! of the policy file. Like attributes, they can't lower the level of a
+//! forbidden lint. Unlike them, they don't override the command line either.
+//! When several entries match a file, the one with the longest `path` takes
+//! precedence.
+
+use lint::Level;
+
+use std::io::File;
+
+/// The contents of a lint policy file.
+pub struct LintConfig {
+    /// Levels for the whole crate, in the order they were given.
+    pub lints: Vec<(String, Level)>,
+
+    /// Levels for the files under certain paths.
+    pub paths: Vec<PathLints>,
+}
+
+/// The levels of a `[[paths]]` entry.
+pub struct PathLints {
+    pub path: Path,
+    pub lints: Vec<(String, Level)>,
+}
+
+/// Read and parse the policy file at `path`.
+pub fn read(path: &Path) -> Result<LintConfig, String> {
+    let contents = match File::open(path).read_to_string() {
+        Ok(contents) => contents,
+        Err(e) => return Err(format!("{}: {}", path.display(), e)),
+    };
+    parse(contents.as_slice()).map_err(|e| format!("{}:{}", path.display(), e))
+}
+
+enum Section {
+    NoSection,
+    Lints,
+    PathEntry,
+    PathEntryLints,
+}
+
+/// Parse the contents of a policy file. Errors start with the line number.
+pub fn parse(contents: &str) -> Result<LintConfig, String> {
+    let mut lints = Vec::new();
+    let mut paths: Vec<(Option<Path>, Vec<(String, Level)>)> = Vec::new();
+    let mut section = NoSection;
+
+    for (i, line) in contents.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        let err = |msg: String| Err(format!("{}: {}", i + 1, msg));
+        if line.is_empty() {
+            continue
+        }
+
+        if line.starts_with("[[") {
+            if !line.ends_with("]]") {
+                return err("expected `]]` at the end of the line".to_string());
+            }
+            match line.slice(2, line.len() - 2).trim() {
+                "paths" => {
+                    paths.push((None, Vec::new()));
+                    section = PathEntry;
+                }
+                name => return err(format!("unknown array of tables `{}`", name)),
+            }
+        } else if line.starts_with("[") {
+            if !line.ends_with("]") {
+                return err("expected `]` at the end of the line".to_string());
+            }
+            match line.slice(1, line.len() - 1).trim() {
+                "lints" => section = Lints,
+                "paths.lints" if !paths.is_empty() => section = PathEntryLints,
+                "paths.lints" => {
+                    return err("`[paths.lints]` must follow a `[[paths]]` entry".to_string());
+                }
+                name => return err(format!("unknown table `{}`", name)),
+            }
+        } else {
+            let (key, value) = match line.find('=') {
+                Some(eq) => (line.slice_to(eq).trim(), line.slice_from(eq + 1).trim()),
+                None => return err("expected `key = \"value\"`".to_string()),
+            };
+            if value.len() < 2 || !value.starts_with("\"") || !value.ends_with("\"") {
+                return err(format!("the value of `{}` must be a string", key));
+            }
+            let value = value.slice(1, value.len() - 1);
+
+            let lint = || {
+                match Level::from_str(value) {
+                    Some(level) => Ok((key.replace("-", "_"), level)),
+                    None => Err(format!("{}: unknown lint level `{}`", i + 1, value)),
+                }
+            };
+            match section {
+                NoSection => return err(format!("`{}` is not in a table", key)),
+                Lints => lints.push(try!(lint())),
+                PathEntryLints => match paths.mut_last() {
+                    Some(&(_, ref mut levels)) => levels.push(try!(lint())),
+                    None => unreachable!(),
+                },
+                PathEntry if key == "path" => match paths.mut_last() {
+                    Some(&(ref mut path, _)) => *path = Some(Path::new(value)),
+                    None => unreachable!(),
+                },
+                PathEntry => return err(format!("unknown key `{}` in `[[paths]]`", key)),
+            }
+        }
+    }
+
+    let mut path_lints = Vec::new();
+    for (path, lints) in paths.move_iter() {
+        match path {
+            Some(path) => path_lints.push(PathLints { path: path, lints: lints }),
+            None => return Err("a `[[paths]]` entry has no `path`".to_string()),
+        }
+    }
+    Ok(LintConfig {
+        lints: lints,
+        paths: path_lints,
+    })
+}
+
+/// Remove a `#` comment from the end of a line, unless it is in a string.
+fn strip_comment<'a>(line: &'a str) -> &'a str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return line.slice_to(i),
+            _ => {}
+        }
+    }
+    line
+}
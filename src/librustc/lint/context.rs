@@ -31,18 +31,20 @@ use middle::typeck::infer;
 use driver::session::Session;
 use driver::early_error;
 use lint::{Level, LevelSource, Lint, LintId, LintArray, LintPass, LintPassObject};
-use lint::{Default, CommandLine, Node, Allow, Warn, Deny, Forbid};
-use lint::builtin;
+use lint::{Default, CommandLine, ConfigFile, Node, Allow, Warn, Deny, Forbid};
+use lint::{builtin, config};
 
 use std::collections::HashMap;
 use std::rc::Rc;
 use std::cell::RefCell;
 use std::tuple::Tuple2;
 use std::mem;
+use std::os;
 use syntax::ast_util::IdVisitingOperation;
 use syntax::attr::AttrMetaMethods;
 use syntax::attr;
 use syntax::codemap::Span;
+use syntax::diagnostic::CodeSuggestion;
 use syntax::visit::{Visitor, FnKind};
 use syntax::parse::token::InternedString;
 use syntax::{ast, ast_util, visit};
@@ -66,6 +68,14 @@ pub struct LintStore {
 
     /// Current levels of each lint, and where they were set.
     levels: HashMap<LintId, LevelSource>,
+
+    /// Lint groups, which set the level of several lints at once. The bool
+    /// is true if the group was added by a plugin.
+    lint_groups: HashMap<String, (Vec<LintId>, bool)>,
+
+    /// Levels set by the `--lint-config` file for the source files under
+    /// each path, with the most specific path last.
+    path_levels: Vec<(Path, Vec<(LintId, Level)>)>,
 }
 
 impl LintStore {
@@ -90,6 +100,8 @@ impl LintStore {
             passes: Some(vec!()),
             by_name: HashMap::new(),
             levels: HashMap::new(),
+            lint_groups: HashMap::new(),
+            path_levels: vec!(),
         }
     }
 
@@ -97,23 +109,39 @@ impl LintStore {
         self.lints.as_slice()
     }
 
+    /// Get the lint groups, sorted by name, with the lints in each group
+    /// and whether the group was added by a plugin.
+    pub fn get_lint_groups<'t>(&'t self) -> Vec<(&'t str, &'t [LintId], bool)> {
+        let mut groups: Vec<(&'t str, &'t [LintId], bool)> =
+            self.lint_groups.iter().map(|(name, &(ref lints, from_plugin))| {
+                (name.as_slice(), lints.as_slice(), from_plugin)
+            }).collect();
+        groups.sort_by(|&(a, _, _), &(b, _, _)| a.cmp(&b));
+        groups
+    }
+
+    fn report_duplicate(sess: Option<&Session>, from_plugin: bool, msg: &str) {
+        match (sess, from_plugin) {
+            // We load builtin lints first, so a duplicate is a compiler bug.
+            // Use early_error when handling -W help with no crate.
+            (None, _) => early_error(msg),
+            (Some(sess), false) => sess.bug(msg),
+
+            // A duplicate name from a plugin is a user error.
+            (Some(sess), true)  => sess.err(msg),
+        }
+    }
+
     pub fn register_pass(&mut self, sess: Option<&Session>,
                          from_plugin: bool, pass: LintPassObject) {
         for &lint in pass.get_lints().iter() {
             self.lints.push((lint, from_plugin));
 
             let id = LintId::of(lint);
-            if !self.by_name.insert(lint.name_lower(), id) {
+            if self.lint_groups.contains_key(&lint.name_lower()) ||
+               !self.by_name.insert(lint.name_lower(), id) {
                 let msg = format!("duplicate specification of lint {}", lint.name_lower());
-                match (sess, from_plugin) {
-                    // We load builtin lints first, so a duplicate is a compiler bug.
-                    // Use early_error when handling -W help with no crate.
-                    (None, _) => early_error(msg.as_slice()),
-                    (Some(sess), false) => sess.bug(msg.as_slice()),
-
-                    // A duplicate name from a plugin is a user error.
-                    (Some(sess), true)  => sess.err(msg.as_slice()),
-                }
+                LintStore::report_duplicate(sess, from_plugin, msg.as_slice());
             }
 
             if lint.default_level != Allow {
@@ -123,6 +151,16 @@ impl LintStore {
         self.passes.get_mut_ref().push(pass);
     }
 
+    pub fn register_group(&mut self, sess: Option<&Session>, from_plugin: bool,
+                          name: &str, to: Vec<LintId>) {
+        let name = name.replace("-", "_");
+        if self.by_name.contains_key(&name) ||
+           !self.lint_groups.insert(name.clone(), (to, from_plugin)) {
+            let msg = format!("duplicate specification of lint group {}", name);
+            LintStore::report_duplicate(sess, from_plugin, msg.as_slice());
+        }
+    }
+
     pub fn register_builtin(&mut self, sess: Option<&Session>) {
         macro_rules! add_builtin ( ( $sess:ident, $($name:ident),*, ) => (
             {$(
@@ -140,6 +178,7 @@ impl LintStore {
                      HardwiredLints,
                      WhileTrue,
                      UnusedCasts,
+                     LossyCasts,
                      CTypes,
                      HeapMemory,
                      UnusedAttribute,
@@ -164,19 +203,113 @@ impl LintStore {
                               MissingDoc,
         )
 
+        macro_rules! add_lint_group ( ( $sess:ident, $name:expr, $($lint:ident),* ) => (
+            self.register_group($sess, false, $name, vec!($(LintId::of(builtin::$lint)),*));
+        ))
+
+        add_lint_group!(sess, "bad_style",
+                        NON_CAMEL_CASE_TYPES, NON_SNAKE_CASE_FUNCTIONS,
+                        NON_UPPERCASE_STATICS, NON_UPPERCASE_PATTERN_STATICS,
+                        UPPERCASE_VARIABLES)
+
+        add_lint_group!(sess, "unused",
+                        UNUSED_IMPORTS, UNUSED_VARIABLE, DEAD_ASSIGNMENT, DEAD_CODE,
+                        UNUSED_MUT, UNREACHABLE_CODE, UNUSED_MUST_USE, UNUSED_UNSAFE,
+                        UNUSED_ATTRIBUTE, PATH_STATEMENT, UNUSED_PUB_ITEMS)
+
         // We have one lint pass defined in this module.
         self.register_pass(sess, false, box GatherNodeLevels as LintPassObject);
     }
 
     pub fn process_command_line(&mut self, sess: &Session) {
+        match sess.opts.lint_config {
+            Some(ref path) => self.process_config_file(sess, path),
+            None => {}
+        }
+
         for &(ref lint_name, level) in sess.opts.lint_opts.iter() {
-            match self.by_name.find_equiv(&lint_name.as_slice()) {
-                Some(&lint_id) => self.set_level(lint_id, (level, CommandLine)),
+            match self.find_lints(lint_name.as_slice()) {
+                Some(lint_ids) => {
+                    for &lint_id in lint_ids.iter() {
+                        self.set_level(lint_id, (level, CommandLine));
+                    }
+                }
                 None => sess.err(format!("unknown {} flag: {}",
                                          level.as_str(), lint_name).as_slice()),
             }
         }
     }
+
+    /// Look up a lint name given in an attribute, on the command line or in
+    /// the `--lint-config` file. This is either a lint or a lint group.
+    fn find_lints(&self, lint_name: &str) -> Option<Vec<LintId>> {
+        match self.by_name.find_equiv(&lint_name) {
+            Some(&lint_id) => Some(vec!(lint_id)),
+            None => self.lint_groups.find_equiv(&lint_name).map(|&(ref lints, _)| {
+                lints.clone()
+            }),
+        }
+    }
+
+    fn process_config_file(&mut self, sess: &Session, path: &Path) {
+        let lint_config = match config::read(path) {
+            Ok(lint_config) => lint_config,
+            Err(e) => {
+                sess.err(format!("failed to read lint config {}", e).as_slice());
+                return;
+            }
+        };
+
+        let (levels, mut path_levels) = {
+            let lookup = |lints: &Vec<(String, Level)>| {
+                let mut levels = vec!();
+                for &(ref lint_name, level) in lints.iter() {
+                    match self.find_lints(lint_name.as_slice()) {
+                        Some(lint_ids) => {
+                            for &lint_id in lint_ids.iter() {
+                                levels.push((lint_id, level));
+                            }
+                        }
+                        None => sess.err(format!("unknown lint in {}: {}",
+                                                 path.display(), lint_name).as_slice()),
+                    }
+                }
+                levels
+            };
+            // Paths are relative to the directory of the policy file.
+            let dir = os::make_absolute(&path.dir_path());
+            let mut path_levels = vec!();
+            for entry in lint_config.paths.iter() {
+                path_levels.push((dir.join(&entry.path), lookup(&entry.lints)));
+            }
+            (lookup(&lint_config.lints), path_levels)
+        };
+        path_levels.sort_by(|&(ref a, _), &(ref b, _)| {
+            a.components().count().cmp(&b.components().count())
+        });
+
+        for (lint_id, level) in levels.move_iter() {
+            self.set_level(lint_id, (level, ConfigFile));
+        }
+        self.path_levels = path_levels;
+    }
+
+    /// The levels set by the `--lint-config` file for the source file
+    /// containing `span`.
+    fn levels_for_path(&self, sess: &Session, span: Span) -> Vec<(LintId, Level)> {
+        if self.path_levels.is_empty() {
+            return vec!();
+        }
+        let file = sess.codemap().span_to_filename(span);
+        let file = os::make_absolute(&Path::new(file.as_slice()));
+        let mut levels = vec!();
+        for &(ref path, ref path_levels) in self.path_levels.iter() {
+            if path.is_ancestor_of(&file) {
+                levels.push_all(path_levels.as_slice());
+            }
+        }
+        levels
+    }
 }
 
 /// Context for lint checking.
@@ -201,6 +334,10 @@ pub struct Context<'a> {
     /// Level of lints for certain NodeIds, stored here because the body of
     /// the lint needs to run in trans.
     node_levels: RefCell<HashMap<(ast::NodeId, LintId), LevelSource>>,
+
+    /// The source file of the node being checked, whose `--lint-config`
+    /// levels are in effect.
+    cur_file: Option<String>,
 }
 
 /// Convenience macro for calling a `LintPass` method on every pass in the context.
@@ -272,6 +409,11 @@ pub fn raw_emit_lint(sess: &Session, lint: &'static Lint,
                         Allow => fail!()
                     }, name.replace("_", "-"))
         },
+        ConfigFile => {
+            format!("{} [{} = \"{}\" in {}]", msg, name, level.as_str(),
+                    sess.opts.lint_config.as_ref().map_or(String::new(),
+                                                          |p| p.display().to_string()))
+        },
         Node(src) => {
             note = Some(src);
             msg.to_string()
@@ -309,6 +451,7 @@ impl<'a> Context<'a> {
             lints: lint_store,
             level_stack: vec!(),
             node_levels: RefCell::new(HashMap::new()),
+            cur_file: None,
         }
     }
 
@@ -323,9 +466,10 @@ impl<'a> Context<'a> {
         self.lints.levels.find(&LintId::of(lint)).map_or(Allow, |&(lvl, _)| lvl)
     }
 
-    fn lookup_and_emit(&self, lint: &'static Lint, span: Option<Span>, msg: &str) {
+    fn lookup_and_emit(&self, lint: &'static Lint, span: Option<Span>, msg: &str)
+                       -> Level {
         let (level, src) = match self.lints.levels.find(&LintId::of(lint)) {
-            None => return,
+            None => return Allow,
             Some(&(Warn, src)) => {
                 let lint_id = LintId::of(builtin::WARNINGS);
                 (self.lints.get_level_source(lint_id).val0(), src)
@@ -334,6 +478,7 @@ impl<'a> Context<'a> {
         };
 
         raw_emit_lint(&self.tcx.sess, lint, (level, src), span, msg);
+        level
     }
 
     /// Emit a lint at the appropriate level, with no associated span.
@@ -346,6 +491,16 @@ impl<'a> Context<'a> {
         self.lookup_and_emit(lint, Some(span), msg);
     }
 
+    /// Emit a lint at the appropriate level, for a particular span, followed
+    /// by suggestions on how to fix it. The suggestions are dropped along
+    /// with the lint if it is allowed.
+    pub fn span_lint_with_suggestions(&self, lint: &'static Lint, span: Span, msg: &str,
+                                      suggestions: &[CodeSuggestion]) {
+        if self.lookup_and_emit(lint, Some(span), msg) != Allow {
+            self.tcx.sess.diagnostic().span_suggestions(suggestions);
+        }
+    }
+
     /**
      * Merge the lints specified by any lint attributes into the
      * current lint context, call the provided function, then reset the
@@ -361,14 +516,14 @@ impl<'a> Context<'a> {
         let mut pushed = 0u;
 
         for result in gather_attrs(attrs).move_iter() {
-            let (lint_id, level, span) = match result {
+            let (lint_ids, level, span) = match result {
                 Err(span) => {
                     self.tcx.sess.span_err(span, "malformed lint attribute");
                     continue;
                 }
                 Ok((lint_name, level, span)) => {
-                    match self.lints.by_name.find_equiv(&lint_name.get()) {
-                        Some(&lint_id) => (lint_id, level, span),
+                    match self.lints.find_lints(lint_name.get()) {
+                        Some(lint_ids) => (lint_ids, level, span),
                         None => {
                             self.span_lint(builtin::UNRECOGNIZED_LINT, span,
                                            format!("unknown `{}` attribute: `{}`",
@@ -379,17 +534,20 @@ impl<'a> Context<'a> {
                 }
             };
 
-            let now = self.lints.get_level_source(lint_id).val0();
-            if now == Forbid && level != Forbid {
-                let lint_name = lint_id.as_str();
-                self.tcx.sess.span_err(span,
-                                       format!("{}({}) overruled by outer forbid({})",
-                                               level.as_str(), lint_name, lint_name).as_slice());
-            } else if now != level {
-                let src = self.lints.get_level_source(lint_id).val1();
-                self.level_stack.push((lint_id, (now, src)));
-                pushed += 1;
-                self.lints.set_level(lint_id, (level, Node(span)));
+            for lint_id in lint_ids.move_iter() {
+                let now = self.lints.get_level_source(lint_id).val0();
+                if now == Forbid && level != Forbid {
+                    let lint_name = lint_id.as_str();
+                    self.tcx.sess.span_err(span,
+                                           format!("{}({}) overruled by outer forbid({})",
+                                                   level.as_str(), lint_name,
+                                                   lint_name).as_slice());
+                } else if now != level {
+                    let src = self.lints.get_level_source(lint_id).val1();
+                    self.level_stack.push((lint_id, (now, src)));
+                    pushed += 1;
+                    self.lints.set_level(lint_id, (level, Node(span)));
+                }
             }
         }
 
@@ -404,6 +562,42 @@ impl<'a> Context<'a> {
         }
     }
 
+    /// Merge the levels set by the `--lint-config` file for the source file
+    /// containing `span` into the current lint context, call the provided
+    /// function, then reset the lints in effect to their previous state.
+    ///
+    /// This treats the levels like attributes on the outermost node of each
+    /// source file, except that they silently leave forbidden lints and the
+    /// levels given on the command line alone. Nodes in the same file as
+    /// their parent keep its levels, so the attributes of enclosing modules
+    /// still apply to them.
+    fn with_path_levels(&mut self, span: Span, f: |&mut Context|) {
+        let file = self.tcx.sess.codemap().span_to_filename(span);
+        if self.cur_file.as_ref() == Some(&file) {
+            f(self);
+            return
+        }
+        let prev_file = mem::replace(&mut self.cur_file, Some(file));
+        let mut pushed = 0u;
+
+        for (lint_id, level) in self.lints.levels_for_path(&self.tcx.sess, span).move_iter() {
+            let (now, src) = self.lints.get_level_source(lint_id);
+            if now != Forbid && now != level && src != CommandLine {
+                self.level_stack.push((lint_id, (now, src)));
+                pushed += 1;
+                self.lints.set_level(lint_id, (level, ConfigFile));
+            }
+        }
+
+        f(self);
+
+        for _ in range(0, pushed) {
+            let (lint, lvlsrc) = self.level_stack.pop().unwrap();
+            self.lints.set_level(lint, lvlsrc);
+        }
+        self.cur_file = prev_file;
+    }
+
     fn visit_ids(&self, f: |&mut ast_util::IdVisitor<Context>|) {
         let mut v = ast_util::IdVisitor {
             operation: self,
@@ -432,10 +626,18 @@ impl<'a> AstConv for Context<'a>{
 
 impl<'a> Visitor<()> for Context<'a> {
     fn visit_item(&mut self, it: &ast::Item, _: ()) {
-        self.with_lint_attrs(it.attrs.as_slice(), |cx| {
-            run_lints!(cx, check_item, it);
-            cx.visit_ids(|v| v.visit_item(it, ()));
-            visit::walk_item(cx, it, ());
+        // The span of `mod foo;` is in the parent file, and that of its
+        // contents in the module's own.
+        let span = match it.node {
+            ast::ItemMod(ref m) => m.inner,
+            _ => it.span,
+        };
+        self.with_path_levels(span, |cx| {
+            cx.with_lint_attrs(it.attrs.as_slice(), |cx| {
+                run_lints!(cx, check_item, it);
+                cx.visit_ids(|v| v.visit_item(it, ()));
+                visit::walk_item(cx, it, ());
+            })
         })
     }
 
@@ -606,8 +808,9 @@ impl<'a> IdVisitingOperation for Context<'a> {
         match self.tcx.sess.lints.borrow_mut().pop(&id) {
             None => {}
             Some(lints) => {
-                for (lint_id, span, msg) in lints.move_iter() {
-                    self.span_lint(lint_id.lint, span, msg.as_slice())
+                for (lint_id, span, msg, suggestions) in lints.move_iter() {
+                    self.span_lint_with_suggestions(lint_id.lint, span, msg.as_slice(),
+                                                    suggestions.as_slice())
                 }
             }
         }
@@ -652,7 +855,7 @@ pub fn check_crate(tcx: &ty::ctxt,
     let mut cx = Context::new(tcx, krate, exported_items);
 
     // Visit the whole crate.
-    cx.with_lint_attrs(krate.attrs.as_slice(), |cx| {
+    cx.with_path_levels(krate.span, |cx| cx.with_lint_attrs(krate.attrs.as_slice(), |cx| {
         cx.visit_id(ast::CRATE_NODE_ID);
         cx.visit_ids(|v| {
             v.visited_outermost = true;
@@ -664,12 +867,12 @@ pub fn check_crate(tcx: &ty::ctxt,
         run_lints!(cx, check_crate, krate);
 
         visit::walk_crate(cx, krate, ());
-    });
+    }));
 
     // If we missed any lints added to the session, then there's a bug somewhere
     // in the iteration code.
     for (id, v) in tcx.sess.lints.borrow().iter() {
-        for &(lint, span, ref msg) in v.iter() {
+        for &(lint, span, ref msg, _) in v.iter() {
             tcx.sess.span_bug(span,
                               format!("unprocessed lint {} at {}: {}",
                                       lint.as_str(), tcx.map.node_to_string(*id), *msg).as_slice())
@@ -244,10 +244,14 @@ pub enum LintSource {
 
     /// Lint level was set by a command-line flag.
     CommandLine,
+
+    /// Lint level was set by the `--lint-config` file.
+    ConfigFile,
 }
 
 pub type LevelSource = (Level, LintSource);
 
 pub mod builtin;
+pub mod config;
 
 mod context;
@@ -211,6 +211,14 @@ pub static tag_reachable_extern_fn_id: uint = 0x91;
 
 pub static tag_items_data_item_stability: uint = 0x92;
 
+pub static tag_fail_strategy: uint = 0x95;
+
+pub static tag_exported_symbols: uint = 0x96;
+pub static tag_exported_symbol: uint = 0x97;
+
+pub static tag_export_list: uint = 0x98;
+pub static tag_export_list_item: uint = 0x99;
+
 
 #[deriving(Clone, Show)]
 pub struct LinkMeta {
@@ -13,8 +13,10 @@
 //! Validates all used crates and extern libraries and loads their metadata
 
 use back::svh::Svh;
+use back::target::Target;
+use driver::config;
 use driver::session::Session;
-use driver::{driver, config};
+use driver::driver;
 use metadata::cstore;
 use metadata::cstore::{CStore, CrateSource};
 use metadata::decoder;
@@ -228,8 +230,7 @@ fn visit_item(e: &Env, i: &ast::Item) {
                             Some(k) => {
                                 if k.equiv(&("static")) {
                                     cstore::NativeStatic
-                                } else if (e.sess.targ_cfg.os == abi::OsMacos ||
-                                           e.sess.targ_cfg.os == abi::OsiOS) &&
+                                } else if e.sess.targ_cfg.target.options.is_like_osx &&
                                           k.equiv(&("framework")) {
                                     cstore::NativeFramework
                                 } else if k.equiv(&("framework")) {
@@ -336,6 +337,20 @@ fn register_crate<'a>(e: &mut Env,
     // Maintain a reference to the top most crate.
     let root = if root.is_some() { root } else { &crate_paths };
 
+    // Unwinding through a crate compiled without landing pads would skip its
+    // cleanups, so a crate using the unwind strategy can't depend on one
+    // using abort. The other way around is fine: the final crate sends the
+    // failures of unwinding crates to the `abort` lang item.
+    let fail_strategy = decoder::get_fail_strategy(lib.metadata.as_slice());
+    if e.sess.opts.cg.fail_strategy == config::FailUnwind &&
+       fail_strategy.as_slice() == "abort" {
+        e.sess.span_err(span,
+                        format!("the crate `{}` is compiled with the fail strategy `{}`, \
+                                 which is incompatible with this crate's strategy `{}`",
+                                name, fail_strategy,
+                                e.sess.opts.cg.fail_strategy).as_slice());
+    }
+
     let cnum_map = resolve_crate_deps(e, root, lib.metadata.as_slice(), span);
 
     let loader::Library{ dylib, rlib, metadata } = lib;
@@ -376,8 +391,8 @@ fn resolve_crate<'a>(e: &mut Env,
                 crate_name: name,
                 hash: hash.map(|a| &*a),
                 filesearch: e.sess.target_filesearch(),
-                os: e.sess.targ_cfg.os,
-                triple: e.sess.targ_cfg.target_strs.target_triple.as_slice(),
+                target: &e.sess.targ_cfg.target,
+                triple: e.sess.targ_cfg.target.name.as_slice(),
                 root: root,
                 rejected_via_hash: vec!(),
                 rejected_via_triple: vec!(),
@@ -427,10 +442,10 @@ impl<'a> PluginMetadataReader<'a> {
 
     pub fn read_plugin_metadata(&mut self, krate: &ast::ViewItem) -> PluginMetadata {
         let info = extract_crate_info(&self.env, krate).unwrap();
-        let target_triple = self.env.sess.targ_cfg.target_strs.target_triple.as_slice();
+        let target_triple = self.env.sess.targ_cfg.target.name.as_slice();
         let is_cross = target_triple != driver::host_triple();
         let mut should_link = info.should_link && !is_cross;
-        let os = config::get_os(driver::host_triple()).unwrap();
+        let host = Target::from_triple(driver::host_triple()).unwrap();
         let mut load_ctxt = loader::Context {
             sess: self.env.sess,
             span: krate.span,
@@ -439,7 +454,7 @@ impl<'a> PluginMetadataReader<'a> {
             hash: None,
             filesearch: self.env.sess.host_filesearch(),
             triple: driver::host_triple(),
-            os: os,
+            target: &host,
             root: &None,
             rejected_via_hash: vec!(),
             rejected_via_triple: vec!(),
@@ -451,7 +466,7 @@ impl<'a> PluginMetadataReader<'a> {
                 // try loading from target crates (only valid if there are
                 // no syntax extensions)
                 load_ctxt.triple = target_triple;
-                load_ctxt.os = self.env.sess.targ_cfg.os;
+                load_ctxt.target = &self.env.sess.targ_cfg.target;
                 load_ctxt.filesearch = self.env.sess.target_filesearch();
                 let lib = load_ctxt.load_library_crate();
                 if decoder::get_plugin_registrar_fn(lib.metadata.as_slice()).is_some() {
@@ -326,6 +326,25 @@ pub fn get_reachable_extern_fns(cstore: &cstore::CStore, cnum: ast::CrateNum)
     decoder::get_reachable_extern_fns(&*cdata)
 }
 
+pub fn get_exported_symbols(cstore: &cstore::CStore, cnum: ast::CrateNum)
+    -> Vec<String>
+{
+    let cdata = cstore.get_crate_data(cnum);
+    decoder::get_exported_symbols(&*cdata)
+}
+
+pub fn get_export_list(cstore: &cstore::CStore, cnum: ast::CrateNum)
+    -> Option<Vec<ast::NodeId>>
+{
+    let cdata = cstore.get_crate_data(cnum);
+    decoder::get_export_list(&*cdata)
+}
+
+pub fn is_inlined_item(cstore: &cstore::CStore, did: ast::DefId) -> bool {
+    let cdata = cstore.get_crate_data(did.krate);
+    decoder::is_inlined_item(&*cdata, did.node)
+}
+
 pub fn is_typedef(cstore: &cstore::CStore, did: ast::DefId) -> bool {
     let cdata = cstore.get_crate_data(did.krate);
     decoder::is_typedef(&*cdata, did.node)
@@ -1146,6 +1146,16 @@ pub fn get_crate_triple(data: &[u8]) -> Option<String> {
     triple_doc.map(|s| s.as_str().to_string())
 }
 
+/// The fail strategy the crate was compiled with. Crates from before the
+/// strategy was recorded always unwind.
+pub fn get_fail_strategy(data: &[u8]) -> String {
+    let cratedoc = ebml::Doc::new(data);
+    match reader::maybe_get_doc(cratedoc, tag_fail_strategy) {
+        Some(doc) => doc.as_str().to_string(),
+        None => "unwind".to_string(),
+    }
+}
+
 pub fn get_crate_name(data: &[u8]) -> String {
     maybe_get_crate_name(data).expect("no crate name in crate")
 }
@@ -1335,6 +1345,40 @@ pub fn get_reachable_extern_fns(cdata: Cmd) -> Vec<ast::DefId> {
     return ret;
 }
 
+pub fn get_exported_symbols(cdata: Cmd) -> Vec<String> {
+    let mut ret = Vec::new();
+    let symbols = match reader::maybe_get_doc(ebml::Doc::new(cdata.data()),
+                                              tag_exported_symbols) {
+        Some(doc) => doc,
+        None => return ret,
+    };
+    reader::tagged_docs(symbols, tag_exported_symbol, |doc| {
+        ret.push(doc.as_str().to_string());
+        true
+    });
+    return ret;
+}
+
+/// The items the crate's dynamic library exports, if it restricts them with
+/// `#![export]` attributes.
+pub fn get_export_list(cdata: Cmd) -> Option<Vec<ast::NodeId>> {
+    reader::maybe_get_doc(ebml::Doc::new(cdata.data()), tag_export_list).map(|items| {
+        let mut ret = Vec::new();
+        reader::tagged_docs(items, tag_export_list_item, |doc| {
+            ret.push(reader::doc_as_u32(doc));
+            true
+        });
+        ret
+    })
+}
+
+/// Whether the metadata holds a copy of the AST of the item, which other
+/// crates translate instead of linking to the crate's symbol for it.
+pub fn is_inlined_item(cdata: Cmd, id: ast::NodeId) -> bool {
+    let item_doc = lookup_item(id, cdata.data());
+    reader::maybe_get_doc(item_doc, tag_ast).is_some()
+}
+
 pub fn is_typedef(cdata: Cmd, id: ast::NodeId) -> bool {
     let item_doc = lookup_item(id, cdata.data());
     match item_family(item_doc) {
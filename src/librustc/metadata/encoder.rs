@@ -77,6 +77,7 @@ pub struct EncodeParams<'a> {
     pub cstore: &'a cstore::CStore,
     pub encode_inlined_item: EncodeInlinedItem<'a>,
     pub reachable: &'a NodeSet,
+    pub export_list: Option<&'a NodeSet>,
 }
 
 pub struct EncodeContext<'a> {
@@ -90,6 +91,7 @@ pub struct EncodeContext<'a> {
     pub encode_inlined_item: RefCell<EncodeInlinedItem<'a>>,
     pub type_abbrevs: tyencode::abbrev_map,
     pub reachable: &'a NodeSet,
+    pub export_list: Option<&'a NodeSet>,
 }
 
 fn encode_name(ebml_w: &mut Encoder, name: Name) {
@@ -340,6 +342,7 @@ fn encode_enum_variant_info(ecx: &EncodeContext,
                 let fields = ty::lookup_struct_fields(ecx.tcx, def_id);
                 let idx = encode_info_for_struct(ecx,
                                                  ebml_w,
+                                                 variant.node.id,
                                                  fields.as_slice(),
                                                  index);
                 encode_struct_fields(ebml_w, fields.as_slice(), def_id);
@@ -675,6 +678,7 @@ fn encode_provided_source(ebml_w: &mut Encoder,
 /* Returns an index of items in this class */
 fn encode_info_for_struct(ecx: &EncodeContext,
                           ebml_w: &mut Encoder,
+                          parent_id: NodeId,
                           fields: &[ty::field_ty],
                           global_index: &mut Vec<entry<i64>>)
                           -> Vec<entry<i64>> {
@@ -698,6 +702,9 @@ fn encode_info_for_struct(ecx: &EncodeContext,
                token::get_name(nm), id);
         encode_struct_field_family(ebml_w, field.vis);
         encode_name(ebml_w, nm);
+        ecx.tcx.map.with_path(parent_id, |path| {
+            encode_path(ebml_w, path.chain(Some(ast_map::PathName(nm)).move_iter()))
+        });
         encode_type(ecx, ebml_w, node_id_to_type(tcx, id));
         encode_def_id(ebml_w, local_def(id));
 
@@ -1046,6 +1053,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
            class itself */
         let idx = encode_info_for_struct(ecx,
                                          ebml_w,
+                                         item.id,
                                          fields.as_slice(),
                                          index);
 
@@ -1718,6 +1726,37 @@ fn encode_reachable_extern_fns(ecx: &EncodeContext, ebml_w: &mut Encoder) {
     ebml_w.end_tag();
 }
 
+// Encode the symbols of all reachable items, which a dynamic library this
+// crate is statically linked into exports on its behalf.
+fn encode_exported_symbols(ecx: &EncodeContext, ebml_w: &mut Encoder) {
+    ebml_w.start_tag(tag_exported_symbols);
+
+    for id in ecx.reachable.iter() {
+        match ecx.item_symbols.borrow().find(id) {
+            Some(sym) => {
+                ebml_w.wr_tagged_str(tag_exported_symbol, sym.as_slice());
+            }
+            None => {}
+        }
+    }
+
+    ebml_w.end_tag();
+}
+
+// Encode the items a dynamic library with `#![export]` attributes exports, so
+// that downstream crates linking to it dynamically only use those.
+fn encode_export_list(ecx: &EncodeContext, ebml_w: &mut Encoder) {
+    let export_list = match ecx.export_list {
+        Some(export_list) => export_list,
+        None => return,
+    };
+    ebml_w.start_tag(tag_export_list);
+    for id in export_list.iter() {
+        ebml_w.wr_tagged_u32(tag_export_list_item, *id);
+    }
+    ebml_w.end_tag();
+}
+
 fn encode_crate_dep(ebml_w: &mut Encoder,
                     dep: decoder::CrateDep) {
     ebml_w.start_tag(tag_crate_dep);
@@ -1748,6 +1787,12 @@ fn encode_crate_triple(ebml_w: &mut Encoder, triple: &str) {
     ebml_w.end_tag();
 }
 
+fn encode_fail_strategy(ebml_w: &mut Encoder, strategy: config::FailStrategy) {
+    ebml_w.start_tag(tag_fail_strategy);
+    ebml_w.writer.write(strategy.to_string().as_bytes());
+    ebml_w.end_tag();
+}
+
 fn encode_dylib_dependency_formats(ebml_w: &mut Encoder, ecx: &EncodeContext) {
     ebml_w.start_tag(tag_dylib_dependency_formats);
     match ecx.tcx.dependency_formats.borrow().find(&config::CrateTypeDylib) {
@@ -1818,6 +1863,7 @@ fn encode_metadata_inner(wr: &mut MemWriter, parms: EncodeParams, krate: &Crate)
         link_meta,
         non_inlineable_statics,
         reachable,
+        export_list,
         ..
     } = parms;
     let ecx = EncodeContext {
@@ -1831,6 +1877,7 @@ fn encode_metadata_inner(wr: &mut MemWriter, parms: EncodeParams, krate: &Crate)
         encode_inlined_item: RefCell::new(encode_inlined_item),
         type_abbrevs: RefCell::new(HashMap::new()),
         reachable: reachable,
+        export_list: export_list,
      };
 
     let mut ebml_w = writer::Encoder::new(wr);
@@ -1839,10 +1886,11 @@ fn encode_metadata_inner(wr: &mut MemWriter, parms: EncodeParams, krate: &Crate)
     encode_crate_triple(&mut ebml_w,
                         tcx.sess
                            .targ_cfg
-                           .target_strs
-                           .target_triple
+                           .target
+                           .name
                            .as_slice());
     encode_hash(&mut ebml_w, &ecx.link_meta.crate_hash);
+    encode_fail_strategy(&mut ebml_w, tcx.sess.opts.cg.fail_strategy);
     encode_dylib_dependency_formats(&mut ebml_w, &ecx);
 
     let mut i = ebml_w.writer.tell().unwrap();
@@ -1882,6 +1930,8 @@ fn encode_metadata_inner(wr: &mut MemWriter, parms: EncodeParams, krate: &Crate)
     i = ebml_w.writer.tell().unwrap();
     encode_misc_info(&ecx, krate, &mut ebml_w);
     encode_reachable_extern_fns(&ecx, &mut ebml_w);
+    encode_exported_symbols(&ecx, &mut ebml_w);
+    encode_export_list(&ecx, &mut ebml_w);
     stats.misc_bytes = ebml_w.writer.tell().unwrap() - i;
 
     // Encode and index the items.
@@ -214,6 +214,7 @@
 
 use back::archive::{METADATA_FILENAME};
 use back::svh::Svh;
+use back::target::Target;
 use driver::session::Session;
 use llvm;
 use llvm::{False, ObjectFile, mk_section_iter};
@@ -222,7 +223,6 @@ use metadata::cstore::{MetadataBlob, MetadataVec, MetadataArchive};
 use metadata::decoder;
 use metadata::encoder;
 use metadata::filesearch::{FileSearch, FileMatches, FileDoesntMatch};
-use syntax::abi;
 use syntax::codemap::Span;
 use syntax::diagnostic::SpanHandler;
 use util::fs;
@@ -239,21 +239,6 @@ use std::collections::{HashMap, HashSet};
 use flate;
 use time;
 
-pub static MACOS_DLL_PREFIX: &'static str = "lib";
-pub static MACOS_DLL_SUFFIX: &'static str = ".dylib";
-
-pub static WIN32_DLL_PREFIX: &'static str = "";
-pub static WIN32_DLL_SUFFIX: &'static str = ".dll";
-
-pub static LINUX_DLL_PREFIX: &'static str = "lib";
-pub static LINUX_DLL_SUFFIX: &'static str = ".so";
-
-pub static FREEBSD_DLL_PREFIX: &'static str = "lib";
-pub static FREEBSD_DLL_SUFFIX: &'static str = ".so";
-
-pub static ANDROID_DLL_PREFIX: &'static str = "lib";
-pub static ANDROID_DLL_SUFFIX: &'static str = ".so";
-
 pub struct CrateMismatch {
     path: Path,
     got: String,
@@ -266,7 +251,7 @@ pub struct Context<'a> {
     pub crate_name: &'a str,
     pub hash: Option<&'a Svh>,
     pub triple: &'a str,
-    pub os: abi::Os,
+    pub target: &'a Target,
     pub filesearch: FileSearch<'a>,
     pub root: &'a Option<CratePaths>,
     pub rejected_via_hash: Vec<CrateMismatch>,
@@ -523,7 +508,7 @@ impl<'a> Context<'a> {
 
         for lib in m.move_iter() {
             info!("{} reading metadata from: {}", flavor, lib.display());
-            let metadata = match get_metadata_section(self.os, &lib) {
+            let metadata = match get_metadata_section(self.target.options.is_like_osx, &lib) {
                 Ok(blob) => {
                     if self.crate_matches(blob.as_slice(), &lib) {
                         blob
@@ -610,14 +595,13 @@ impl<'a> Context<'a> {
 
     // Returns the corresponding (prefix, suffix) that files need to have for
     // dynamic libraries
-    fn dylibname(&self) -> Option<(&'static str, &'static str)> {
-        match self.os {
-            abi::OsWin32 => Some((WIN32_DLL_PREFIX, WIN32_DLL_SUFFIX)),
-            abi::OsMacos => Some((MACOS_DLL_PREFIX, MACOS_DLL_SUFFIX)),
-            abi::OsLinux => Some((LINUX_DLL_PREFIX, LINUX_DLL_SUFFIX)),
-            abi::OsAndroid => Some((ANDROID_DLL_PREFIX, ANDROID_DLL_SUFFIX)),
-            abi::OsFreebsd => Some((FREEBSD_DLL_PREFIX, FREEBSD_DLL_SUFFIX)),
-            abi::OsiOS => None,
+    fn dylibname(&self) -> Option<(&'a str, &'a str)> {
+        let target: &'a Target = self.target;
+        if target.options.dynamic_linking {
+            Some((target.options.dll_prefix.as_slice(),
+                  target.options.dll_suffix.as_slice()))
+        } else {
+            None
         }
     }
 
@@ -721,15 +705,15 @@ impl ArchiveMetadata {
 }
 
 // Just a small wrapper to time how long reading metadata takes.
-fn get_metadata_section(os: abi::Os, filename: &Path) -> Result<MetadataBlob, String> {
+fn get_metadata_section(is_like_osx: bool, filename: &Path) -> Result<MetadataBlob, String> {
     let start = time::precise_time_ns();
-    let ret = get_metadata_section_imp(os, filename);
+    let ret = get_metadata_section_imp(is_like_osx, filename);
     info!("reading {} => {}ms", filename.filename_display(),
            (time::precise_time_ns() - start) / 1000000);
     return ret;
 }
 
-fn get_metadata_section_imp(os: abi::Os, filename: &Path) -> Result<MetadataBlob, String> {
+fn get_metadata_section_imp(is_like_osx: bool, filename: &Path) -> Result<MetadataBlob, String> {
     if !filename.exists() {
         return Err(format!("no such file: '{}'", filename.display()));
     }
@@ -775,7 +759,7 @@ fn get_metadata_section_imp(os: abi::Os, filename: &Path) -> Result<MetadataBlob
             let name = str::raw::from_buf_len(name_buf as *const u8,
                                               name_len as uint);
             debug!("get_metadata_section: name {}", name);
-            if read_meta_section_name(os).as_slice() == name.as_slice() {
+            if read_meta_section_name(is_like_osx).as_slice() == name.as_slice() {
                 let cbuf = llvm::LLVMGetSectionContents(si.llsi);
                 let csz = llvm::LLVMGetSectionSize(si.llsi) as uint;
                 let mut found =
@@ -816,32 +800,35 @@ fn get_metadata_section_imp(os: abi::Os, filename: &Path) -> Result<MetadataBlob
     }
 }
 
-pub fn meta_section_name(os: abi::Os) -> Option<&'static str> {
-    match os {
-        abi::OsMacos => Some("__DATA,__note.rustc"),
-        abi::OsiOS => Some("__DATA,__note.rustc"),
-        abi::OsWin32 => Some(".note.rustc"),
-        abi::OsLinux => Some(".note.rustc"),
-        abi::OsAndroid => Some(".note.rustc"),
-        abi::OsFreebsd => Some(".note.rustc")
+pub fn meta_section_name(is_like_osx: bool) -> &'static str {
+    if is_like_osx {
+        "__DATA,__note.rustc"
+    } else {
+        ".note.rustc"
+    }
+}
+
+pub fn read_meta_section_name(is_like_osx: bool) -> &'static str {
+    if is_like_osx {
+        "__note.rustc"
+    } else {
+        ".note.rustc"
     }
 }
 
-pub fn read_meta_section_name(os: abi::Os) -> &'static str {
-    match os {
-        abi::OsMacos => "__note.rustc",
-        abi::OsiOS => unreachable!(),
-        abi::OsWin32 => ".note.rustc",
-        abi::OsLinux => ".note.rustc",
-        abi::OsAndroid => ".note.rustc",
-        abi::OsFreebsd => ".note.rustc"
+/// The SVH recorded in the metadata of the library at `path`, if it can be
+/// read.
+pub fn read_crate_hash(is_like_osx: bool, path: &Path) -> Option<Svh> {
+    match get_metadata_section(is_like_osx, path) {
+        Ok(blob) => decoder::maybe_get_crate_hash(blob.as_slice()),
+        Err(..) => None,
     }
 }
 
 // A diagnostic function for dumping crate metadata to an output stream
-pub fn list_file_metadata(os: abi::Os, path: &Path,
+pub fn list_file_metadata(is_like_osx: bool, path: &Path,
                           out: &mut io::Writer) -> io::IoResult<()> {
-    match get_metadata_section(os, path) {
+    match get_metadata_section(is_like_osx, path) {
         Ok(bytes) => decoder::list_crate_metadata(bytes.as_slice(), out),
         Err(msg) => {
             write!(out, "{}\n", msg)
@@ -10,6 +10,7 @@
 
 
 use driver::session::Session;
+use middle::const_eval;
 use middle::def::*;
 use middle::resolve;
 use middle::ty;
@@ -82,6 +83,29 @@ fn check_pat(v: &mut CheckCrateVisitor, p: &Pat, _is_const: bool) {
     }
 }
 
+// Report integer arithmetic which overflows. Only operations on two integers
+// of the same signedness are checked, so failing to evaluate the operation
+// itself means it overflowed, and each overflow is reported once.
+fn check_overflow(v: &mut CheckCrateVisitor, e: &Expr) {
+    match e.node {
+        ExprBinary(BiAdd, ref a, ref b) |
+        ExprBinary(BiSub, ref a, ref b) |
+        ExprBinary(BiMul, ref a, ref b) => {
+            match (const_eval::eval_const_expr_partial(v.tcx, &**a),
+                   const_eval::eval_const_expr_partial(v.tcx, &**b)) {
+                (Ok(const_eval::const_int(_)), Ok(const_eval::const_int(_))) |
+                (Ok(const_eval::const_uint(_)), Ok(const_eval::const_uint(_))) => {}
+                _ => return
+            }
+            match const_eval::eval_const_expr_partial(v.tcx, e) {
+                Err(msg) => v.tcx.sess.span_err(e.span, msg.as_slice()),
+                Ok(_) => {}
+            }
+        }
+        _ => {}
+    }
+}
+
 fn check_expr(v: &mut CheckCrateVisitor, e: &Expr, is_const: bool) {
     if is_const {
         match e.node {
@@ -96,6 +120,8 @@ fn check_expr(v: &mut CheckCrateVisitor, e: &Expr, is_const: bool) {
             if v.tcx.method_map.borrow().contains_key(&method_call) {
                 span_err!(v.tcx.sess, e.span, E0011,
                     "user-defined operators are not allowed in constant expressions");
+            } else {
+                check_overflow(v, e);
             }
           }
           ExprLit(_) => (),
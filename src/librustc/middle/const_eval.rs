@@ -27,6 +27,7 @@ use syntax::{ast, ast_map, ast_util};
 
 use std::rc::Rc;
 use std::gc::Gc;
+use std::{i8, i16, i32, i64, u8, u16, u32, u64};
 
 //
 // This pass classifies expressions by their constant-ness.
@@ -310,6 +311,69 @@ pub fn eval_const_expr(tcx: &ty::ctxt, e: &Expr) -> const_val {
     }
 }
 
+fn overflow_error(op: BinOp) -> String {
+    match op {
+        BiAdd => "attempted to add with overflow".to_string(),
+        BiSub => "attempted to subtract with overflow".to_string(),
+        _ => "attempted to multiply with overflow".to_string(),
+    }
+}
+
+/// The result of `+`, `-` or `*` on signed integers, which is an error if it
+/// overflowed 64 bits or, when it is known, the type of `e`.
+fn int_arith<T: ty::ExprTyProvider>(tcx: &T, e: &Expr, op: BinOp,
+                                    result: Option<i64>) -> Result<const_val, String> {
+    fn bounds(tcx: &ty::ctxt, t: IntTy) -> (i64, i64) {
+        match t {
+            TyI => bounds(tcx, tcx.sess.targ_cfg.int_type),
+            TyI8 => (i8::MIN as i64, i8::MAX as i64),
+            TyI16 => (i16::MIN as i64, i16::MAX as i64),
+            TyI32 => (i32::MIN as i64, i32::MAX as i64),
+            TyI64 => (i64::MIN, i64::MAX),
+        }
+    }
+    let tcx = tcx.ty_ctxt();
+    match result {
+        Some(n) => {
+            match ty::expr_ty_opt(tcx, e).map(|t| &ty::get(t).sty) {
+                Some(&ty::ty_int(t)) => {
+                    let (min, max) = bounds(tcx, t);
+                    if n < min || n > max {
+                        return Err(overflow_error(op));
+                    }
+                }
+                _ => {}
+            }
+            Ok(const_int(n))
+        }
+        None => Err(overflow_error(op)),
+    }
+}
+
+/// The unsigned counterpart of `int_arith`.
+fn uint_arith<T: ty::ExprTyProvider>(tcx: &T, e: &Expr, op: BinOp,
+                                     result: Option<u64>) -> Result<const_val, String> {
+    fn max(tcx: &ty::ctxt, t: UintTy) -> u64 {
+        match t {
+            TyU => max(tcx, tcx.sess.targ_cfg.uint_type),
+            TyU8 => u8::MAX as u64,
+            TyU16 => u16::MAX as u64,
+            TyU32 => u32::MAX as u64,
+            TyU64 => u64::MAX,
+        }
+    }
+    let tcx = tcx.ty_ctxt();
+    match result {
+        Some(n) => {
+            match ty::expr_ty_opt(tcx, e).map(|t| &ty::get(t).sty) {
+                Some(&ty::ty_uint(t)) if n > max(tcx, t) => Err(overflow_error(op)),
+                _ => Ok(const_uint(n)),
+            }
+        }
+        None => Err(overflow_error(op)),
+    }
+}
+
 pub fn eval_const_expr_partial<T: ty::ExprTyProvider>(tcx: &T, e: &Expr)
                             -> Result<const_val, String> {
     fn fromb(b: bool) -> Result<const_val, String> { Ok(const_int(b as i64)) }
@@ -353,9 +417,9 @@ pub fn eval_const_expr_partial<T: ty::ExprTyProvider>(tcx: &T, e: &Expr)
           }
           (Ok(const_int(a)), Ok(const_int(b))) => {
             match op {
-              BiAdd => Ok(const_int(a + b)),
-              BiSub => Ok(const_int(a - b)),
-              BiMul => Ok(const_int(a * b)),
+              BiAdd => int_arith(tcx, e, op, a.checked_add(&b)),
+              BiSub => int_arith(tcx, e, op, a.checked_sub(&b)),
+              BiMul => int_arith(tcx, e, op, a.checked_mul(&b)),
               BiDiv if b == 0 => {
                   Err("attempted to divide by zero".to_string())
               }
@@ -380,9 +444,9 @@ pub fn eval_const_expr_partial<T: ty::ExprTyProvider>(tcx: &T, e: &Expr)
           }
           (Ok(const_uint(a)), Ok(const_uint(b))) => {
             match op {
-              BiAdd => Ok(const_uint(a + b)),
-              BiSub => Ok(const_uint(a - b)),
-              BiMul => Ok(const_uint(a * b)),
+              BiAdd => uint_arith(tcx, e, op, a.checked_add(&b)),
+              BiSub => uint_arith(tcx, e, op, a.checked_sub(&b)),
+              BiMul => uint_arith(tcx, e, op, a.checked_mul(&b)),
               BiDiv if b == 0 => {
                   Err("attempted to divide by zero".to_string())
               }
@@ -487,7 +551,14 @@ pub fn eval_const_expr_partial<T: ty::ExprTyProvider>(tcx: &T, e: &Expr)
               None => Err("non-constant path in constant expr".to_string())
           }
       }
-      ExprLit(ref lit) => Ok(lit_to_const(&**lit)),
+      ExprLit(ref lit) => {
+        // Unsuffixed literals of unsigned types evaluate to unsigned values,
+        // so arithmetic with them is checked against the right bounds
+        match (&lit.node, ty::expr_ty_opt(tcx.ty_ctxt(), e).map(|t| &ty::get(t).sty)) {
+          (&LitIntUnsuffixed(n), Some(&ty::ty_uint(_))) => Ok(const_uint(n as u64)),
+          _ => Ok(lit_to_const(&**lit))
+        }
+      }
       // If we have a vstore, just keep going; it has to be a string
       ExprVstore(ref e, _) => eval_const_expr_partial(tcx, &**e),
       ExprParen(ref e)     => eval_const_expr_partial(tcx, &**e),
@@ -61,14 +61,17 @@
 //! Additionally, the algorithm is geared towards finding *any* solution rather
 //! than finding a number of solutions (there are normally quite a few).
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use syntax::ast;
+use syntax::ast_util;
 
 use driver::session;
 use driver::config;
 use metadata::cstore;
 use metadata::csearch;
+use middle::def;
 use middle::ty;
+use middle::typeck;
 
 /// A list of dependencies for a certain crate type.
 ///
@@ -88,9 +91,135 @@ pub fn calculate(tcx: &ty::ctxt) {
     for &ty in tcx.sess.crate_types.borrow().iter() {
         fmts.insert(ty, calculate_type(&tcx.sess, ty));
     }
+    check_export_lists(tcx, &*fmts);
     tcx.sess.abort_if_errors();
 }
 
+/// A dynamic library built with `#![export]` attributes only exports some of
+/// its public items, so report the uses of the others by crates linking to it
+/// dynamically, which would otherwise fail to link.
+fn check_export_lists(tcx: &ty::ctxt, fmts: &Dependencies) {
+    let mut export_lists = HashMap::new();
+    for list in fmts.values() {
+        for (i, kind) in list.iter().enumerate() {
+            let cnum = i as ast::CrateNum + 1;
+            if *kind != Some(cstore::RequireDynamic) ||
+               export_lists.contains_key(&cnum) {
+                continue
+            }
+            match csearch::get_export_list(&tcx.sess.cstore, cnum) {
+                Some(items) => {
+                    let items: HashSet<ast::NodeId> = items.move_iter().collect();
+                    export_lists.insert(cnum, items);
+                }
+                None => {}
+            }
+        }
+    }
+    if export_lists.is_empty() {
+        return
+    }
+
+    // Only the uses of items which need a symbol of the library are checked.
+    let cstore = &tcx.sess.cstore;
+    let mut uses = Vec::new();
+    for (&id, def) in tcx.def_map.borrow().iter() {
+        match *def {
+            def::DefFn(did, _) | def::DefStaticMethod(did, _, _)
+                    if export_lists.contains_key(&did.krate) &&
+                       needs_symbol(cstore, did) => {
+                uses.push((id, did));
+            }
+            def::DefStatic(did, mutbl)
+                    if export_lists.contains_key(&did.krate) &&
+                       static_needs_symbol(cstore, did, mutbl) => {
+                uses.push((id, did));
+            }
+            _ => {}
+        }
+    }
+    for (call, callee) in tcx.method_map.borrow().iter() {
+        match callee.origin {
+            typeck::MethodStatic(did)
+                    if export_lists.contains_key(&did.krate) &&
+                       needs_symbol(cstore, did) => {
+                uses.push((call.expr_id, did));
+            }
+            _ => {}
+        }
+    }
+    // Calls on type parameters and trait objects reach the methods of the
+    // impls their vtables are built from, where the type is known.
+    for (call, vtables) in tcx.vtable_map.borrow().iter() {
+        for origins in vtables.iter() {
+            for origin in origins.iter() {
+                add_impl_uses(cstore, &export_lists, call.expr_id, origin, &mut uses);
+            }
+        }
+    }
+    uses.sort();
+    uses.dedup();
+    for &(id, did) in uses.iter() {
+        let exported = match export_lists.find(&did.krate) {
+            Some(items) => items.contains(&did.node),
+            None => true,
+        };
+        if !exported {
+            tcx.sess.span_err(tcx.map.span(id),
+                              format!("`{}` is not exported by the dynamic \
+                                       library `{}`",
+                                      ty::item_path_str(tcx, did),
+                                      tcx.sess.cstore.get_crate_data(did.krate)
+                                         .name).as_slice());
+        }
+    }
+}
+
+/// Whether uses of `did` refer to the library's symbol for it. Generic and
+/// `#[inline]` functions are instead translated from the copy of their AST
+/// in the library's metadata.
+fn needs_symbol(cstore: &cstore::CStore, did: ast::DefId) -> bool {
+    !csearch::is_inlined_item(cstore, did)
+}
+
+/// Like `needs_symbol`, for a static. The copy of an immutable static is only
+/// used in place of the library's when its address doesn't matter.
+fn static_needs_symbol(cstore: &cstore::CStore, did: ast::DefId, mutbl: bool) -> bool {
+    if mutbl || needs_symbol(cstore, did) {
+        return true
+    }
+    let mut significant = true;
+    csearch::get_item_attrs(cstore, did, |attrs| {
+        significant = ast_util::static_has_significant_address(ast::MutImmutable,
+                                                               attrs.as_slice());
+    });
+    significant
+}
+
+fn add_impl_uses(cstore: &cstore::CStore,
+                 export_lists: &HashMap<ast::CrateNum, HashSet<ast::NodeId>>,
+                 id: ast::NodeId,
+                 origin: &typeck::vtable_origin,
+                 uses: &mut Vec<(ast::NodeId, ast::DefId)>) {
+    match *origin {
+        typeck::vtable_static(impl_did, _, ref sub_vtables) => {
+            if export_lists.contains_key(&impl_did.krate) {
+                for &did in csearch::get_impl_methods(cstore, impl_did).iter() {
+                    if needs_symbol(cstore, did) {
+                        uses.push((id, did));
+                    }
+                }
+            }
+            for origins in sub_vtables.iter() {
+                for origin in origins.iter() {
+                    add_impl_uses(cstore, export_lists, id, origin, uses);
+                }
+            }
+        }
+        typeck::vtable_param(..) | typeck::vtable_error => {}
+    }
+}
+
 fn calculate_type(sess: &session::Session,
                   ty: config::CrateType) -> DependencyList {
     match ty {
@@ -107,9 +236,9 @@ fn calculate_type(sess: &session::Session,
         // got long ago), so don't bother with anything.
         config::CrateTypeRlib => return Vec::new(),
 
-        // Staticlibs must have all static dependencies. If any fail to be
-        // found, we generate some nice pretty errors.
-        config::CrateTypeStaticlib => {
+        // Staticlibs and cdylibs must have all static dependencies. If any fail
+        // to be found, we generate some nice pretty errors.
+        config::CrateTypeStaticlib | config::CrateTypeCdylib => {
             match attempt_static(sess) {
                 Some(v) => return v,
                 None => {}
@@ -0,0 +1,461 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A whole-crate build cache (`-C incremental=DIR`).
+//!
+//! After a successful build we record, in `DIR/<crate name>/state.json`:
+//!
+//! * a fingerprint of every item in the crate, computed from the expanded AST
+//!   with the same hashing as the crate's SVH plus the item's position in the
+//!   source (so that moved code, whose line numbers end up in the object code,
+//!   counts as changed);
+//! * the dependency graph between those items, built from the resolved paths
+//!   and method calls of each item once the analysis passes have run;
+//! * a hash of the command line, the compiler version, the crate attributes
+//!   and the attributes and `use` and `extern crate` declarations of every
+//!   module;
+//! * the path and SVH of every upstream library that was used;
+//! * copies of the artifacts that were produced.
+//!
+//! On the next build, the new fingerprints are computed right after
+//! expansion. If the crate hash is the same, every upstream library still
+//! has the SVH it had, and no item changed, resolution, type checking,
+//! translation and linking are skipped altogether and the cached artifacts
+//! are copied into place.
+//!
+//! Otherwise the whole crate is rebuilt: nothing is reused per item. The
+//! dependency graph is only used to explain the rebuild: `-Z
+//! incremental-info` prints which items changed, and which items were
+//! invalidated through them.
+//!
+//! FIXME: reusing the work done for unchanged items is not implemented.
+//! Type checking keeps its tables for the whole crate in one `ty::ctxt` that
+//! can't be saved or partially restored, and translation emits one LLVM
+//! module whose symbols and inlining decisions depend on the entire crate.
+
+use back::svh;
+use driver::session::Session;
+use metadata::loader;
+use middle::def;
+use middle::ty;
+use middle::typeck;
+
+use serialize::json;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::hash::sip::SipState;
+use std::io;
+use std::io::{File, fs};
+use std::os;
+use syntax::ast;
+use syntax::ast_map;
+
+/// The fingerprints of the crate being compiled, computed after expansion.
+pub struct CrateFingerprints {
+    crate_hash: String,
+    items: Vec<ItemFingerprint>,
+}
+
+struct ItemFingerprint {
+    path: String,
+    id: ast::NodeId,
+    hash: String,
+}
+
+/// The dependencies of each item, keyed and valued by item paths.
+pub type DepGraph = HashMap<String, Vec<String>>;
+
+#[deriving(Encodable, Decodable)]
+struct SavedState {
+    crate_hash: String,
+    items: Vec<SavedItem>,
+    inputs: Vec<SavedInput>,
+    artifacts: Vec<String>,
+}
+
+#[deriving(Encodable, Decodable)]
+struct SavedItem {
+    path: String,
+    hash: String,
+    deps: Vec<String>,
+}
+
+#[deriving(Encodable, Decodable)]
+struct SavedInput {
+    path: String,
+    svh: String,
+}
+
+fn cache_dir(sess: &Session, crate_name: &str) -> Option<Path> {
+    sess.opts.cg.incremental.as_ref().map(|dir| Path::new(dir.as_slice()).join(crate_name))
+}
+
+fn hex(hash: u64) -> String {
+    format!("{:016x}", hash)
+}
+
+/// Compute the fingerprints of all items of the expanded crate.
+pub fn fingerprint_crate(sess: &Session,
+                         krate: &ast::Crate,
+                         map: &ast_map::Map) -> CrateFingerprints {
+    let cm = sess.codemap();
+
+    let mut state = SipState::new();
+    option_env!("CFG_VERSION").hash(&mut state);
+    os::args().hash(&mut state);
+    for attr in krate.attrs.iter() {
+        attr.node.value.hash(&mut state);
+    }
+    for &sp in krate.exported_macros.iter() {
+        cm.span_to_snippet(sp).hash(&mut state);
+    }
+
+    let mut items = Vec::new();
+    let mut seen = HashMap::new();
+    collect_items(sess, map, &krate.module, &mut state, &mut seen, &mut items);
+
+    CrateFingerprints {
+        crate_hash: hex(state.result()),
+        items: items,
+    }
+}
+
+fn collect_items(sess: &Session,
+                 map: &ast_map::Map,
+                 module: &ast::Mod,
+                 crate_state: &mut SipState,
+                 seen: &mut HashMap<String, uint>,
+                 items: &mut Vec<ItemFingerprint>) {
+    let cm = sess.codemap();
+
+    // `use` and `extern crate` change what every item of the module refers
+    // to, so they are hashed into the crate hash rather than into any item.
+    for view_item in module.view_items.iter() {
+        svh::hash_view_item(view_item).hash(crate_state);
+        cm.span_to_snippet(view_item.span).hash(crate_state);
+    }
+
+    for item in module.items.iter() {
+        match item.node {
+            ast::ItemMod(ref m) => {
+                // A module's attributes, such as `#![deny(...)]`, apply to
+                // all of its items, so like the crate attributes they go
+                // into the crate hash.
+                map.path_to_string(item.id).hash(crate_state);
+                for attr in item.attrs.iter() {
+                    attr.node.value.hash(crate_state);
+                }
+                collect_items(sess, map, m, crate_state, seen, items);
+                continue
+            }
+            _ => {}
+        }
+
+        // Paths are not unique for items such as impls, so number the
+        // duplicates in the order they appear.
+        let mut path = map.path_to_string(item.id);
+        let count = seen.find_or_insert(path.clone(), 0);
+        if *count > 0 {
+            path = format!("{}#{}", path, *count);
+        }
+        *count += 1;
+
+        let mut state = SipState::new();
+        svh::hash_item(&**item).hash(&mut state);
+        cm.span_to_string(item.span).hash(&mut state);
+        cm.span_to_snippet(item.span).hash(&mut state);
+
+        items.push(ItemFingerprint {
+            path: path,
+            id: item.id,
+            hash: hex(state.result()),
+        });
+    }
+}
+
+/// Build the dependency graph between the items of the crate from the
+/// results of resolution and type checking.
+pub fn build_dep_graph(tcx: &ty::ctxt, fingerprints: &CrateFingerprints) -> DepGraph {
+    let paths: HashMap<ast::NodeId, String> = fingerprints.items.iter().map(|item| {
+        (item.id, item.path.clone())
+    }).collect();
+
+    let mut uses = Vec::new();
+    for (&id, def) in tcx.def_map.borrow().iter() {
+        match *def {
+            def::DefPrimTy(_) => {}
+            _ => uses.push((id, def.def_id())),
+        }
+    }
+    for (call, callee) in tcx.method_map.borrow().iter() {
+        let def_id = match callee.origin {
+            typeck::MethodStatic(def_id) => def_id,
+            typeck::MethodParam(ref param) => param.trait_id,
+            typeck::MethodObject(ref object) => object.trait_id,
+        };
+        uses.push((call.expr_id, def_id));
+    }
+    // Code calling through a trait ends up using its impls.
+    for (trait_id, impls) in tcx.trait_impls.borrow().iter() {
+        if trait_id.krate != ast::LOCAL_CRATE {
+            continue;
+        }
+        for &impl_id in impls.borrow().iter() {
+            uses.push((trait_id.node, impl_id));
+        }
+    }
+
+    let mut edges: HashMap<String, HashSet<String>> = HashMap::new();
+    for &(id, def_id) in uses.iter() {
+        if def_id.krate != ast::LOCAL_CRATE {
+            continue;
+        }
+        match (enclosing_item(tcx, &paths, id),
+               enclosing_item(tcx, &paths, def_id.node)) {
+            (Some(ref from), Some(ref to)) if from != to => {
+                edges.find_or_insert(from.clone(), HashSet::new()).insert(to.clone());
+            }
+            _ => {}
+        }
+    }
+
+    edges.move_iter().map(|(from, to)| {
+        let mut to: Vec<String> = to.move_iter().collect();
+        to.sort();
+        (from, to)
+    }).collect()
+}
+
+/// Find the fingerprinted item containing the node `id`.
+fn enclosing_item(tcx: &ty::ctxt,
+                  paths: &HashMap<ast::NodeId, String>,
+                  mut id: ast::NodeId) -> Option<String> {
+    loop {
+        match paths.find(&id) {
+            Some(path) => return Some(path.clone()),
+            None => {}
+        }
+        let parent = tcx.map.get_parent(id);
+        if parent == id {
+            return None;
+        }
+        id = parent;
+    }
+}
+
+fn load(path: &Path) -> Option<SavedState> {
+    let contents = match File::open(path).read_to_string() {
+        Ok(contents) => contents,
+        Err(..) => return None,
+    };
+    json::decode(contents.as_slice()).ok()
+}
+
+fn input_unchanged(sess: &Session, input: &SavedInput) -> bool {
+    let path = Path::new(input.path.as_slice());
+    match loader::read_crate_hash(sess.targ_cfg.target.options.is_like_osx, &path) {
+        Some(svh) => svh.as_str() == input.svh.as_slice(),
+        None => false,
+    }
+}
+
+/// Compare the fingerprints of this build with the state saved by the last
+/// one. If nothing changed, copy the cached artifacts over `outputs` and
+/// return true; the rest of the compilation can then be skipped.
+pub fn reuse_artifacts(sess: &Session,
+                       crate_name: &str,
+                       fingerprints: &CrateFingerprints,
+                       outputs: &[Path]) -> bool {
+    let dir = match cache_dir(sess, crate_name) {
+        Some(dir) => dir,
+        None => return false,
+    };
+    let saved = match load(&dir.join("state.json")) {
+        Some(saved) => saved,
+        None => return false,
+    };
+
+    if saved.crate_hash != fingerprints.crate_hash {
+        if sess.incremental_info() {
+            sess.note("incremental: command line, crate or module attributes, or imports changed");
+        }
+        return false;
+    }
+
+    for input in saved.inputs.iter() {
+        if !input_unchanged(sess, input) {
+            if sess.incremental_info() {
+                sess.note(format!("incremental: `{}` changed",
+                                  input.path).as_slice());
+            }
+            return false;
+        }
+    }
+
+    // Items that were added, removed or whose fingerprint changed.
+    let current: HashMap<&str, &str> = fingerprints.items.iter().map(|item| {
+        (item.path.as_slice(), item.hash.as_slice())
+    }).collect();
+    let mut changed = HashSet::new();
+    for item in saved.items.iter() {
+        match current.find(&item.path.as_slice()) {
+            Some(hash) if *hash == item.hash.as_slice() => {}
+            _ => { changed.insert(item.path.as_slice()); }
+        }
+    }
+    let previous: HashSet<&str> = saved.items.iter().map(|item| {
+        item.path.as_slice()
+    }).collect();
+    for item in fingerprints.items.iter() {
+        if !previous.contains(&item.path.as_slice()) {
+            changed.insert(item.path.as_slice());
+        }
+    }
+
+    // Propagate invalidation to everything that (transitively) depended on
+    // a changed item in the previous build.
+    let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+    for item in saved.items.iter() {
+        for dep in item.deps.iter() {
+            dependents.find_or_insert(dep.as_slice(), Vec::new())
+                      .push(item.path.as_slice());
+        }
+    }
+    let mut invalidated: HashMap<&str, Option<&str>> = changed.iter().map(|&path| {
+        (path, None)
+    }).collect();
+    let mut worklist: Vec<&str> = changed.iter().map(|&path| path).collect();
+    while !worklist.is_empty() {
+        let path = worklist.pop().unwrap();
+        match dependents.find(&path) {
+            Some(users) => {
+                for &user in users.iter() {
+                    if !invalidated.contains_key(&user) {
+                        invalidated.insert(user, Some(path));
+                        worklist.push(user);
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+
+    if sess.incremental_info() {
+        let mut report: Vec<(&str, Option<&str>)> = invalidated.iter().map(|(&path, &cause)| {
+            (path, cause)
+        }).collect();
+        report.sort();
+        for &(path, cause) in report.iter() {
+            match cause {
+                None => sess.note(format!("incremental: `{}` changed", path).as_slice()),
+                Some(cause) => sess.note(format!("incremental: `{}` invalidated by `{}`",
+                                                 path, cause).as_slice()),
+            }
+        }
+    }
+    if !invalidated.is_empty() {
+        return false;
+    }
+
+    let mut names = Vec::new();
+    for output in outputs.iter() {
+        match output.filename_str() {
+            Some(name) if dir.join(name).exists() => names.push(name.to_string()),
+            _ => return false,
+        }
+    }
+    names.sort();
+    let mut artifacts = saved.artifacts.clone();
+    artifacts.sort();
+    if names != artifacts {
+        return false;
+    }
+
+    for output in outputs.iter() {
+        let cached = dir.join(output.filename_str().unwrap());
+        match fs::copy(&cached, output) {
+            Ok(()) => {}
+            Err(e) => {
+                sess.warn(format!("failed to reuse cached `{}`: {}",
+                                  cached.display(), e).as_slice());
+                return false;
+            }
+        }
+    }
+    if sess.incremental_info() {
+        sess.note("incremental: nothing changed, reusing the previous build");
+    }
+    true
+}
+
+/// Record the state of a successful build, along with copies of its
+/// artifacts, for the next build to compare against.
+pub fn save(sess: &Session,
+            crate_name: &str,
+            fingerprints: &CrateFingerprints,
+            graph: &DepGraph,
+            outputs: &[Path]) {
+    let dir = match cache_dir(sess, crate_name) {
+        Some(dir) => dir,
+        None => return,
+    };
+
+    let result = (|| {
+        try!(fs::mkdir_recursive(&dir, io::UserRWX));
+
+        let mut artifacts = Vec::new();
+        for output in outputs.iter() {
+            if !output.exists() {
+                continue;
+            }
+            let name = output.filename_str().unwrap().to_string();
+            try!(fs::copy(output, &dir.join(name.as_slice())));
+            artifacts.push(name);
+        }
+
+        let mut inputs = Vec::new();
+        sess.cstore.iter_crate_data(|cnum, data| {
+            let src = match sess.cstore.get_used_crate_source(cnum) {
+                Some(src) => src,
+                None => return,
+            };
+            for path in src.dylib.iter().chain(src.rlib.iter()) {
+                inputs.push(SavedInput {
+                    path: path.as_str().unwrap().to_string(),
+                    svh: data.hash().as_str().to_string(),
+                });
+            }
+        });
+
+        let empty = Vec::new();
+        let state = SavedState {
+            crate_hash: fingerprints.crate_hash.clone(),
+            items: fingerprints.items.iter().map(|item| {
+                SavedItem {
+                    path: item.path.clone(),
+                    hash: item.hash.clone(),
+                    deps: graph.find(&item.path).unwrap_or(&empty).clone(),
+                }
+            }).collect(),
+            inputs: inputs,
+            artifacts: artifacts,
+        };
+        let mut file = try!(File::create(&dir.join("state.json")));
+        file.write_str(json::encode(&state).as_slice())
+    })();
+
+    match result {
+        Ok(()) => {}
+        Err(e) => {
+            sess.warn(format!("failed to save incremental compilation state \
+                               to `{}`: {}", dir.display(), e).as_slice());
+        }
+    }
+}
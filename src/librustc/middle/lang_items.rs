@@ -259,9 +259,14 @@ lets_do_this! {
     // defined to use it, but a final product is required to define it
     // somewhere. Additionally, there are restrictions on crates that use a weak
     // lang item, but do not have it defined.
+    //
+    // With `-C fail-strategy=abort`, `begin_unwind` is replaced by the weak
+    // `abort` lang item, which must have the same type (see
+    // `typeck::check_abort_fn_ty`).
     FailFnLangItem,                  "fail_",                   fail_fn;
     FailBoundsCheckFnLangItem,       "fail_bounds_check",       fail_bounds_check_fn;
     BeginUnwindLangItem,             "begin_unwind",            begin_unwind;
+    AbortLangItem,                   "abort",                   abort;
 
     ExchangeMallocFnLangItem,        "exchange_malloc",         exchange_malloc_fn;
     ClosureExchangeMallocFnLangItem, "closure_exchange_malloc", closure_exchange_malloc_fn;
@@ -30,6 +30,7 @@ use syntax::parse::token::special_names;
 use syntax::parse::token::special_idents;
 use syntax::parse::token;
 use syntax::codemap::{Span, DUMMY_SP, Pos};
+use syntax::diagnostic::{CodeSuggestion, MachineApplicable, MaybeIncorrect};
 use syntax::owned_slice::OwnedSlice;
 use syntax::visit;
 use syntax::visit::Visitor;
@@ -5217,33 +5218,51 @@ impl<'a> Resolver<'a> {
                                              `self` argument is missing?");
                                 } else {
                                     let last_name = path.segments.last().unwrap().identifier.name;
-                                    let mut msg = match self.find_fallback_in_self_type(last_name) {
+                                    let replacement = match self.find_fallback_in_self_type(last_name) {
                                         NoSuggestion => {
                                             // limit search to 5 to reduce the number
                                             // of stupid suggestions
                                             self.find_best_match_for_name(wrong_name.as_slice(), 5)
-                                                                .map_or("".to_string(),
-                                                                        |x| format!("`{}`", x))
+                                                .map(|x| (format!("`{}`", x), x))
                                         }
                                         Field =>
-                                            format!("`self.{}`", wrong_name),
+                                            Some((format!("`self.{}`", wrong_name),
+                                                  format!("self.{}", wrong_name))),
                                         Method
                                         | TraitMethod =>
-                                            format!("to call `self.{}`", wrong_name),
+                                            Some((format!("to call `self.{}`", wrong_name),
+                                                  format!("self.{}", wrong_name))),
                                         StaticTraitMethod(path_str)
                                         | StaticMethod(path_str) =>
-                                            format!("to call `{}::{}`", path_str, wrong_name)
+                                            Some((format!("to call `{}::{}`", path_str, wrong_name),
+                                                  format!("{}::{}", path_str, wrong_name)))
                                     };
 
-                                    if msg.len() > 0 {
-                                        msg = format!(" Did you mean {}?", msg)
-                                    }
+                                    let msg = match replacement {
+                                        Some((ref msg, _)) => format!(" Did you mean {}?", msg),
+                                        None => "".to_string(),
+                                    };
 
                                     self.resolve_error(
                                         expr.span,
                                         format!("unresolved name `{}`.{}",
                                                 wrong_name,
                                                 msg).as_slice());
+
+                                    // These are only guesses, so they are
+                                    // never applied automatically.
+                                    match replacement {
+                                        Some((_, ref replacement)) if self.emit_errors => {
+                                            self.session.span_suggestion(
+                                                expr.span,
+                                                format!("replace `{}` with `{}`",
+                                                        wrong_name,
+                                                        replacement).as_slice(),
+                                                replacement.clone(),
+                                                MaybeIncorrect);
+                                        }
+                                        _ => {}
+                                    }
                                 }
                             }
                         }
@@ -5483,21 +5502,53 @@ impl<'a> Resolver<'a> {
         match vi.node {
             ViewItemExternCrate(..) => {} // ignore
             ViewItemUse(ref p) => {
+                let remove_item = CodeSuggestion {
+                    span: vi.span,
+                    msg: "remove the unused import".to_string(),
+                    replacement: "".to_string(),
+                    applicability: MachineApplicable,
+                };
                 match p.node {
-                    ViewPathSimple(_, _, id) => self.finalize_import(id, p.span),
-                    ViewPathList(_, ref list, _) => {
+                    ViewPathSimple(_, _, id) => {
+                        self.finalize_import(id, p.span, vec!(remove_item))
+                    }
+                    ViewPathList(ref path, ref list, _) => {
+                        let used: Vec<String> = list.iter()
+                            .filter(|i| self.is_import_used(i.node.id))
+                            .map(|i| token::get_ident(i.node.name).get().to_string())
+                            .collect();
+                        // Either drop the whole `use`, or rewrite the list
+                        // so that it only names the imports which are used.
+                        let mut suggestion = Some(if used.is_empty() {
+                            remove_item
+                        } else {
+                            CodeSuggestion {
+                                span: p.span,
+                                msg: "remove the unused imports".to_string(),
+                                replacement: format!("{}{}::{{{}}}",
+                                                     if path.global { "::" } else { "" },
+                                                     self.path_idents_to_string(path),
+                                                     used.connect(", ")),
+                                applicability: MachineApplicable,
+                            }
+                        });
                         for i in list.iter() {
-                            self.finalize_import(i.node.id, i.span);
+                            let suggestions = if self.is_import_used(i.node.id) {
+                                Vec::new()
+                            } else {
+                                suggestion.take().move_iter().collect()
+                            };
+                            self.finalize_import(i.node.id, i.span, suggestions);
                         }
                     },
                     ViewPathGlob(_, id) => {
-                        if !self.used_imports.contains(&(id, TypeNS)) &&
-                           !self.used_imports.contains(&(id, ValueNS)) {
+                        if !self.is_import_used(id) {
                             self.session
-                                .add_lint(lint::builtin::UNUSED_IMPORTS,
-                                          id,
-                                          p.span,
-                                          "unused import".to_string());
+                                .add_lint_with_suggestions(lint::builtin::UNUSED_IMPORTS,
+                                                           id,
+                                                           p.span,
+                                                           "unused import".to_string(),
+                                                           vec!(remove_item));
                         }
                     },
                 }
@@ -5505,6 +5556,11 @@ impl<'a> Resolver<'a> {
         }
     }
 
+    fn is_import_used(&self, id: NodeId) -> bool {
+        self.used_imports.contains(&(id, TypeNS)) ||
+            self.used_imports.contains(&(id, ValueNS))
+    }
+
     // We have information about whether `use` (import) directives are actually used now.
     // If an import is not used at all, we signal a lint error. If an import is only used
     // for a single namespace, we remove the other namespace from the recorded privacy
@@ -5512,16 +5568,17 @@ impl<'a> Resolver<'a> {
     // which are used. In particular, this means that if an import could name either a
     // public or private item, we will check the correct thing, dependent on how the import
     // is used.
-    fn finalize_import(&mut self, id: NodeId, span: Span) {
+    fn finalize_import(&mut self, id: NodeId, span: Span,
+                       suggestions: Vec<CodeSuggestion>) {
         debug!("finalizing import uses for {}",
                self.session.codemap().span_to_snippet(span));
 
-        if !self.used_imports.contains(&(id, TypeNS)) &&
-           !self.used_imports.contains(&(id, ValueNS)) {
-            self.session.add_lint(lint::builtin::UNUSED_IMPORTS,
-                                  id,
-                                  span,
-                                  "unused import".to_string());
+        if !self.is_import_used(id) {
+            self.session.add_lint_with_suggestions(lint::builtin::UNUSED_IMPORTS,
+                                                   id,
+                                                   span,
+                                                   "unused import".to_string(),
+                                                   suggestions);
         }
 
         let (v_priv, t_priv) = match self.last_private.find(&id) {
@@ -13,6 +13,32 @@
 //! Rust plugin. It could also be used by IDEs or other code browsing, search, or
 //! cross-referencing tools.
 //!
+//! With `-Z save-analysis-json` the same data is written as one JSON object
+//! instead, in `<crate>.json`. Its schema is versioned by
+//! `recorder::JSON_SCHEMA_VERSION`, stored in the `version` field:
+//!
+//! * `crate` - the `crate` row for the crate being compiled,
+//! * `external_crates` - an `external_crate` row per crate it links to,
+//! * `defs` - a row per definition (variables, functions, types, modules, ...),
+//! * `refs` - a row per reference to a definition,
+//! * `impls` - the `impl` and `inheritance` rows,
+//! * `macro_uses` - a `macro_use` row per macro invocation.
+//!
+//! Every row is an object with a `kind` naming the CSV row it corresponds to,
+//! the CSV fields under the same names, and the extent under `span`. Ids are
+//! numbers (null where the CSV has an empty field) and the enclosing scope's
+//! id, `scopeid` in the CSV, is named `parent`.
+//!
+//! Rows referring to a definition in another crate identify it by crate number
+//! and node id, which are only meaningful within this crate, and also by a
+//! crate-independent id (`refextid`, `declextid` or `baseextid`): the crate's
+//! name and SVH and the definition's qualified path, which matches the
+//! `qualname` in the other crate's dump. The `crate` and `external_crate` rows
+//! carry each crate's SVH, so the dumps of a whole workspace can be joined.
+//! `pub use` declarations are recorded as `reexport` rows, and references
+//! through a re-exported path as `reexport_ref` rows, each with both the
+//! alias and the original path.
+//!
 //! Dumping the analysis is implemented by walking the AST and getting a bunch of
 //! info out from all over the place. We use Def IDs to identify objects. The
 //! tricky part is getting syntactic (span, source text) and semantic (reference
@@ -23,13 +49,16 @@
 //!
 //! SpanUtils is used to manipulate spans. In particular, to extract sub-spans
 //! from spans (e.g., the span for `bar` from the above example path).
-//! Recorder is used for recording the output in csv format. FmtStrs separates
+//! Recorder is used for recording the output in csv or json format. FmtStrs separates
 //! the format of the output away from extracting it from the compiler.
 //! DxrVisitor walks the AST and processes it.
 
+use back::svh::Svh;
+use driver::config;
 use driver::driver::CrateAnalysis;
 use driver::session::Session;
 
+use metadata::csearch;
 use middle::def;
 use middle::ty;
 use middle::typeck;
@@ -45,6 +74,7 @@ use syntax::ast;
 use syntax::ast_util;
 use syntax::ast_util::PostExpansionMethod;
 use syntax::ast::{NodeId,DefId};
+use syntax::ast_map;
 use syntax::ast_map::NodeItem;
 use syntax::attr;
 use syntax::codemap::*;
@@ -62,6 +92,8 @@ use util::ppaux;
 
 mod span_utils;
 mod recorder;
+pub mod query;
+pub mod unused_pub;
 
 // Helper function to escape quotes in a string
 fn escape(s: String) -> String {
@@ -79,6 +111,8 @@ struct DxrVisitor<'l> {
 
     collected_paths: Vec<(NodeId, ast::Path, bool, recorder::Row)>,
     collecting: bool,
+    // Call sites of the macro invocations recorded so far.
+    macro_calls: Vec<Span>,
 
     span: SpanUtils<'l>,
     fmt: FmtStrs<'l>,
@@ -87,13 +121,125 @@ struct DxrVisitor<'l> {
 impl <'l> DxrVisitor<'l> {
     fn dump_crate_info(&mut self, name: &str, krate: &ast::Crate) {
         // the current crate
-        self.fmt.crate_str(krate.span, name);
+        let svh = Svh::calculate(&self.sess.opts.cg.metadata, krate);
+        self.fmt.crate_str(krate.span, name, svh.as_str());
 
         // dump info about all the external crates referenced from this crate
         self.sess.cstore.iter_crate_data(|n, cmd| {
             self.fmt.external_crate_str(krate.span, cmd.name.as_slice(), n);
         });
-        self.fmt.recorder.record("end_external_crates\n");
+        self.fmt.end_external_crates();
+    }
+
+    // Records the outermost macro invocation that generated `span`. Nothing
+    // inside an expansion is indexed, so this is the only trace it leaves.
+    fn process_macro_use(&mut self, span: Span, e: DxrVisitorEnv) {
+        let mut info = match span.expn_info {
+            Some(info) => info,
+            None => return,
+        };
+        loop {
+            match info.call_site.expn_info {
+                Some(outer) => info = outer,
+                None => break,
+            }
+        }
+
+        let call_site = info.call_site;
+        if call_site == DUMMY_SP || self.macro_calls.contains(&call_site) {
+            return
+        }
+        self.macro_calls.push(call_site);
+
+        let sub_span = self.span.span_for_first_ident(call_site);
+        self.fmt.macro_use_str(call_site,
+                               sub_span,
+                               info.callee.name.as_slice(),
+                               e.cur_scope);
+    }
+
+    // The qualified path a definition was declared at, in the same form as the
+    // `qualname` of the definition's own row (in its own crate's dump).
+    fn def_qualname(&self, def_id: DefId) -> String {
+        if def_id.krate == ast::LOCAL_CRATE {
+            let path = self.analysis.ty_cx.map.path_to_string(def_id.node);
+            self.fmt.local_qualname(path.as_slice())
+        } else {
+            let path = csearch::get_item_path(&self.analysis.ty_cx, def_id);
+            ast_map::path_to_string(path.move_iter())
+        }
+    }
+
+    fn def_path_names(&self, def_id: DefId) -> Vec<ast::Name> {
+        if def_id.krate == ast::LOCAL_CRATE {
+            self.analysis.ty_cx.map.with_path(def_id.node, |path| {
+                path.map(|elem| elem.name()).collect()
+            })
+        } else {
+            let path = csearch::get_item_path(&self.analysis.ty_cx, def_id);
+            path.iter().map(|elem| elem.name()).collect()
+        }
+    }
+
+    // Records a reference made through a path which is not a suffix of the
+    // path the definition was declared at, i.e. one that goes through a
+    // re-export or a renaming `use`.
+    fn process_reexport_ref(&mut self, path: &ast::Path, def: def::Def, scope_id: NodeId) {
+        let def_id = match def {
+            def::DefFn(id, _) | def::DefStatic(id, _) | def::DefStruct(id) |
+            def::DefTy(id) | def::DefTrait(id) | def::DefMod(id) |
+            def::DefVariant(_, id, _) => id,
+            _ => return,
+        };
+
+        let written: Vec<ast::Name> = path.segments.iter().map(|seg| seg.identifier.name)
+            .filter(|name| *name != keywords::Self.to_name() &&
+                           *name != keywords::Super.to_name())
+            .collect();
+        if written.len() < 2 {
+            return
+        }
+        let declared = self.def_path_names(def_id);
+        if declared.len() >= written.len() &&
+           declared.slice_from(declared.len() - written.len()) == written.as_slice() {
+            return
+        }
+
+        let sub_span = self.span.span_for_last_ident(path.span);
+        let original = self.def_qualname(def_id);
+        self.fmt.reexport_ref_str(path.span,
+                                  sub_span,
+                                  def_id,
+                                  path_to_string(path).as_slice(),
+                                  original.as_slice(),
+                                  scope_id);
+    }
+
+    // Records a `pub use` of `def_id` as `name` in the current module.
+    fn process_reexport(&mut self,
+                        span: Span,
+                        sub_span: Option<Span>,
+                        id: NodeId,
+                        name: ast::Ident,
+                        def_id: DefId,
+                        e: DxrVisitorEnv) {
+        let name = get_ident(name);
+        let qualname = if e.cur_scope == ast::CRATE_NODE_ID {
+            name.get().to_string()
+        } else {
+            let mut qualname = self.analysis.ty_cx.map.path_to_string(e.cur_scope);
+            qualname.push_str("::");
+            qualname.push_str(name.get());
+            qualname
+        };
+        let original = self.def_qualname(def_id);
+        self.fmt.reexport_str(span,
+                              sub_span,
+                              id,
+                              qualname.as_slice(),
+                              def_id,
+                              original.as_slice(),
+                              e.cur_scope);
     }
 
     // Return all non-empty prefixes of a path.
@@ -766,6 +912,8 @@ impl <'l> DxrVisitor<'l> {
                                     format!("Unexpected def kind while looking up path in '{}'",
                                             self.span.snippet(ex.span)).as_slice()),
         }
+        self.process_reexport_ref(path, *def, e.cur_scope);
+
         // modules or types in the path prefix
         match *def {
             def::DefStaticMethod(_, _, _) => {
@@ -806,6 +954,12 @@ impl <'l> DxrVisitor<'l> {
             None => ()
         }
 
+        let def = self.analysis.ty_cx.def_map.borrow().find_copy(&ex.id);
+        match def {
+            Some(def) => self.process_reexport_ref(path, def, e.cur_scope),
+            None => ()
+        }
+
         self.write_sub_paths_truncated(path, e.cur_scope);
 
         for field in fields.iter() {
@@ -957,7 +1111,7 @@ impl <'l> DxrVisitor<'l> {
 impl<'l> Visitor<DxrVisitorEnv> for DxrVisitor<'l> {
     fn visit_item(&mut self, item:&ast::Item, e: DxrVisitorEnv) {
         if generated_code(item.span) {
-            return
+            return self.process_macro_use(item.span, e)
         }
 
         match item.node {
@@ -1112,6 +1266,12 @@ impl<'l> Visitor<DxrVisitorEnv> for DxrVisitor<'l> {
                                                mod_id,
                                                get_ident(ident).get(),
                                                e.cur_scope);
+                        match mod_id {
+                            Some(def_id) if i.vis == ast::Public => {
+                                self.process_reexport(path.span, sub_span, id, ident, def_id, e);
+                            }
+                            _ => {}
+                        }
                         self.write_sub_paths_truncated(path, e.cur_scope);
                     }
                     ast::ViewPathGlob(ref path, _) => {
@@ -1120,13 +1280,23 @@ impl<'l> Visitor<DxrVisitorEnv> for DxrVisitor<'l> {
                     ast::ViewPathList(ref path, ref list, _) => {
                         for plid in list.iter() {
                             match self.lookup_type_ref(plid.node.id) {
-                                Some(id) => match self.lookup_def_kind(plid.node.id, plid.span) {
-                                    Some(kind) => self.fmt.ref_str(kind,
-                                                                   plid.span,
-                                                                   Some(plid.span),
-                                                                   id,
-                                                                   e.cur_scope),
-                                    None => (),
+                                Some(id) => {
+                                    match self.lookup_def_kind(plid.node.id, plid.span) {
+                                        Some(kind) => self.fmt.ref_str(kind,
+                                                                       plid.span,
+                                                                       Some(plid.span),
+                                                                       id,
+                                                                       e.cur_scope),
+                                        None => (),
+                                    }
+                                    if i.vis == ast::Public {
+                                        self.process_reexport(plid.span,
+                                                              Some(plid.span),
+                                                              plid.node.id,
+                                                              plid.node.name,
+                                                              id,
+                                                              e);
+                                    }
                                 },
                                 None => ()
                             }
@@ -1178,6 +1348,12 @@ impl<'l> Visitor<DxrVisitorEnv> for DxrVisitor<'l> {
                     None => ()
                 }
 
+                let def = self.analysis.ty_cx.def_map.borrow().find_copy(&id);
+                match def {
+                    Some(def) => self.process_reexport_ref(path, def, e.cur_scope),
+                    None => ()
+                }
+
                 self.write_sub_paths_truncated(path, e.cur_scope);
 
                 visit::walk_path(self, path, e);
@@ -1188,7 +1364,7 @@ impl<'l> Visitor<DxrVisitorEnv> for DxrVisitor<'l> {
 
     fn visit_expr(&mut self, ex: &ast::Expr, e: DxrVisitorEnv) {
         if generated_code(ex.span) {
-            return
+            return self.process_macro_use(ex.span, e)
         }
 
         match ex.node {
@@ -1311,7 +1487,7 @@ impl<'l> Visitor<DxrVisitorEnv> for DxrVisitor<'l> {
 
     fn visit_stmt(&mut self, s:&ast::Stmt, e:DxrVisitorEnv) {
         if generated_code(s.span) {
-            return
+            return self.process_macro_use(s.span, e)
         }
 
         visit::walk_stmt(self, s, e)
@@ -1406,9 +1582,18 @@ pub fn process_crate(sess: &Session,
         info!("Writing output to {}", disp);
     }
 
+    let format = if sess.debugging_opt(config::SAVE_ANALYSIS_JSON) {
+        recorder::JsonFormat
+    } else {
+        recorder::CsvFormat
+    };
+
     // Create output file.
     let mut out_name = cratename.clone();
-    out_name.push_str(".csv");
+    match format {
+        recorder::CsvFormat => out_name.push_str(".csv"),
+        recorder::JsonFormat => out_name.push_str(".json"),
+    }
     root_path.push(out_name);
     let output_file = match File::create(&root_path) {
         Ok(f) => box f,
@@ -1423,6 +1608,7 @@ pub fn process_crate(sess: &Session,
                                   analysis: analysis,
                                   collected_paths: vec!(),
                                   collecting: false,
+                                  macro_calls: vec!(),
                                   fmt: FmtStrs::new(box Recorder {
                                                         out: output_file as Box<Writer>,
                                                         dump_spans: false,
@@ -1431,7 +1617,8 @@ pub fn process_crate(sess: &Session,
                                                         sess: sess,
                                                         err_count: Cell::new(0)
                                                     },
-                                                    cratename.clone()),
+                                                    cratename.clone(),
+                                                    format),
                                   span: SpanUtils {
                                       sess: sess,
                                       err_count: Cell::new(0)
@@ -1440,4 +1627,6 @@ pub fn process_crate(sess: &Session,
     visitor.dump_crate_info(cratename.as_slice(), krate);
 
     visit::walk_crate(&mut visitor, krate, DxrVisitorEnv::new());
+
+    visitor.fmt.finish();
 }
@@ -0,0 +1,863 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! An in-process query API for IDEs and other code browsing tools.
+//!
+//! `Analysis` runs the compiler up to and including type checking and then
+//! answers questions about a position in the crate's source, given as a file
+//! name (as it appears in the codemap) and a byte offset into that file:
+//!
+//! * `find_definition` - the definition of the name at the position,
+//! * `type_at` - the type of the innermost expression or pattern at the position,
+//! * `find_references` - every use of the definition named at the position,
+//! * `completions` - the names that could be written at the position.
+//!
+//! Names are mapped to definitions with resolve's def map and typeck's method
+//! map, and sub-spans are recovered with the same `SpanUtils` the CSV dump
+//! uses. Since the queries run over a fully analysed crate, the crate must
+//! compile; errors abort the analysis just as they would abort compilation.
+//! Completion is therefore driven by the identifier the position falls in: a
+//! position inside `foo.ba` offers the members of `foo`'s type starting with
+//! `ba`, a position inside the last segment of `a::b` offers the siblings of
+//! `b`, and any other position offers the locals declared earlier in the
+//! enclosing function and the items of the enclosing module.
+
+use back::link;
+use driver::driver;
+use driver::driver::{CrateAnalysis, Input};
+use driver::session::Session;
+
+use metadata::csearch;
+use metadata::decoder;
+use middle::def;
+use middle::ty;
+use middle::typeck;
+
+use std::cell::Cell;
+
+use syntax::ast;
+use syntax::ast::{NodeId, DefId};
+use syntax::ast_map;
+use syntax::ast_util;
+use syntax::ast_util::{local_def, PostExpansionMethod};
+use syntax::codemap::*;
+use syntax::parse::token;
+use syntax::parse::token::special_idents;
+use syntax::visit;
+use syntax::visit::Visitor;
+
+use middle::save::generated_code;
+use middle::save::span_utils::SpanUtils;
+
+use util::ppaux;
+
+/// A position in the source, as a byte range and the line and column of its
+/// start.
+#[deriving(Clone, PartialEq, Show)]
+pub struct Location {
+    pub file: String,
+    pub lo: uint,
+    pub hi: uint,
+    pub line: uint,
+    pub col: uint,
+}
+
+#[deriving(Clone, PartialEq, Show)]
+pub struct Definition {
+    /// The qualified path of an item, or the name of a local variable.
+    pub path: String,
+    /// Where the definition's name is declared; `None` for definitions in
+    /// other crates.
+    pub location: Option<Location>,
+}
+
+#[deriving(Clone, PartialEq, Show)]
+pub enum CompletionKind {
+    FieldCompletion,
+    MethodCompletion,
+    VariableCompletion,
+    ItemCompletion,
+}
+
+#[deriving(Clone, PartialEq, Show)]
+pub struct Completion {
+    pub name: String,
+    pub kind: CompletionKind,
+}
+
+// A name in the source together with the definition it refers to.
+struct Reference {
+    span: Span,
+    def_id: DefId,
+    // The def resolve recorded for a path, if the reference is one.
+    def: Option<def::Def>,
+    // True for the name at the definition itself.
+    is_decl: bool,
+    // True for the last segment of a path with more than one segment.
+    qualified: bool,
+}
+
+// The name in a field access or method call, and the type it is looked up in.
+struct Member {
+    span: Span,
+    ty: ty::t,
+}
+
+pub struct Analysis {
+    krate: ast::Crate,
+    analysis: CrateAnalysis,
+    refs: Vec<Reference>,
+    members: Vec<Member>,
+    fns: Vec<Span>,
+}
+
+impl Analysis {
+    /// Parses, expands, resolves and type checks `input`. Fails the task if
+    /// any of those passes report errors.
+    pub fn new(sess: Session, cfg: ast::CrateConfig, input: &Input) -> Analysis {
+        let krate = driver::phase_1_parse_input(&sess, cfg, input);
+        let name = link::find_crate_name(Some(&sess), krate.attrs.as_slice(), input);
+        let (krate, ast_map) = match driver::phase_2_configure_and_expand(&sess, krate,
+                                                                          name.as_slice()) {
+            Some(pair) => pair,
+            None => sess.fatal("compilation stopped before the crate could be analysed"),
+        };
+        let analysis = driver::phase_3_run_analysis_passes(sess, &krate, ast_map, name);
+
+        let (refs, members, fns) = {
+            let mut collector = RefCollector {
+                tcx: &analysis.ty_cx,
+                span: SpanUtils {
+                    sess: &analysis.ty_cx.sess,
+                    err_count: Cell::new(0),
+                },
+                refs: vec!(),
+                members: vec!(),
+                fns: vec!(),
+            };
+            visit::walk_crate(&mut collector, &krate, ());
+            (collector.refs, collector.members, collector.fns)
+        };
+
+        Analysis {
+            krate: krate,
+            analysis: analysis,
+            refs: refs,
+            members: members,
+            fns: fns,
+        }
+    }
+
+    pub fn krate<'a>(&'a self) -> &'a ast::Crate {
+        &self.krate
+    }
+
+    pub fn tcx<'a>(&'a self) -> &'a ty::ctxt {
+        &self.analysis.ty_cx
+    }
+
+    /// The definition of the name at `offset` in `file`.
+    pub fn find_definition(&self, file: &str, offset: uint) -> Option<Definition> {
+        let pos = match self.position(file, offset) {
+            Some(pos) => pos,
+            None => return None,
+        };
+        self.reference_at(pos).map(|r| {
+            let location = self.refs.iter()
+                               .find(|d| d.is_decl && d.def_id == r.def_id)
+                               .map(|d| self.location(d.span));
+            Definition {
+                path: self.def_path(r),
+                location: location,
+            }
+        })
+    }
+
+    /// The type of the innermost expression or pattern containing `offset`.
+    pub fn type_at(&self, file: &str, offset: uint) -> Option<String> {
+        let pos = match self.position(file, offset) {
+            Some(pos) => pos,
+            None => return None,
+        };
+        let mut finder = NodeFinder { pos: pos, best: None };
+        visit::walk_crate(&mut finder, &self.krate, ());
+        finder.best.and_then(|(_, id)| ty::node_id_to_type_opt(self.tcx(), id))
+                   .map(|t| ppaux::ty_to_string(self.tcx(), t))
+    }
+
+    /// Every use of the definition named at `offset`, including the
+    /// definition itself, in source order.
+    pub fn find_references(&self, file: &str, offset: uint) -> Vec<Location> {
+        let pos = match self.position(file, offset) {
+            Some(pos) => pos,
+            None => return vec!(),
+        };
+        let def_id = match self.reference_at(pos) {
+            Some(r) => r.def_id,
+            None => return vec!(),
+        };
+        let mut spans: Vec<Span> = self.refs.iter()
+                                            .filter(|r| r.def_id == def_id)
+                                            .map(|r| r.span)
+                                            .collect();
+        spans.sort_by(|a, b| a.lo.to_uint().cmp(&b.lo.to_uint()));
+        spans.dedup();
+        spans.iter().map(|&span| self.location(span)).collect()
+    }
+
+    /// The names that could complete the identifier ending at `offset`,
+    /// sorted by name.
+    pub fn completions(&self, file: &str, offset: uint) -> Vec<Completion> {
+        let pos = match self.position(file, offset) {
+            Some(pos) => pos,
+            None => return vec!(),
+        };
+
+        let mut result = match self.members.iter().find(|m| contains(m.span, pos)) {
+            Some(member) => {
+                let prefix = self.prefix(member.span, pos);
+                filter(member_completions(self.tcx(), member.ty), prefix.as_slice())
+            }
+            None => {
+                let reference = self.reference_at(pos);
+                let prefix = match reference {
+                    Some(r) => self.prefix(r.span, pos),
+                    None => String::new(),
+                };
+                match reference {
+                    Some(r) if r.qualified => {
+                        filter(self.sibling_completions(r), prefix.as_slice())
+                    }
+                    _ => filter(self.scope_completions(pos), prefix.as_slice()),
+                }
+            }
+        };
+
+        result.sort_by(|a, b| a.name.cmp(&b.name));
+        result.dedup();
+        result
+    }
+
+    fn sess<'a>(&'a self) -> &'a Session {
+        &self.analysis.ty_cx.sess
+    }
+
+    // Maps a file name and byte offset to a position in the codemap.
+    fn position(&self, file: &str, offset: uint) -> Option<BytePos> {
+        let files = self.sess().codemap().files.borrow();
+        files.iter().find(|fm| fm.name.as_slice() == file).and_then(|fm| {
+            if offset > fm.src.len() {
+                None
+            } else {
+                Some(fm.start_pos + BytePos(offset as u32))
+            }
+        })
+    }
+
+    fn location(&self, span: Span) -> Location {
+        let cm = self.sess().codemap();
+        let lo = cm.lookup_char_pos(span.lo);
+        Location {
+            file: lo.file.name.clone(),
+            lo: cm.lookup_byte_offset(span.lo).pos.to_uint(),
+            hi: cm.lookup_byte_offset(span.hi).pos.to_uint(),
+            line: lo.line,
+            col: lo.col.to_uint(),
+        }
+    }
+
+    // The text of `span` up to `pos`.
+    fn prefix(&self, span: Span, pos: BytePos) -> String {
+        let span = Span { lo: span.lo, hi: pos, expn_info: None };
+        self.sess().codemap().span_to_snippet(span).unwrap_or(String::new())
+    }
+
+    fn reference_at<'a>(&'a self, pos: BytePos) -> Option<&'a Reference> {
+        self.refs.iter().find(|r| contains(r.span, pos))
+    }
+
+    fn def_path(&self, r: &Reference) -> String {
+        let is_item = r.def_id.krate != ast::LOCAL_CRATE ||
+            match self.tcx().map.find(r.def_id.node) {
+                Some(ast_map::NodeItem(_)) |
+                Some(ast_map::NodeForeignItem(_)) |
+                Some(ast_map::NodeTraitMethod(_)) |
+                Some(ast_map::NodeMethod(_)) |
+                Some(ast_map::NodeVariant(_)) => true,
+                _ => false,
+            };
+        if is_item {
+            ty::item_path_str(self.tcx(), r.def_id)
+        } else {
+            self.sess().codemap().span_to_snippet(r.span).unwrap_or(String::new())
+        }
+    }
+
+    // The names declared alongside the definition a qualified path refers to.
+    fn sibling_completions(&self, r: &Reference) -> Vec<Completion> {
+        let tcx = self.tcx();
+        match r.def {
+            Some(def::DefVariant(enum_id, _, _)) => {
+                ty::enum_variants(tcx, enum_id).iter().map(|v| {
+                    completion(v.name.name, ItemCompletion)
+                }).collect()
+            }
+            Some(def::DefStaticMethod(_, def::FromTrait(trait_id), _)) => {
+                ty::trait_methods(tcx, trait_id).iter().map(|m| {
+                    completion(m.ident.name, MethodCompletion)
+                }).collect()
+            }
+            Some(def::DefStaticMethod(_, def::FromImpl(impl_id), _)) => {
+                let mut result = vec!();
+                impl_method_completions(tcx, impl_id, &mut result);
+                result
+            }
+            _ if r.def_id.krate == ast::LOCAL_CRATE => {
+                let parent = tcx.map.get_parent(r.def_id.node);
+                if parent == ast::CRATE_NODE_ID {
+                    return mod_completions(&self.krate.module);
+                }
+                match tcx.map.find(parent) {
+                    Some(ast_map::NodeItem(item)) => match item.node {
+                        ast::ItemMod(ref m) => mod_completions(m),
+                        _ => vec!(),
+                    },
+                    _ => vec!(),
+                }
+            }
+            _ => external_sibling_completions(tcx, r.def_id),
+        }
+    }
+
+    // The locals declared before `pos` in the enclosing function and the
+    // items of the enclosing module.
+    fn scope_completions(&self, pos: BytePos) -> Vec<Completion> {
+        let mut result = mod_completions(enclosing_mod(&self.krate.module, pos));
+
+        let enclosing_fn = self.fns.iter()
+                                   .filter(|s| contains(**s, pos))
+                                   .min_by(|s| s.hi.to_uint() - s.lo.to_uint());
+        match enclosing_fn {
+            Some(fn_span) => {
+                for r in self.refs.iter() {
+                    if r.is_decl && r.def_id.krate == ast::LOCAL_CRATE &&
+                       r.span.hi < pos && contains(*fn_span, r.span.lo) &&
+                       is_local(self.tcx(), r.def_id.node) {
+                        let name = self.sess().codemap().span_to_snippet(r.span);
+                        match name {
+                            Some(name) => result.push(Completion {
+                                name: name,
+                                kind: VariableCompletion,
+                            }),
+                            None => {}
+                        }
+                    }
+                }
+            }
+            None => {}
+        }
+        result
+    }
+}
+
+fn contains(span: Span, pos: BytePos) -> bool {
+    span.lo <= pos && pos <= span.hi
+}
+
+fn completion(name: ast::Name, kind: CompletionKind) -> Completion {
+    Completion {
+        name: token::get_name(name).get().to_string(),
+        kind: kind,
+    }
+}
+
+fn filter(completions: Vec<Completion>, prefix: &str) -> Vec<Completion> {
+    completions.move_iter().filter(|c| c.name.as_slice().starts_with(prefix)).collect()
+}
+
+fn is_local(tcx: &ty::ctxt, id: NodeId) -> bool {
+    match tcx.map.find(id) {
+        Some(ast_map::NodeLocal(_)) | Some(ast_map::NodeArg(_)) |
+        Some(ast_map::NodePat(_)) => true,
+        _ => false,
+    }
+}
+
+// Ignores the pointers a member access would auto-dereference.
+fn strip_pointers(t: ty::t) -> ty::t {
+    let mut t = t;
+    loop {
+        match ty::deref(t, true) {
+            Some(mt) => t = mt.ty,
+            None => return t,
+        }
+    }
+}
+
+fn member_completions(tcx: &ty::ctxt, t: ty::t) -> Vec<Completion> {
+    let mut result = vec!();
+    let def_id = match ty::get(strip_pointers(t)).sty {
+        ty::ty_struct(def_id, _) => {
+            for f in ty::lookup_struct_fields(tcx, def_id).iter() {
+                if f.name != special_idents::unnamed_field.name {
+                    result.push(completion(f.name, FieldCompletion));
+                }
+            }
+            def_id
+        }
+        ty::ty_enum(def_id, _) => def_id,
+        ty::ty_trait(box ty::TyTrait { def_id, .. }) => {
+            for m in ty::trait_methods(tcx, def_id).iter() {
+                result.push(completion(m.ident.name, MethodCompletion));
+            }
+            return result;
+        }
+        _ => return result,
+    };
+
+    ty::populate_implementations_for_type_if_necessary(tcx, def_id);
+    match tcx.inherent_impls.borrow().find(&def_id) {
+        Some(impls) => {
+            for impl_id in impls.borrow().iter() {
+                impl_method_completions(tcx, *impl_id, &mut result);
+            }
+        }
+        None => {}
+    }
+
+    // Trait methods are available whether the impl overrides them or not, so
+    // list the trait's methods rather than the impl's.
+    let mut traits = vec!();
+    for (&trait_id, impls) in tcx.trait_impls.borrow().iter() {
+        for &impl_id in impls.borrow().iter() {
+            let self_ty = ty::lookup_item_type(tcx, impl_id).ty;
+            match ty::get(self_ty).sty {
+                ty::ty_struct(id, _) | ty::ty_enum(id, _) if id == def_id => {
+                    traits.push(trait_id);
+                }
+                _ => {}
+            }
+        }
+    }
+    for trait_id in traits.iter() {
+        for m in ty::trait_methods(tcx, *trait_id).iter() {
+            result.push(completion(m.ident.name, MethodCompletion));
+        }
+    }
+    result
+}
+
+fn impl_method_completions(tcx: &ty::ctxt, impl_id: DefId, result: &mut Vec<Completion>) {
+    match tcx.impl_methods.borrow().find(&impl_id) {
+        Some(methods) => {
+            for &method_id in methods.iter() {
+                let method = ty::method(tcx, method_id);
+                result.push(completion(method.ident.name, MethodCompletion));
+            }
+        }
+        None => {}
+    }
+}
+
+// The innermost module (not counting modules inside functions) containing
+// `pos`.
+fn enclosing_mod<'a>(m: &'a ast::Mod, pos: BytePos) -> &'a ast::Mod {
+    for item in m.items.iter() {
+        match item.node {
+            ast::ItemMod(ref inner) if contains(inner.inner, pos) => {
+                return enclosing_mod(inner, pos);
+            }
+            _ => {}
+        }
+    }
+    m
+}
+
+fn mod_completions(m: &ast::Mod) -> Vec<Completion> {
+    let mut result = vec!();
+    for vi in m.view_items.iter() {
+        match vi.node {
+            ast::ViewItemExternCrate(ident, _, _) => {
+                result.push(completion(ident.name, ItemCompletion));
+            }
+            ast::ViewItemUse(ref vp) => match vp.node {
+                ast::ViewPathSimple(ident, _, _) => {
+                    result.push(completion(ident.name, ItemCompletion));
+                }
+                ast::ViewPathList(_, ref idents, _) => {
+                    for ident in idents.iter() {
+                        result.push(completion(ident.node.name.name, ItemCompletion));
+                    }
+                }
+                ast::ViewPathGlob(..) => {}
+            },
+        }
+    }
+    for item in m.items.iter() {
+        if generated_code(item.span) {
+            continue;
+        }
+        match item.node {
+            ast::ItemImpl(..) | ast::ItemMac(..) => {}
+            ast::ItemForeignMod(ref fm) => {
+                for fi in fm.items.iter() {
+                    result.push(completion(fi.ident.name, ItemCompletion));
+                }
+            }
+            _ => result.push(completion(item.ident.name, ItemCompletion)),
+        }
+    }
+    result
+}
+
+// The children of the module containing `def_id` in another crate. The
+// module is found by following the item's path from the crate root.
+fn external_sibling_completions(tcx: &ty::ctxt, def_id: DefId) -> Vec<Completion> {
+    let cstore = &tcx.sess.cstore;
+    let path = csearch::get_item_path(tcx, def_id);
+
+    let mut children = vec!();
+    csearch::each_top_level_item_of_crate(cstore, def_id.krate, |def, ident, _| {
+        children.push((def, ident));
+    });
+    // The first element is the crate and the last is the item itself.
+    for elem in path.slice(1, path.len() - 1).iter() {
+        let parent = children.iter().filter_map(|&(ref def, ident)| {
+            match *def {
+                decoder::DlDef(def::DefMod(id)) if ident.name == elem.name() => Some(id),
+                _ => None,
+            }
+        }).next();
+        children = vec!();
+        match parent {
+            Some(id) => csearch::each_child_of_item(cstore, id, |def, ident, _| {
+                children.push((def, ident));
+            }),
+            None => return vec!(),
+        }
+    }
+
+    children.iter().filter_map(|&(ref def, ident)| {
+        match *def {
+            decoder::DlDef(_) => Some(completion(ident.name, ItemCompletion)),
+            _ => None,
+        }
+    }).collect()
+}
+
+// Walks the crate and records every name whose definition is known.
+struct RefCollector<'l> {
+    tcx: &'l ty::ctxt,
+    span: SpanUtils<'l>,
+    refs: Vec<Reference>,
+    members: Vec<Member>,
+    fns: Vec<Span>,
+}
+
+impl<'l> RefCollector<'l> {
+    fn record_decl(&mut self, span: Option<Span>, id: NodeId) {
+        match span {
+            Some(span) => self.refs.push(Reference {
+                span: span,
+                def_id: local_def(id),
+                def: None,
+                is_decl: true,
+                qualified: false,
+            }),
+            None => {}
+        }
+    }
+
+    fn record_ref(&mut self, span: Option<Span>, def_id: DefId) {
+        match span {
+            Some(span) => self.refs.push(Reference {
+                span: span,
+                def_id: def_id,
+                def: None,
+                is_decl: false,
+                qualified: false,
+            }),
+            None => {}
+        }
+    }
+
+    // Records the last segment of `path`, which resolve mapped to a def
+    // under `id`.
+    fn record_path(&mut self, path: &ast::Path, id: NodeId) {
+        let def = match self.tcx.def_map.borrow().find(&id) {
+            Some(def) => def.clone(),
+            None => return,
+        };
+        let def_id = match canonical_def_id(self.tcx, &def) {
+            Some(def_id) => def_id,
+            None => return,
+        };
+        match self.span.span_for_last_ident(path.span) {
+            Some(span) => self.refs.push(Reference {
+                span: span,
+                def_id: def_id,
+                def: Some(def),
+                is_decl: false,
+                qualified: path.segments.len() > 1,
+            }),
+            None => {}
+        }
+    }
+
+    fn record_field(&mut self, span: Span, struct_ty: ty::t, name: ast::Name) {
+        match ty::get(struct_ty).sty {
+            ty::ty_struct(def_id, _) => {
+                let fields = ty::lookup_struct_fields(self.tcx, def_id);
+                match fields.iter().find(|f| f.name == name) {
+                    Some(f) => self.record_ref(Some(span), f.id),
+                    None => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn record_method_call(&mut self, ex: &ast::Expr, span: Span) {
+        let method_map = self.tcx.method_map.borrow();
+        let def_id = match method_map.find(&typeck::MethodCall::expr(ex.id)) {
+            Some(callee) => match callee.origin {
+                typeck::MethodStatic(def_id) => {
+                    ty::method(self.tcx, def_id).provided_source.unwrap_or(def_id)
+                }
+                typeck::MethodParam(mp) => {
+                    ty::trait_method(self.tcx, mp.trait_id, mp.method_num).def_id
+                }
+                typeck::MethodObject(mo) => {
+                    ty::trait_method(self.tcx, mo.trait_id, mo.method_num).def_id
+                }
+            },
+            None => return,
+        };
+        self.record_ref(Some(span), def_id);
+    }
+}
+
+// The def id references to `def` are grouped by, or None for defs without a
+// declaration worth navigating to.
+fn canonical_def_id(tcx: &ty::ctxt, def: &def::Def) -> Option<DefId> {
+    match *def {
+        def::DefPrimTy(..) | def::DefTyParam(..) | def::DefSelfTy(..) |
+        def::DefTyParamBinder(..) | def::DefRegion(..) | def::DefLabel(..) |
+        def::DefUse(..) => None,
+        def::DefUpvar(_, ref inner, _, _) => canonical_def_id(tcx, &**inner),
+        _ => {
+            // Tuple struct constructors are referred to by the id of their
+            // StructDef, but declared by the id of the item.
+            let def_id = def.def_id();
+            if def_id.krate == ast::LOCAL_CRATE {
+                match tcx.map.find(def_id.node) {
+                    Some(ast_map::NodeStructCtor(_)) => {
+                        return Some(local_def(tcx.map.get_parent(def_id.node)));
+                    }
+                    _ => {}
+                }
+            }
+            Some(def_id)
+        }
+    }
+}
+
+impl<'l> Visitor<()> for RefCollector<'l> {
+    fn visit_item(&mut self, item: &ast::Item, e: ()) {
+        if generated_code(item.span) {
+            return
+        }
+
+        match item.node {
+            ast::ItemImpl(..) | ast::ItemForeignMod(..) | ast::ItemMac(..) => {}
+            _ => {
+                let sub_span = self.span.sub_span_for_name(item.span, item.ident.name);
+                self.record_decl(sub_span, item.id);
+            }
+        }
+        visit::walk_item(self, item, e)
+    }
+
+    fn visit_foreign_item(&mut self, item: &ast::ForeignItem, e: ()) {
+        if generated_code(item.span) {
+            return
+        }
+
+        let sub_span = self.span.sub_span_for_name(item.span, item.ident.name);
+        self.record_decl(sub_span, item.id);
+        visit::walk_foreign_item(self, item, e)
+    }
+
+    fn visit_fn(&mut self, fk: &visit::FnKind, fd: &ast::FnDecl, b: &ast::Block,
+                s: Span, _: NodeId, e: ()) {
+        if generated_code(s) {
+            return
+        }
+
+        match *fk {
+            visit::FkMethod(_, _, m) => {
+                let sub_span = self.span.sub_span_for_name(m.span, m.pe_ident().name);
+                self.record_decl(sub_span, m.id);
+            }
+            _ => {}
+        }
+        self.fns.push(s);
+        visit::walk_fn(self, fk, fd, b, s, e)
+    }
+
+    fn visit_ty_method(&mut self, m: &ast::TypeMethod, e: ()) {
+        if generated_code(m.span) {
+            return
+        }
+
+        let sub_span = self.span.sub_span_for_name(m.span, m.ident.name);
+        self.record_decl(sub_span, m.id);
+        visit::walk_ty_method(self, m, e)
+    }
+
+    fn visit_struct_field(&mut self, f: &ast::StructField, e: ()) {
+        if generated_code(f.span) {
+            return
+        }
+
+        match f.node.kind {
+            ast::NamedField(ident, _) => {
+                let sub_span = self.span.sub_span_for_name(f.span, ident.name);
+                self.record_decl(sub_span, f.node.id);
+            }
+            ast::UnnamedField(_) => {}
+        }
+        visit::walk_struct_field(self, f, e)
+    }
+
+    fn visit_variant(&mut self, v: &ast::Variant, g: &ast::Generics, e: ()) {
+        if generated_code(v.span) {
+            return
+        }
+
+        let sub_span = self.span.sub_span_for_name(v.span, v.node.name.name);
+        self.record_decl(sub_span, v.node.id);
+        visit::walk_variant(self, v, g, e)
+    }
+
+    fn visit_ty(&mut self, t: &ast::Ty, e: ()) {
+        if generated_code(t.span) {
+            return
+        }
+
+        match t.node {
+            ast::TyPath(ref path, _, id) => self.record_path(path, id),
+            _ => {}
+        }
+        visit::walk_ty(self, t, e)
+    }
+
+    fn visit_pat(&mut self, p: &ast::Pat, e: ()) {
+        if generated_code(p.span) {
+            return
+        }
+
+        match p.node {
+            ast::PatIdent(_, ref path1, _) => {
+                // A lone identifier is either a new binding or a reference to
+                // a unit variant, struct or static.
+                let def = self.tcx.def_map.borrow().find(&p.id).map(|d| d.clone());
+                match def {
+                    Some(def::DefLocal(..)) | Some(def::DefArg(..)) |
+                    Some(def::DefBinding(..)) | None => {
+                        self.record_decl(Some(path1.span), p.id);
+                    }
+                    Some(_) => {
+                        let path = ast_util::ident_to_path(path1.span, path1.node);
+                        self.record_path(&path, p.id);
+                    }
+                }
+            }
+            ast::PatEnum(ref path, _) | ast::PatStruct(ref path, _, _) => {
+                self.record_path(path, p.id);
+            }
+            _ => {}
+        }
+        visit::walk_pat(self, p, e)
+    }
+
+    fn visit_expr(&mut self, ex: &ast::Expr, e: ()) {
+        if generated_code(ex.span) {
+            return
+        }
+
+        match ex.node {
+            ast::ExprPath(ref path) => self.record_path(path, ex.id),
+            ast::ExprStruct(ref path, ref fields, _) => {
+                self.record_path(path, ex.id);
+                let struct_ty = ty::expr_ty(self.tcx, ex);
+                for field in fields.iter() {
+                    if !generated_code(field.ident.span) {
+                        self.record_field(field.ident.span, struct_ty, field.ident.node.name);
+                    }
+                }
+            }
+            ast::ExprField(sub_ex, ref ident, _) => {
+                if !generated_code(ident.span) {
+                    let t = ty::expr_ty_adjusted(self.tcx, &*sub_ex);
+                    self.record_field(ident.span, t, ident.node.name);
+                    self.members.push(Member { span: ident.span, ty: t });
+                }
+            }
+            ast::ExprMethodCall(ref ident, _, ref args) => {
+                if !generated_code(ident.span) {
+                    self.record_method_call(ex, ident.span);
+                    let t = ty::expr_ty_adjusted(self.tcx, &**args.get(0));
+                    self.members.push(Member { span: ident.span, ty: t });
+                }
+            }
+            _ => {}
+        }
+        visit::walk_expr(self, ex, e)
+    }
+
+    fn visit_mac(&mut self, _: &ast::Mac, _: ()) {
+        // Macros have been expanded; the invocations themselves name nothing.
+    }
+}
+
+// Finds the innermost expression or pattern containing a position.
+struct NodeFinder {
+    pos: BytePos,
+    best: Option<(Span, NodeId)>,
+}
+
+impl NodeFinder {
+    fn consider(&mut self, span: Span, id: NodeId) {
+        if generated_code(span) || !contains(span, self.pos) {
+            return
+        }
+        let better = match self.best {
+            Some((best, _)) => span.hi.to_uint() - span.lo.to_uint() <=
+                               best.hi.to_uint() - best.lo.to_uint(),
+            None => true,
+        };
+        if better {
+            self.best = Some((span, id));
+        }
+    }
+}
+
+impl Visitor<()> for NodeFinder {
+    fn visit_expr(&mut self, ex: &ast::Expr, e: ()) {
+        self.consider(ex.span, ex.id);
+        visit::walk_expr(self, ex, e)
+    }
+
+    fn visit_pat(&mut self, p: &ast::Pat, e: ()) {
+        self.consider(p.span, p.id);
+        visit::walk_pat(self, p, e)
+    }
+}
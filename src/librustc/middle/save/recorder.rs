@@ -8,15 +8,47 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use metadata::decoder;
 use middle::save::escape;
 use middle::save::span_utils::SpanUtils;
 
+use std::collections::TreeMap;
+use std::mem;
 use std::vec::Vec;
 
+use serialize::json;
+
 use syntax::ast;
 use syntax::ast::{NodeId,DefId};
+use syntax::ast_map;
 use syntax::codemap::*;
 
+/// The version of the schema written by `-Z save-analysis-json`. Bump this
+/// whenever a row kind or field is added, removed or changes meaning.
+pub static JSON_SCHEMA_VERSION: uint = 2;
+
+// Fields holding a definition's node id and crate number, and the field its
+// crate-independent id is written to when the crate is not the local one.
+static EXTERNAL_ID_FIELDS: [(&'static str, &'static str, &'static str), ..3] = [
+    ("refid", "refidcrate", "refextid"),
+    ("declid", "declidcrate", "declextid"),
+    ("base", "basecrate", "baseextid"),
+];
+
+// Identifies a definition in another crate independently of the crate
+// numbers of the crate being compiled, so that dumps of several crates can be
+// joined. `qualname` matches the `qualname` in the defining crate's own dump.
+struct ExternalId {
+    krate: String,
+    svh: String,
+    qualname: String,
+}
+
+pub enum Format {
+    CsvFormat,
+    JsonFormat,
+}
+
 pub struct Recorder {
     // output file
     pub out: Box<Writer>,
@@ -47,6 +79,9 @@ pub struct FmtStrs<'a> {
     pub recorder: Box<Recorder>,
     span: SpanUtils<'a>,
     krate: String,
+    format: Format,
+    // Rows waiting to be written as one JSON document, with their section.
+    json_rows: Vec<(&'static str, json::Json)>,
 }
 
 macro_rules! s { ($e:expr) => { format!("{}", $e) }}
@@ -83,14 +118,22 @@ pub enum Row {
     TypeRef,
     StructRef,
     FnRef,
+    MacroUse,
+    Reexport,
+    ReexportRef,
 }
 
 impl<'a> FmtStrs<'a> {
-    pub fn new(rec: Box<Recorder>, span: SpanUtils<'a>, krate: String) -> FmtStrs<'a> {
+    pub fn new(rec: Box<Recorder>,
+               span: SpanUtils<'a>,
+               krate: String,
+               format: Format) -> FmtStrs<'a> {
         FmtStrs {
             recorder: rec,
             span: span,
             krate: krate,
+            format: format,
+            json_rows: vec!(),
         }
     }
 
@@ -128,8 +171,10 @@ impl<'a> FmtStrs<'a> {
                            vec!("refid","refidcrate","declid","declidcrate","scopeid"),
                             true, true),
             Typedef => ("typedef", vec!("id","qualname","value"), true, true),
-            ExternalCrate => ("external_crate", vec!("name","crate","file_name"), false, false),
-            Crate => ("crate", vec!("name"), true, false),
+            ExternalCrate => ("external_crate",
+                              vec!("name","crate","file_name","svh"),
+                              false, false),
+            Crate => ("crate", vec!("name","svh"), true, false),
             FnCall => ("fn_call", vec!("refid","refidcrate","qualname","scopeid"), true, true),
             ModRef => ("mod_ref", vec!("refid","refidcrate","qualname","scopeid"), true, true),
             VarRef => ("var_ref", vec!("refid","refidcrate","qualname","scopeid"), true, true),
@@ -139,7 +184,165 @@ impl<'a> FmtStrs<'a> {
             StructRef => ("struct_ref",
                           vec!("refid","refidcrate","qualname","scopeid"),
                            true, true),
-            FnRef => ("fn_ref", vec!("refid","refidcrate","qualname","scopeid"), true, true)
+            FnRef => ("fn_ref", vec!("refid","refidcrate","qualname","scopeid"), true, true),
+            MacroUse => ("macro_use", vec!("name","scopeid"), true, true),
+            Reexport => ("reexport",
+                         vec!("id","qualname","refid","refidcrate","original","scopeid"),
+                         true, true),
+            ReexportRef => ("reexport_ref",
+                            vec!("refid","refidcrate","alias","original","scopeid"),
+                            true, true),
+        }
+    }
+
+    // The section of the JSON document rows of each kind are written to.
+    fn json_section(r: Row) -> &'static str {
+        match r {
+            Crate => "crate",
+            ExternalCrate => "external_crates",
+            Impl | Inheritance => "impls",
+            MethodCall | FnCall | ModRef | VarRef | TypeRef | StructRef | FnRef |
+            ReexportRef => "refs",
+            MacroUse => "macro_uses",
+            _ => "defs",
+        }
+    }
+
+    // Converts one field to its JSON key and value. Ids are numbers (null if
+    // the row has none), `scopeid` is renamed to `parent`, and everything else
+    // is a string.
+    fn json_field(&self, field: &str, value: &str) -> (String, json::Json) {
+        match field {
+            "qualname" => {
+                let qualname = self.krate.clone().append("::").append(value);
+                (field.to_string(), json::String(qualname))
+            }
+            "scopeid" => ("parent".to_string(), json_number(value)),
+            "id" | "ctor_id" | "refid" | "refidcrate" | "declid" | "declidcrate" |
+            "base" | "basecrate" | "derived" | "derivedcrate" | "crate" => {
+                (field.to_string(), json_number(value))
+            }
+            _ => (field.to_string(), json::String(value.to_string())),
+        }
+    }
+
+    fn record_json(&mut self,
+                   kind: Row,
+                   label: &'static str,
+                   fields: &Vec<&'static str>,
+                   values: Vec<String>,
+                   span: Span,
+                   sub_span: Option<Span>) {
+        if values.len() != fields.len() {
+            self.span.sess.span_bug(span, format!(
+                "Mismatch between length of fields for '{}', expected '{}', found '{}'",
+                label, fields.len(), values.len()).as_slice());
+        }
+
+        let mut row = TreeMap::new();
+        row.insert("kind".to_string(), json::String(label.to_string()));
+        for (f, v) in fields.iter().zip(values.iter()) {
+            let (key, value) = self.json_field(*f, v.as_slice());
+            row.insert(key, value);
+        }
+        for (field, ext) in self.external_ids(fields, &values).move_iter() {
+            let mut id = TreeMap::new();
+            id.insert("crate".to_string(), json::String(ext.krate));
+            id.insert("svh".to_string(), json::String(ext.svh));
+            id.insert("qualname".to_string(), json::String(ext.qualname));
+            row.insert(field.to_string(), json::Object(id));
+        }
+        match sub_span {
+            Some(sub_span) => {
+                row.insert("span".to_string(), self.span.extent_json(sub_span));
+            }
+            None => {}
+        }
+        self.json_rows.push((FmtStrs::json_section(kind), json::Object(row)));
+    }
+
+    // The crate-independent ids of the definitions a row refers to in other
+    // crates, with the field each is written to.
+    fn external_ids(&self,
+                    fields: &Vec<&'static str>,
+                    values: &Vec<String>) -> Vec<(&'static str, ExternalId)> {
+        let mut result = vec!();
+        for &(id_field, crate_field, ext_field) in EXTERNAL_ID_FIELDS.iter() {
+            let id = fields.iter().position(|f| *f == id_field);
+            let krate = fields.iter().position(|f| *f == crate_field);
+            let (id, krate) = match (id, krate) {
+                (Some(id), Some(krate)) => (values.get(id), values.get(krate)),
+                _ => continue,
+            };
+            let (id, krate) = match (from_str::<NodeId>(id.as_slice()),
+                                     from_str::<ast::CrateNum>(krate.as_slice())) {
+                (Some(id), Some(krate)) if krate != ast::LOCAL_CRATE => (id, krate),
+                _ => continue,
+            };
+
+            let cstore = &self.span.sess.cstore;
+            let cdata = cstore.get_crate_data(krate);
+            let path = decoder::get_item_path(&*cdata, id);
+            result.push((ext_field, ExternalId {
+                krate: cdata.name.clone(),
+                svh: cstore.get_crate_hash(krate).as_str().to_string(),
+                qualname: cdata.name.clone().append("::")
+                                    .append(ast_map::path_to_string(path.move_iter()).as_slice()),
+            }));
+        }
+        result
+    }
+
+    // Prefixes a path within the local crate the way `qualname` fields are.
+    pub fn local_qualname(&self, path: &str) -> String {
+        self.krate.clone().append("::").append(path)
+    }
+
+    // Writes out everything recorded so far. Only the JSON format buffers
+    // rows, since its sections are written one after the other.
+    pub fn finish(&mut self) {
+        match self.format {
+            CsvFormat => return,
+            JsonFormat => {}
+        }
+
+        let mut krate = json::Null;
+        let mut external_crates = vec!();
+        let mut defs = vec!();
+        let mut refs = vec!();
+        let mut impls = vec!();
+        let mut macro_uses = vec!();
+        for (section, row) in mem::replace(&mut self.json_rows, vec!()).move_iter() {
+            match section {
+                "crate" => krate = row,
+                "external_crates" => external_crates.push(row),
+                "refs" => refs.push(row),
+                "impls" => impls.push(row),
+                "macro_uses" => macro_uses.push(row),
+                _ => defs.push(row),
+            }
+        }
+
+        let mut doc = TreeMap::new();
+        doc.insert("version".to_string(), json::Number(JSON_SCHEMA_VERSION as f64));
+        doc.insert("crate".to_string(), krate);
+        doc.insert("external_crates".to_string(), json::List(external_crates));
+        doc.insert("defs".to_string(), json::List(defs));
+        doc.insert("refs".to_string(), json::List(refs));
+        doc.insert("impls".to_string(), json::List(impls));
+        doc.insert("macro_uses".to_string(), json::List(macro_uses));
+
+        match json::Object(doc).to_writer(&mut *self.recorder.out) {
+            Err(_) => error!("Error writing JSON output"),
+            _ => (),
+        }
+    }
+
+    // Marks the end of the crate-level rows in the CSV output.
+    pub fn end_external_crates(&mut self) {
+        match self.format {
+            CsvFormat => self.recorder.record("end_external_crates\n"),
+            JsonFormat => {}
         }
     }
 
@@ -154,6 +357,8 @@ impl<'a> FmtStrs<'a> {
                 kind, fields.len(), values.len()).as_slice());
         }
 
+        let ext_ids = self.external_ids(fields, &values);
+
         let values = values.iter().map(|s| {
             if s.len() > 1020 {
                 s.as_slice().slice_to(1020)
@@ -170,7 +375,12 @@ impl<'a> FmtStrs<'a> {
                 String::from_str(v)
             }
         )));
-        Some(strs.fold(String::new(), |s, ss| s.append(ss.as_slice())))
+        let strs = strs.fold(String::new(), |s, ss| s.append(ss.as_slice()));
+        Some(ext_ids.iter().fold(strs, |s, &(f, ref ext)| {
+            s.append(format!(",{},\"{}/{}/{}\"",
+                             f, escape(ext.krate.clone()), ext.svh,
+                             escape(ext.qualname.clone())).as_slice())
+        }))
     }
 
     pub fn record_without_span(&mut self,
@@ -190,6 +400,11 @@ impl<'a> FmtStrs<'a> {
             return;
         }
 
+        match self.format {
+            JsonFormat => return self.record_json(kind, label, fields, values, span, None),
+            CsvFormat => {}
+        }
+
         let values_str = match self.make_values_str(label, fields, values, span) {
             Some(vs) => vs,
             None => return,
@@ -219,6 +434,13 @@ impl<'a> FmtStrs<'a> {
                                              which does not require a span", label).as_slice());
         }
 
+        match self.format {
+            JsonFormat => {
+                return self.record_json(kind, label, fields, values, span, Some(sub_span))
+            }
+            CsvFormat => {}
+        }
+
         let values_str = match self.make_values_str(label, fields, values, span) {
             Some(vs) => vs,
             None => return,
@@ -511,6 +733,48 @@ impl<'a> FmtStrs<'a> {
                               svec!(dfn, dfk, dcn, dck, scope_id));
     }
 
+    pub fn macro_use_str(&mut self,
+                         span: Span,
+                         sub_span: Option<Span>,
+                         name: &str,
+                         scope_id: NodeId) {
+        self.check_and_record(MacroUse,
+                              span,
+                              sub_span,
+                              svec!(name, scope_id));
+    }
+
+    // `pub use` of `ref_id` under the name `qualname`. `original` is the
+    // qualified path `ref_id` was declared at.
+    pub fn reexport_str(&mut self,
+                        span: Span,
+                        sub_span: Option<Span>,
+                        id: NodeId,
+                        qualname: &str,
+                        ref_id: DefId,
+                        original: &str,
+                        scope_id: NodeId) {
+        self.check_and_record(Reexport,
+                              span,
+                              sub_span,
+                              svec!(id, qualname, ref_id.node, ref_id.krate, original, scope_id));
+    }
+
+    // A reference to `ref_id` through the path `alias`, which differs from
+    // the path it was declared at.
+    pub fn reexport_ref_str(&mut self,
+                            span: Span,
+                            sub_span: Option<Span>,
+                            ref_id: DefId,
+                            alias: &str,
+                            original: &str,
+                            scope_id: NodeId) {
+        self.check_and_record(ReexportRef,
+                              span,
+                              sub_span,
+                              svec!(ref_id.node, ref_id.krate, alias, original, scope_id));
+    }
+
     pub fn sub_mod_ref_str(&mut self,
                            span: Span,
                            sub_span: Span,
@@ -536,11 +800,12 @@ impl<'a> FmtStrs<'a> {
 
     pub fn crate_str(&mut self,
                      span: Span,
-                     name: &str) {
+                     name: &str,
+                     svh: &str) {
         self.record_with_span(Crate,
                               span,
                               span,
-                              svec!(name));
+                              svec!(name, svh));
     }
 
     pub fn external_crate_str(&mut self,
@@ -548,8 +813,9 @@ impl<'a> FmtStrs<'a> {
                               name: &str,
                               num: ast::CrateNum) {
         let lo_loc = self.span.sess.codemap().lookup_char_pos(span.lo);
+        let svh = self.span.sess.cstore.get_crate_hash(num);
         self.record_without_span(ExternalCrate,
-                                 svec!(name, num, lo_loc.file.name),
+                                 svec!(name, num, lo_loc.file.name, svh.as_str()),
                                  span);
     }
 
@@ -576,3 +842,10 @@ impl<'a> FmtStrs<'a> {
                               svec!(id.node, id.krate, "", scope_id));
     }
 }
+
+fn json_number(value: &str) -> json::Json {
+    match from_str::<i64>(value) {
+        Some(n) => json::Number(n as f64),
+        None => json::Null,
+    }
+}
@@ -13,6 +13,9 @@ use driver::session::Session;
 use middle::save::generated_code;
 
 use std::cell::Cell;
+use std::collections::TreeMap;
+
+use serialize::json;
 
 use syntax::ast;
 use syntax::codemap::*;
@@ -40,6 +43,24 @@ impl<'a> SpanUtils<'a> {
                 hi_loc.line, hi_loc.col.to_uint(), hi_pos.to_uint())
     }
 
+    // The same extent as `extent_str`, as a JSON object.
+    pub fn extent_json(&self, span: Span) -> json::Json {
+        let lo_loc = self.sess.codemap().lookup_char_pos(span.lo);
+        let hi_loc = self.sess.codemap().lookup_char_pos(span.hi);
+        let lo_pos = self.sess.codemap().lookup_byte_offset(span.lo).pos;
+        let hi_pos = self.sess.codemap().lookup_byte_offset(span.hi).pos;
+
+        let mut extent = TreeMap::new();
+        extent.insert("file_name".to_string(), json::String(lo_loc.file.name.clone()));
+        extent.insert("line_start".to_string(), json::Number(lo_loc.line as f64));
+        extent.insert("column_start".to_string(), json::Number(lo_loc.col.to_uint() as f64));
+        extent.insert("byte_start".to_string(), json::Number(lo_pos.to_uint() as f64));
+        extent.insert("line_end".to_string(), json::Number(hi_loc.line as f64));
+        extent.insert("column_end".to_string(), json::Number(hi_loc.col.to_uint() as f64));
+        extent.insert("byte_end".to_string(), json::Number(hi_pos.to_uint() as f64));
+        json::Object(extent)
+    }
+
     // sub_span starts at span.lo, so we need to adjust the positions etc.
     // If sub_span is None, we don't need to adjust.
     pub fn make_sub_span(&self, span: Span, sub_span: Option<Span>) -> Option<Span> {
@@ -348,6 +369,21 @@ impl<'a> SpanUtils<'a> {
         }
     }
 
+    // Return the span for the first occurrence of the identifier `name`.
+    pub fn sub_span_for_name(&self, span: Span, name: ast::Name) -> Option<Span> {
+        let mut toks = self.retokenise_span(span);
+        loop {
+            let ts = toks.next_token();
+            match ts.tok {
+                token::EOF => return None,
+                token::IDENT(ident, _) if ident.name == name => {
+                    return self.make_sub_span(span, Some(ts.sp));
+                }
+                _ => {}
+            }
+        }
+    }
+
     // Returns a list of the spans of idents in a patch.
     // E.g., For foo::bar<x,t>::baz, we return [foo, bar, baz] (well, their spans)
     pub fn spans_for_path_segments(&self, path: &ast::Path) -> Vec<Span> {
@@ -0,0 +1,294 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Finds the public items of a library which none of the crates depending on
+//! it use, given their save-analysis output with `--downstream-analysis`.
+//!
+//! The output of `-Z save-analysis` and `-Z save-analysis-json` names every
+//! definition a crate refers to in another crate by its crate-independent id:
+//! the other crate's name and SVH and the definition's qualified path (see
+//! the `middle::save` module). Each exported function, inherent method and
+//! static of this crate, and each public field of its exported structs, whose
+//! qualified path is not named by any of the given dumps is reported with the
+//! `unused_pub_items` lint.
+//!
+//! Since the qualified paths don't depend on the build, dumps made against an
+//! older build of this crate are still used, with a warning. The dumps don't
+//! record anything inside macro expansions, so items only used by macros in
+//! the dependent crates are reported as well.
+
+use back::svh::Svh;
+use lint::builtin::UNUSED_PUB_ITEMS;
+use middle::privacy::ExportedItems;
+use middle::ty;
+
+use std::collections::HashSet;
+use std::io::File;
+use std::io::fs;
+
+use serialize::json;
+
+use syntax::ast;
+use syntax::ast_util::PostExpansionMethod;
+use syntax::attr;
+use syntax::codemap::Span;
+use syntax::parse::token;
+use syntax::visit;
+use syntax::visit::Visitor;
+
+// The fields of a dump row holding the crate-independent id of a definition
+// in another crate.
+static EXTERNAL_ID_FIELDS: [&'static str, ..3] = ["refextid", "declextid", "baseextid"];
+
+/// Report the public items of `krate` which the dumps given with
+/// `--downstream-analysis` never refer to.
+pub fn check_crate(tcx: &ty::ctxt,
+                   krate: &ast::Crate,
+                   exported_items: &ExportedItems,
+                   crate_name: &str) {
+    let svh = Svh::calculate(&tcx.sess.opts.cg.metadata, krate);
+    let mut used = HashSet::new();
+    for path in tcx.sess.opts.downstream_analysis.iter() {
+        for dump in dump_files(tcx, path).iter() {
+            read_dump(tcx, dump, crate_name, svh.as_str(), &mut used);
+        }
+    }
+    tcx.sess.abort_if_errors();
+
+    let mut visitor = UnusedPubVisitor {
+        tcx: tcx,
+        exported_items: exported_items,
+        crate_name: crate_name,
+        used: &used,
+    };
+    visit::walk_crate(&mut visitor, krate, ());
+}
+
+// The dumps at `path`: the file itself, or the `.csv` and `.json` files in it
+// if it is a directory.
+fn dump_files(tcx: &ty::ctxt, path: &Path) -> Vec<Path> {
+    if !path.is_dir() {
+        return vec!(path.clone());
+    }
+    match fs::readdir(path) {
+        Ok(mut files) => {
+            files.retain(|file| {
+                file.extension_str() == Some("csv") || file.extension_str() == Some("json")
+            });
+            files.sort();
+            files
+        }
+        Err(e) => {
+            tcx.sess.err(format!("failed to read {}: {}", path.display(), e).as_slice());
+            vec!()
+        }
+    }
+}
+
+// Add the qualified paths of the definitions in `crate_name` which `dump`
+// refers to to `used`.
+fn read_dump(tcx: &ty::ctxt, dump: &Path, crate_name: &str, svh: &str,
+             used: &mut HashSet<String>) {
+    let contents = match File::open(dump).read_to_string() {
+        Ok(contents) => contents,
+        Err(e) => {
+            tcx.sess.err(format!("failed to read {}: {}", dump.display(), e).as_slice());
+            return;
+        }
+    };
+
+    let ids = if dump.extension_str() == Some("json") {
+        json_external_ids(contents.as_slice())
+    } else {
+        Some(csv_external_ids(contents.as_slice()))
+    };
+    let ids = match ids {
+        Some(ids) => ids,
+        None => {
+            tcx.sess.err(format!("{} is not save-analysis output",
+                                 dump.display()).as_slice());
+            return;
+        }
+    };
+
+    let mut stale = false;
+    for (krate, id_svh, qualname) in ids.move_iter() {
+        if krate.as_slice() != crate_name {
+            continue;
+        }
+        if id_svh.as_slice() != svh {
+            stale = true;
+        }
+        used.insert(qualname);
+    }
+    if stale {
+        tcx.sess.warn(format!("{} was made against a different build of `{}`",
+                              dump.display(), crate_name).as_slice());
+    }
+}
+
+// The `(crate, svh, qualname)` ids in the rows of a CSV dump, where they are
+// written as `crate/svh/qualname`.
+fn csv_external_ids(contents: &str) -> Vec<(String, String, String)> {
+    let mut ids = vec!();
+    for line in contents.lines() {
+        let fields = split_csv_line(line);
+        // The first field is the row's kind, followed by name and value pairs.
+        for pair in fields.slice_from(1).chunks(2) {
+            if pair.len() != 2 || !EXTERNAL_ID_FIELDS.iter().any(|f| *f == pair[0].as_slice()) {
+                continue;
+            }
+            let mut parts = pair[1].as_slice().splitn('/', 2);
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(krate), Some(svh), Some(qualname)) => {
+                    ids.push((krate.to_string(), svh.to_string(), qualname.to_string()));
+                }
+                _ => {}
+            }
+        }
+    }
+    ids
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec!();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    loop {
+        match chars.next() {
+            Some('"') if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push_char('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            Some('"') => quoted = true,
+            Some(',') if !quoted => {
+                fields.push(field);
+                field = String::new();
+            }
+            Some(c) => field.push_char(c),
+            None => {
+                fields.push(field);
+                return fields;
+            }
+        }
+    }
+}
+
+// The `(crate, svh, qualname)` ids in the rows of a JSON dump, or `None` if
+// `contents` is not a JSON dump.
+fn json_external_ids(contents: &str) -> Option<Vec<(String, String, String)>> {
+    let doc = match json::from_str(contents) {
+        Ok(doc) => doc,
+        Err(..) => return None,
+    };
+    if doc.find(&"version".to_string()).and_then(|v| v.as_number()).is_none() {
+        return None;
+    }
+
+    let mut ids = vec!();
+    for section in ["defs", "refs", "impls"].iter() {
+        let rows = match doc.find(&section.to_string()).and_then(|rows| rows.as_list()) {
+            Some(rows) => rows,
+            None => continue,
+        };
+        for row in rows.iter() {
+            for field in EXTERNAL_ID_FIELDS.iter() {
+                let id = match row.find(&field.to_string()) {
+                    Some(id) => id,
+                    None => continue,
+                };
+                let part = |name: &str| {
+                    id.find(&name.to_string()).and_then(|s| s.as_string())
+                };
+                match (part("crate"), part("svh"), part("qualname")) {
+                    (Some(krate), Some(svh), Some(qualname)) => {
+                        ids.push((krate.to_string(), svh.to_string(), qualname.to_string()));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    Some(ids)
+}
+
+struct UnusedPubVisitor<'a> {
+    tcx: &'a ty::ctxt,
+    exported_items: &'a ExportedItems,
+    crate_name: &'a str,
+    // The qualified paths of the items of this crate used downstream.
+    used: &'a HashSet<String>,
+}
+
+impl<'a> UnusedPubVisitor<'a> {
+    // Report `path`, the path of the item `id` within this crate, if it is
+    // not used downstream.
+    fn check(&self, id: ast::NodeId, span: Span, kind: &str, path: String) {
+        let qualname = format!("{}::{}", self.crate_name, path);
+        if !self.used.contains(&qualname) {
+            self.tcx.sess.add_lint(UNUSED_PUB_ITEMS, id, span,
+                                   format!("public {} `{}` is not used by any downstream crate",
+                                           kind, path));
+        }
+    }
+}
+
+impl<'a> Visitor<()> for UnusedPubVisitor<'a> {
+    fn visit_item(&mut self, item: &ast::Item, _: ()) {
+        let exported = self.exported_items.contains(&item.id);
+        match item.node {
+            // `#[no_mangle]` functions and statics are meant for callers
+            // outside of Rust.
+            _ if attr::contains_name(item.attrs.as_slice(), "no_mangle") => {}
+            ast::ItemFn(..) if exported => {
+                self.check(item.id, item.span, "function",
+                           self.tcx.map.path_to_string(item.id));
+            }
+            ast::ItemStatic(..) if exported => {
+                self.check(item.id, item.span, "static",
+                           self.tcx.map.path_to_string(item.id));
+            }
+            // Trait methods must be implemented whether they are used or not,
+            // so only inherent methods are checked.
+            ast::ItemImpl(_, None, _, ref methods) => {
+                for method in methods.iter() {
+                    if method.pe_vis() == ast::Public &&
+                       self.exported_items.contains(&method.id) {
+                        self.check(method.id, method.span, "method",
+                                   self.tcx.map.path_to_string(method.id));
+                    }
+                }
+            }
+            ast::ItemStruct(ref struct_def, _) if exported => {
+                for field in struct_def.fields.iter() {
+                    match field.node.kind {
+                        ast::NamedField(ident, ast::Public) => {
+                            let path = format!("{}::{}",
+                                               self.tcx.map.path_to_string(item.id),
+                                               token::get_ident(ident));
+                            self.check(field.node.id, field.span, "field", path);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+        visit::walk_item(self, item, ());
+    }
+
+    // Items in function bodies can't be used from other crates.
+    fn visit_block(&mut self, _: &ast::Block, _: ()) {}
+}
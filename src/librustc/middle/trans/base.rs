@@ -34,9 +34,11 @@ use driver::driver::{CrateAnalysis, CrateTranslation};
 use llvm;
 use llvm::{ModuleRef, ValueRef, BasicBlockRef};
 use llvm::{Vector};
-use metadata::{csearch, encoder, loader};
+use metadata::{csearch, cstore, encoder, loader};
 use lint;
 use middle::astencode;
+use middle::privacy;
+use middle::reachable;
 use middle::lang_items::{LangItem, ExchangeMallocFnLangItem, StartFnLangItem};
 use middle::weak_lang_items;
 use middle::subst;
@@ -73,12 +75,13 @@ use middle::typeck;
 use util::common::indenter;
 use util::ppaux::{Repr, ty_to_string};
 use util::sha2::Sha256;
-use util::nodemap::NodeMap;
+use util::nodemap::{NodeMap, NodeSet};
 
 use arena::TypedArena;
 use libc::{c_uint, uint64_t};
 use std::c_str::ToCStr;
 use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::rc::Rc;
 use std::{i8, i16, i32, i64};
 use std::gc::Gc;
@@ -87,6 +90,7 @@ use syntax::ast_util::{local_def, is_local};
 use syntax::attr::AttrMetaMethods;
 use syntax::attr;
 use syntax::codemap::Span;
+use syntax::parse::token;
 use syntax::parse::token::InternedString;
 use syntax::visit::Visitor;
 use syntax::visit;
@@ -201,6 +205,23 @@ fn decl_fn(ccx: &CrateContext, name: &str, cc: llvm::CallConv,
         }
     }
 
+    // The sanitizer passes only instrument functions which ask for it
+    match ccx.tcx.sess.opts.sanitizer {
+        Some(sanitizer) => {
+            let attr = match sanitizer {
+                config::AddressSanitizer => llvm::SanitizeAddressAttribute,
+                config::ThreadSanitizer => llvm::SanitizeThreadAttribute,
+                config::MemorySanitizer => llvm::SanitizeMemoryAttribute,
+            };
+            unsafe {
+                llvm::LLVMAddFunctionAttribute(llfn,
+                                               llvm::FunctionIndex as c_uint,
+                                               attr as uint64_t)
+            }
+        }
+        None => {}
+    }
+
     llvm::SetFunctionCallConv(llfn, cc);
     // Function addresses in Rust are never significant, allowing functions to be merged.
     llvm::SetUnnamedAddr(llfn, true);
@@ -864,11 +885,67 @@ pub fn fail_if_zero_or_overflows<'a>(
     }
 }
 
+/// Translate `+`, `-` or `*` on integers of type `t` with LLVM's overflow
+/// intrinsics, failing on overflow. Used for `-C overflow-checks`.
+pub fn trans_checked_int_binop<'a>(bcx: &'a Block<'a>,
+                                   span: Span,
+                                   op: ast::BinOp,
+                                   t: ty::t,
+                                   lhs: ValueRef,
+                                   rhs: ValueRef)
+                                   -> Result<'a> {
+    let is_signed = ty::type_is_signed(t);
+    let width = machine::llbitsize_of_real(bcx.ccx(), val_ty(lhs));
+    let name = match (op, is_signed, width) {
+        (ast::BiAdd, true, 8) => "llvm.sadd.with.overflow.i8",
+        (ast::BiAdd, true, 16) => "llvm.sadd.with.overflow.i16",
+        (ast::BiAdd, true, 32) => "llvm.sadd.with.overflow.i32",
+        (ast::BiAdd, true, 64) => "llvm.sadd.with.overflow.i64",
+        (ast::BiAdd, false, 8) => "llvm.uadd.with.overflow.i8",
+        (ast::BiAdd, false, 16) => "llvm.uadd.with.overflow.i16",
+        (ast::BiAdd, false, 32) => "llvm.uadd.with.overflow.i32",
+        (ast::BiAdd, false, 64) => "llvm.uadd.with.overflow.i64",
+        (ast::BiSub, true, 8) => "llvm.ssub.with.overflow.i8",
+        (ast::BiSub, true, 16) => "llvm.ssub.with.overflow.i16",
+        (ast::BiSub, true, 32) => "llvm.ssub.with.overflow.i32",
+        (ast::BiSub, true, 64) => "llvm.ssub.with.overflow.i64",
+        (ast::BiSub, false, 8) => "llvm.usub.with.overflow.i8",
+        (ast::BiSub, false, 16) => "llvm.usub.with.overflow.i16",
+        (ast::BiSub, false, 32) => "llvm.usub.with.overflow.i32",
+        (ast::BiSub, false, 64) => "llvm.usub.with.overflow.i64",
+        (ast::BiMul, true, 8) => "llvm.smul.with.overflow.i8",
+        (ast::BiMul, true, 16) => "llvm.smul.with.overflow.i16",
+        (ast::BiMul, true, 32) => "llvm.smul.with.overflow.i32",
+        (ast::BiMul, true, 64) => "llvm.smul.with.overflow.i64",
+        (ast::BiMul, false, 8) => "llvm.umul.with.overflow.i8",
+        (ast::BiMul, false, 16) => "llvm.umul.with.overflow.i16",
+        (ast::BiMul, false, 32) => "llvm.umul.with.overflow.i32",
+        (ast::BiMul, false, 64) => "llvm.umul.with.overflow.i64",
+        _ => {
+            bcx.sess().span_bug(span, format!("overflow check on unexpected {}-bit {}",
+                                              width, ty_to_string(bcx.tcx(), t)).as_slice())
+        }
+    };
+    let text = match op {
+        ast::BiAdd => "attempted to add with overflow",
+        ast::BiSub => "attempted to subtract with overflow",
+        _ => "attempted to multiply with overflow",
+    };
+    let llfn = bcx.ccx().get_intrinsic(&name);
+    let val = Call(bcx, llfn, [lhs, rhs], []);
+    let result = ExtractValue(bcx, val, 0);
+    let overflowed = ExtractValue(bcx, val, 1);
+    let bcx = with_cond(bcx, overflowed, |bcx| {
+        controlflow::trans_fail(bcx, span, InternedString::new(text))
+    });
+    Result::new(bcx, result)
+}
+
 pub fn trans_external_path(ccx: &CrateContext, did: ast::DefId, t: ty::t) -> ValueRef {
     let name = csearch::get_symbol(&ccx.sess().cstore, did);
     match ty::get(t).sty {
         ty::ty_bare_fn(ref fn_ty) => {
-            match fn_ty.abi.for_target(ccx.sess().targ_cfg.os,
+            match fn_ty.abi.for_target(ccx.sess().targ_cfg.target.options.is_like_windows,
                                        ccx.sess().targ_cfg.arch) {
                 Some(Rust) => {
                     get_extern_rust_fn(ccx, t, name.as_slice(), did)
@@ -1760,6 +1837,10 @@ pub fn trans_mod(ccx: &CrateContext, m: &ast::Mod) {
 
 fn finish_register_fn(ccx: &CrateContext, sp: Span, sym: String, node_id: ast::NodeId,
                       llfn: ValueRef) {
+    if ccx.sess().llvm_fn_stats() {
+        ccx.stats.fn_items.borrow_mut().insert(sym.clone(),
+                                               ccx.tcx.map.path_to_string(node_id));
+    }
     ccx.item_symbols.borrow_mut().insert(node_id, sym);
 
     if !ccx.reachable.contains(&node_id) {
@@ -2012,6 +2093,79 @@ pub fn create_entry_wrapper(ccx: &CrateContext,
     }
 }
 
+/// With `-C fail-strategy=abort`, crates built with the unwind strategy, such
+/// as the shipped libcore, still fail by calling `rust_begin_unwind`, and
+/// their landing pads refer to `rust_eh_personality`. Unless some crate
+/// defines the corresponding lang items, the final crate defines these
+/// symbols in terms of the `abort` lang item. `rust_begin_unwind` passes its
+/// arguments on to it, as it has the same type. The personality routine is
+/// only called if something unwinds, which nothing does, so it aborts with an
+/// empty message. Returns the symbols defined.
+fn create_abort_shims(ccx: &CrateContext) -> Vec<String> {
+    if ccx.sess().opts.cg.fail_strategy != config::FailAbort ||
+       ccx.sess().crate_types.borrow().iter().all(|t| *t == config::CrateTypeRlib) {
+        return Vec::new()
+    }
+    let abort_def_id = match ccx.tcx.lang_items.abort() {
+        Some(def_id) => def_id,
+        None => return Vec::new(),
+    };
+
+    let abort_ty = ty::lookup_item_type(ccx.tcx(), abort_def_id).ty;
+    let abort_fn = if abort_def_id.krate == ast::LOCAL_CRATE {
+        get_item_val(ccx, abort_def_id.node)
+    } else {
+        trans_external_path(ccx, abort_def_id, abort_ty)
+    };
+
+    let mut shims = Vec::new();
+    if ccx.tcx.lang_items.begin_unwind().is_none() {
+        let llfty = type_of_fn_from_ty(ccx, abort_ty);
+        let llfn = decl_cdecl_fn(ccx, "rust_begin_unwind", llfty, ty::mk_bot());
+        let args = unsafe {
+            range(0, llvm::LLVMCountParams(llfn)).map(|i| {
+                llvm::LLVMGetParam(llfn, i)
+            }).collect()
+        };
+        build_abort_call(ccx, llfn, abort_fn, args);
+        shims.push("rust_begin_unwind".to_string());
+    }
+    if ccx.tcx.lang_items.eh_personality().is_none() {
+        // `abort` has the type `fn(&fmt::Arguments, &'static str, uint) -> !`,
+        // see `typeck::check_abort_fn_ty`. A zeroed `fmt::Arguments` is one
+        // without any pieces.
+        let params = val_ty(abort_fn).element_type().func_params();
+        let fmt = consts::const_addr_of(ccx, C_null(params.get(0).element_type()));
+        let file = C_str_slice(ccx, token::intern_and_get_ident("<unknown>"));
+        let file = if params.get(1).kind() == llvm::Pointer {
+            consts::const_addr_of(ccx, file)
+        } else {
+            file
+        };
+        let llfty = Type::variadic_func(&[], &Type::i32(ccx));
+        let llfn = decl_cdecl_fn(ccx, "rust_eh_personality", llfty, ty::mk_i32());
+        build_abort_call(ccx, llfn, abort_fn, vec!(fmt, file, C_uint(ccx, 0)));
+        shims.push("rust_eh_personality".to_string());
+    }
+    shims
+}
+
+/// Define `llfn` as a call to `abort_fn` with `args`.
+fn build_abort_call(ccx: &CrateContext, llfn: ValueRef, abort_fn: ValueRef,
+                    args: Vec<ValueRef>) {
+    let llbb = "top".with_c_str(|buf| {
+        unsafe {
+            llvm::LLVMAppendBasicBlockInContext(ccx.llcx, llfn, buf)
+        }
+    });
+    let bld = ccx.builder.b;
+    unsafe {
+        llvm::LLVMPositionBuilderAtEnd(bld, llbb);
+        llvm::LLVMBuildCall(bld, abort_fn, args.as_ptr(), args.len() as c_uint, noname());
+        llvm::LLVMBuildUnreachable(bld);
+    }
+}
+
 fn exported_name(ccx: &CrateContext, id: ast::NodeId,
                  ty: ty::t, attrs: &[ast::Attribute]) -> String {
     match attr::first_attr_value_str_by_name(attrs, "export_name") {
@@ -2178,7 +2332,7 @@ pub fn get_item_val(ccx: &CrateContext, id: ast::NodeId) -> ValueRef {
                 ast::ForeignItemFn(..) => {
                     let abi = ccx.tcx.map.get_foreign_abi(id);
                     let ty = ty::node_id_to_type(ccx.tcx(), ni.id);
-                    let name = foreign::link_name(&*ni);
+                    let name = foreign::link_name(ccx, &*ni);
                     foreign::register_foreign_item_fn(ccx, abi, ty,
                                                       name.get().as_slice(),
                                                       Some(ni.span))
@@ -2273,7 +2427,8 @@ pub fn p2i(ccx: &CrateContext, v: ValueRef) -> ValueRef {
     }
 }
 
-pub fn crate_ctxt_to_encode_parms<'r>(cx: &'r CrateContext, ie: encoder::EncodeInlinedItem<'r>)
+pub fn crate_ctxt_to_encode_parms<'r>(cx: &'r CrateContext, ie: encoder::EncodeInlinedItem<'r>,
+                                      export_list: Option<&'r NodeSet>)
     -> encoder::EncodeParams<'r> {
         encoder::EncodeParams {
             diag: cx.sess().diagnostic(),
@@ -2285,14 +2440,18 @@ pub fn crate_ctxt_to_encode_parms<'r>(cx: &'r CrateContext, ie: encoder::EncodeI
             cstore: &cx.sess().cstore,
             encode_inlined_item: ie,
             reachable: &cx.reachable,
+            export_list: export_list,
         }
 }
 
-pub fn write_metadata(cx: &CrateContext, krate: &ast::Crate) -> Vec<u8> {
+pub fn write_metadata(cx: &CrateContext, krate: &ast::Crate,
+                      export_list: Option<&NodeSet>) -> Vec<u8> {
     use flate;
 
+    // Only the crate types which can be linked against by other Rust crates
+    // need metadata.
     let any_library = cx.sess().crate_types.borrow().iter().any(|ty| {
-        *ty != config::CrateTypeExecutable
+        *ty != config::CrateTypeExecutable && *ty != config::CrateTypeCdylib
     });
     if !any_library {
         return Vec::new()
@@ -2301,7 +2460,7 @@ pub fn write_metadata(cx: &CrateContext, krate: &ast::Crate) -> Vec<u8> {
     let encode_inlined_item: encoder::EncodeInlinedItem =
         |ecx, ebml_w, ii| astencode::encode_inlined_item(ecx, ebml_w, ii);
 
-    let encode_parms = crate_ctxt_to_encode_parms(cx, encode_inlined_item);
+    let encode_parms = crate_ctxt_to_encode_parms(cx, encode_inlined_item, export_list);
     let metadata = encoder::encode_metadata(encode_parms, krate);
     let compressed = Vec::from_slice(encoder::metadata_encoding_version)
                      .append(match flate::deflate_bytes(metadata.as_slice()) {
@@ -2322,17 +2481,194 @@ pub fn write_metadata(cx: &CrateContext, krate: &ast::Crate) -> Vec<u8> {
     });
     unsafe {
         llvm::LLVMSetInitializer(llglobal, llconst);
-        let name = loader::meta_section_name(cx.sess().targ_cfg.os);
-        name.unwrap_or("rust_metadata").with_c_str(|buf| {
+        let name = loader::meta_section_name(cx.sess().targ_cfg.target.options.is_like_osx);
+        name.with_c_str(|buf| {
             llvm::LLVMSetSection(llglobal, buf)
         });
     }
     return metadata;
 }
 
+/// The items a Rust dynamic library exports when it has `#![export = "path"]`
+/// attributes: those reachable from the items they name. `None` if it has no
+/// such attributes, or isn't a dynamic library.
+fn export_list(ccx: &CrateContext, krate: &ast::Crate,
+               exported_items: &privacy::ExportedItems) -> Option<NodeSet> {
+    if !ccx.sess().crate_types.borrow().contains(&config::CrateTypeDylib) {
+        return None
+    }
+    let list = listed_exports(ccx, krate, exported_items).map(|seeds| {
+        reachable::find_reachable(ccx.tcx(), &seeds)
+    });
+    ccx.sess().abort_if_errors();
+    list
+}
+
+/// The symbols exported by each dynamic library output.
+fn exported_symbols(ccx: &CrateContext, export_list: Option<&NodeSet>)
+                    -> HashMap<config::CrateType, Vec<String>> {
+    let mut exported = HashMap::new();
+    for &crate_type in ccx.sess().crate_types.borrow().iter() {
+        let symbols = match crate_type {
+            config::CrateTypeDylib => {
+                dylib_exported_symbols(ccx, export_list)
+            }
+            config::CrateTypeCdylib => cdylib_exported_symbols(ccx),
+            _ => continue
+        };
+        exported.insert(crate_type, symbols);
+    }
+    exported
+}
+
+/// The symbols a Rust dynamic library exports: those of the items reachable
+/// from its public interface, or only from the items named by its
+/// `#![export = "path"]` attributes, along with the symbols of the upstream
+/// crates linked statically into it.
+fn dylib_exported_symbols(ccx: &CrateContext, export_list: Option<&NodeSet>)
+                          -> Vec<String> {
+    let mut exported: Vec<String> = ccx.reachable.iter().filter(|&id| {
+        export_list.map_or(true, |listed| listed.contains(id))
+    }).filter_map(|id| {
+        ccx.item_symbols.borrow().find(id).map(|s| s.to_string())
+    }).collect();
+
+    // Downstream crates may link to the crates included in this one through
+    // it, so they keep their exports.
+    let formats = ccx.tcx.dependency_formats.borrow();
+    match formats.find(&config::CrateTypeDylib) {
+        Some(formats) => {
+            for (i, kind) in formats.iter().enumerate() {
+                match *kind {
+                    Some(cstore::RequireStatic) => {
+                        let cnum = i as ast::CrateNum + 1;
+                        exported.push_all_move(
+                            csearch::get_exported_symbols(&ccx.sess().cstore, cnum));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        None => {}
+    }
+
+    exported
+}
+
+/// The public items named by the crate's `#![export = "path"]` attributes,
+/// or `None` if it has none. A path names an item along with everything
+/// nested inside it, and the methods of the impls of the types it names.
+fn listed_exports(ccx: &CrateContext, krate: &ast::Crate,
+                  exported_items: &privacy::ExportedItems) -> Option<NodeSet> {
+    let attrs: Vec<&ast::Attribute> = krate.attrs.iter().filter(|a| {
+        a.check_name("export")
+    }).collect();
+    if attrs.is_empty() {
+        return None
+    }
+
+    let mut seeds = NodeSet::new();
+    for attr in attrs.iter() {
+        let path = match attr.value_str() {
+            Some(path) => path,
+            None => {
+                ccx.sess().span_err(attr.span,
+                                    "malformed `export` attribute, expected \
+                                     `#![export = \"path\"]`");
+                continue
+            }
+        };
+        let path = path.get();
+        let nested = format!("{}::", path);
+        let mut found = false;
+        for &id in exported_items.iter() {
+            if id == ast::CRATE_NODE_ID {
+                continue
+            }
+            let item_path = ccx.tcx.map.path_to_string(id);
+            if item_path.as_slice() == path ||
+               item_path.as_slice().starts_with(nested.as_slice()) {
+                seeds.insert(id);
+                found = true;
+            }
+        }
+        if !found {
+            ccx.sess().span_err(attr.span,
+                                format!("`{}` does not name a public item of \
+                                         this crate", path).as_slice());
+        }
+    }
+
+    // Impls can be declared anywhere in the crate, so the methods of those of
+    // the listed types are found by their self type rather than their path,
+    // such as those of `impl Clone for api::S` at the crate root.
+    let mut impl_methods = Vec::new();
+    for &id in exported_items.iter() {
+        match ccx.tcx.map.find(id) {
+            Some(ast_map::NodeMethod(..)) => {}
+            _ => continue
+        }
+        let impl_did = local_def(ccx.tcx.map.get_parent(id));
+        let self_ty = ty::lookup_item_type(ccx.tcx(), impl_did).ty;
+        match ty::ty_to_def_id(self_ty) {
+            Some(did) if is_local(did) && seeds.contains(&did.node) => {
+                impl_methods.push(id);
+            }
+            _ => {}
+        }
+    }
+    seeds.extend(impl_methods.move_iter());
+
+    // The compiler looks up the plugin registrar of a dynamic library when
+    // loading it, so it is always exported.
+    match ccx.sess().plugin_registrar_fn.get() {
+        Some(id) => { seeds.insert(id); }
+        None => {}
+    }
+
+    Some(seeds)
+}
+
+/// The symbols a C dynamic library exports: the reachable `#[no_mangle]`
+/// extern fns of this crate and of the upstream crates linked into it.
+fn cdylib_exported_symbols(ccx: &CrateContext) -> Vec<String> {
+    let mut exported: Vec<String> = ccx.reachable.iter().filter(|&id| {
+        match ccx.tcx.map.find(*id) {
+            Some(ast_map::NodeItem(i)) => {
+                match i.node {
+                    ast::ItemFn(_, _, abi, _, _) if abi != Rust => {
+                        attr::contains_name(i.attrs.as_slice(), "no_mangle")
+                    }
+                    _ => false
+                }
+            }
+            _ => false
+        }
+    }).filter_map(|id| {
+        ccx.item_symbols.borrow().find(id).map(|s| s.to_string())
+    }).collect();
+
+    ccx.sess().cstore.iter_crate_data(|cnum, _| {
+        let fns = csearch::get_reachable_extern_fns(&ccx.sess().cstore, cnum);
+        for did in fns.move_iter() {
+            let mut no_mangle = false;
+            csearch::get_item_attrs(&ccx.sess().cstore, did, |attrs| {
+                no_mangle = attr::contains_name(attrs.as_slice(), "no_mangle");
+            });
+            if no_mangle {
+                exported.push(csearch::get_symbol(&ccx.sess().cstore, did));
+            }
+        }
+    });
+
+    exported
+}
+
 pub fn trans_crate(krate: ast::Crate,
                    analysis: CrateAnalysis) -> (ty::ctxt, CrateTranslation) {
-    let CrateAnalysis { ty_cx: tcx, exp_map2, reachable, name, .. } = analysis;
+    let CrateAnalysis {
+        ty_cx: tcx, exp_map2, exported_items, reachable, name, ..
+    } = analysis;
 
     // Before we touch LLVM, make sure that multithreading is enabled.
     unsafe {
@@ -2376,6 +2712,7 @@ pub fn trans_crate(krate: ast::Crate,
         let _icx = push_ctxt("text");
         trans_mod(&ccx, &krate.module);
     }
+    let abort_shims = create_abort_shims(&ccx);
 
     glue::emit_tydescs(&ccx);
     if ccx.sess().opts.debuginfo != NoDebugInfo {
@@ -2383,7 +2720,8 @@ pub fn trans_crate(krate: ast::Crate,
     }
 
     // Translate the metadata.
-    let metadata = write_metadata(&ccx, &krate);
+    let export_list = export_list(&ccx, &krate, &exported_items);
+    let metadata = write_metadata(&ccx, &krate, export_list.as_ref());
     if ccx.sess().trans_stats() {
         println!("--- trans stats ---");
         println!("n_static_tydescs: {}", ccx.stats.n_static_tydescs.get());
@@ -2445,9 +2783,15 @@ pub fn trans_crate(krate: ast::Crate,
     // referenced from rt/rust_try.ll
     reachable.push("rust_eh_personality_catch".to_string());
 
+    // referenced by the upstream crates built with the unwind fail strategy
+    reachable.push_all_move(abort_shims);
+
+    let exported_symbols = exported_symbols(&ccx, export_list.as_ref());
+
     let metadata_module = ccx.metadata_llmod;
     let formats = ccx.tcx.dependency_formats.borrow().clone();
     let no_builtins = attr::contains_name(krate.attrs.as_slice(), "no_builtins");
+    let fn_items = ccx.stats.fn_items.borrow().clone();
 
     (ccx.tcx, CrateTranslation {
         context: llcx,
@@ -2456,7 +2800,9 @@ pub fn trans_crate(krate: ast::Crate,
         metadata_module: metadata_module,
         metadata: metadata,
         reachable: reachable,
+        exported_symbols: exported_symbols,
         crate_formats: formats,
         no_builtins: no_builtins,
+        fn_items: fn_items,
     })
 }
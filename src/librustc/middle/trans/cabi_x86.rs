@@ -8,8 +8,6 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-
-use syntax::abi::{OsWin32, OsMacos, OsiOS};
 use llvm::*;
 use super::cabi::*;
 use super::common::*;
@@ -35,19 +33,17 @@ pub fn compute_abi_info(ccx: &CrateContext,
         // Clang's ABI handling is in lib/CodeGen/TargetInfo.cpp
 
         enum Strategy { RetValue(Type), RetPointer }
-        let strategy = match ccx.sess().targ_cfg.os {
-            OsWin32 | OsMacos | OsiOS => {
-                match llsize_of_alloc(ccx, rty) {
-                    1 => RetValue(Type::i8(ccx)),
-                    2 => RetValue(Type::i16(ccx)),
-                    4 => RetValue(Type::i32(ccx)),
-                    8 => RetValue(Type::i64(ccx)),
-                    _ => RetPointer
-                }
-            }
-            _ => {
-                RetPointer
+        let options = &ccx.sess().targ_cfg.target.options;
+        let strategy = if options.is_like_windows || options.is_like_osx {
+            match llsize_of_alloc(ccx, rty) {
+                1 => RetValue(Type::i8(ccx)),
+                2 => RetValue(Type::i16(ccx)),
+                4 => RetValue(Type::i32(ccx)),
+                8 => RetValue(Type::i64(ccx)),
+                _ => RetPointer
             }
+        } else {
+            RetPointer
         };
 
         match strategy {
@@ -32,7 +32,6 @@ use std::c_str::ToCStr;
 use std::ptr;
 use std::rc::Rc;
 use std::collections::{HashMap, HashSet};
-use syntax::abi;
 use syntax::ast;
 use syntax::parse::token::InternedString;
 
@@ -49,6 +48,8 @@ pub struct Stats {
     pub llvm_insns: RefCell<HashMap<String, uint>>,
     // (ident, time-in-ms, llvm-instructions)
     pub fn_stats: RefCell<Vec<(String, uint, uint)> >,
+    // The item each function symbol was translated from, for -Z llvm-fn-stats
+    pub fn_items: RefCell<HashMap<String, String>>,
 }
 
 pub struct CrateContext {
@@ -146,7 +147,7 @@ impl CrateContext {
             });
             tcx.sess
                .targ_cfg
-               .target_strs
+               .target
                .data_layout
                .as_slice()
                .with_c_str(|buf| {
@@ -155,8 +156,8 @@ impl CrateContext {
             });
             tcx.sess
                .targ_cfg
-               .target_strs
-               .target_triple
+               .target
+               .llvm_target
                .as_slice()
                .with_c_str(|buf| {
                 llvm::LLVMRustSetNormalizedTarget(llmod, buf);
@@ -165,7 +166,7 @@ impl CrateContext {
 
             let td = mk_target_data(tcx.sess
                                        .targ_cfg
-                                       .target_strs
+                                       .target
                                        .data_layout
                                        .as_slice());
 
@@ -221,6 +222,7 @@ impl CrateContext {
                     n_llvm_insns: Cell::new(0u),
                     llvm_insns: RefCell::new(HashMap::new()),
                     fn_stats: RefCell::new(Vec::new()),
+                    fn_items: RefCell::new(HashMap::new()),
                 },
                 int_type: Type::from_ref(ptr::mut_null()),
                 opaque_vec_type: Type::from_ref(ptr::mut_null()),
@@ -275,16 +277,8 @@ impl CrateContext {
         }
     }
 
-    // Although there is an experimental implementation of LLVM which
-    // supports SS on armv7 it wasn't approved by Apple, see:
-    // http://lists.cs.uiuc.edu/pipermail/llvm-commits/Week-of-Mon-20140505/216350.html
-    // It looks like it might be never accepted to upstream LLVM.
-    //
-    // So far the decision was to disable them in default builds
-    // but it could be enabled (with patched LLVM)
     pub fn is_split_stack_supported(&self) -> bool {
-        let ref cfg = self.sess().targ_cfg;
-        cfg.os != abi::OsiOS || cfg.arch != abi::Arm
+        self.sess().targ_cfg.target.options.morestack
     }
 }
 
@@ -360,7 +360,8 @@ pub fn trans_fail<'a>(
 
     let v_str = str_slice_arg(bcx, fail_str);
     let loc = bcx.sess().codemap().lookup_char_pos(sp.lo);
-    let filename = token::intern_and_get_ident(loc.file.name.as_slice());
+    let filename = bcx.sess().remap_path(loc.file.name.as_slice());
+    let filename = token::intern_and_get_ident(filename.as_slice());
     let v_filename = str_slice_arg(bcx, filename);
     let v_line = loc.line as int;
     let args = vec!(v_str, v_filename, C_int(ccx, v_line));
@@ -383,7 +384,8 @@ pub fn trans_fail_bounds_check<'a>(
 
     // Extract the file/line from the span
     let loc = bcx.sess().codemap().lookup_char_pos(sp.lo);
-    let filename = token::intern_and_get_ident(loc.file.name.as_slice());
+    let filename = bcx.sess().remap_path(loc.file.name.as_slice());
+    let filename = token::intern_and_get_ident(filename.as_slice());
 
     // Invoke the lang item
     let filename = str_slice_arg(bcx, filename);
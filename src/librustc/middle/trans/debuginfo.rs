@@ -206,7 +206,7 @@ use std::ptr;
 use std::rc::{Rc, Weak};
 use syntax::util::interner::Interner;
 use syntax::codemap::{Span, Pos};
-use syntax::{abi, ast, codemap, ast_util, ast_map};
+use syntax::{ast, codemap, ast_util, ast_map};
 use syntax::ast_util::PostExpansionMethod;
 use syntax::owned_slice::OwnedSlice;
 use syntax::parse::token;
@@ -739,8 +739,7 @@ pub fn finalize(cx: &CrateContext) {
         // instruct LLVM to emit an older version of dwarf, however,
         // for OS X to understand. For more info see #11352
         // This can be overridden using --llvm-opts -dwarf-version,N.
-        if cx.sess().targ_cfg.os == abi::OsMacos ||
-            cx.sess().targ_cfg.os == abi::OsiOS {
+        if cx.sess().targ_cfg.target.options.is_like_osx {
             "Dwarf Version".with_c_str(
                 |s| llvm::LLVMRustAddModuleFlag(cx.llmod, s, 2));
         } else {
@@ -1442,7 +1441,10 @@ fn create_DIArray(builder: DIBuilderRef, arr: &[DIDescriptor]) -> DIArray {
 }
 
 fn compile_unit_metadata(cx: &CrateContext) {
-    let work_dir = &cx.sess().working_dir;
+    // FIXME (#9639): This needs to handle non-utf8 paths
+    // Both paths are remapped first, so that the name doesn't depend on where
+    // the source was when a prefix of it is remapped.
+    let work_dir = cx.sess().remap_path(cx.sess().working_dir.as_str().unwrap());
     let compile_unit_name = match cx.sess().local_crate_source_file {
         None => fallback_path(cx),
         Some(ref abs_path) => {
@@ -1450,7 +1452,10 @@ fn compile_unit_metadata(cx: &CrateContext) {
                 cx.sess().warn("debuginfo: Invalid path to crate's local root source file!");
                 fallback_path(cx)
             } else {
-                match abs_path.path_relative_from(work_dir) {
+                let path = Path::new(cx.sess().remap_path(abs_path.as_str().unwrap()));
+                match path.path_relative_from(&Path::new(work_dir.as_slice())) {
+                    // remapped to a relative path, which is used as is
+                    _ if path.is_relative() => path.as_vec().to_c_str(),
                     Some(ref p) if p.is_relative() => {
                             // prepend "./" if necessary
                             let dotdot = b"..";
@@ -1476,7 +1481,7 @@ fn compile_unit_metadata(cx: &CrateContext) {
                            (option_env!("CFG_VERSION")).expect("CFG_VERSION"));
 
     let compile_unit_name = compile_unit_name.as_ptr();
-    work_dir.as_vec().with_c_str(|work_dir| {
+    work_dir.with_c_str(|work_dir| {
         producer.with_c_str(|producer| {
             "".with_c_str(|flags| {
                 "".with_c_str(|split_name| {
@@ -1599,10 +1604,11 @@ fn file_metadata(cx: &CrateContext, full_path: &str) -> DIFile {
     let work_dir = cx.sess().working_dir.as_str().unwrap();
     let file_name =
         if full_path.starts_with(work_dir) {
-            full_path.slice(work_dir.len() + 1u, full_path.len())
+            full_path.slice(work_dir.len() + 1u, full_path.len()).to_string()
         } else {
-            full_path
+            cx.sess().remap_path(full_path)
         };
+    let work_dir = cx.sess().remap_path(work_dir);
 
     let file_metadata =
         file_name.with_c_str(|file_name| {
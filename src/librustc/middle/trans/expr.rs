@@ -1316,11 +1316,17 @@ fn trans_eager_binop<'a>(
     };
     let is_float = ty::type_is_fp(intype);
     let is_signed = ty::type_is_signed(intype);
+    let check_overflow = bcx.sess().opts.cg.overflow_checks &&
+                         !is_simd && ty::type_is_integral(intype);
 
     let rhs = base::cast_shift_expr_rhs(bcx, op, lhs, rhs);
 
     let mut bcx = bcx;
     let val = match op {
+      ast::BiAdd | ast::BiSub | ast::BiMul if check_overflow => {
+        unpack_result!(bcx, base::trans_checked_int_binop(bcx, binop_expr.span, op,
+                                                          intype, lhs, rhs))
+      }
       ast::BiAdd => {
         if is_float { FAdd(bcx, lhs, rhs) }
         else { Add(bcx, lhs, rhs) }
@@ -70,9 +70,9 @@ struct LlvmSignature {
 
 pub fn llvm_calling_convention(ccx: &CrateContext,
                                abi: Abi) -> Option<CallConv> {
-    let os = ccx.sess().targ_cfg.os;
+    let is_like_windows = ccx.sess().targ_cfg.target.options.is_like_windows;
     let arch = ccx.sess().targ_cfg.arch;
-    abi.for_target(os, arch).map(|abi| {
+    abi.for_target(is_like_windows, arch).map(|abi| {
         match abi {
             RustIntrinsic => {
                 // Intrinsics are emitted at the call site
@@ -129,7 +129,7 @@ pub fn register_static(ccx: &CrateContext,
     let ty = ty::node_id_to_type(ccx.tcx(), foreign_item.id);
     let llty = type_of::type_of(ccx, ty);
 
-    let ident = link_name(foreign_item);
+    let ident = link_name(ccx, foreign_item);
     match attr::first_attr_value_str_by_name(foreign_item.attrs.as_slice(),
                                              "linkage") {
         // If this is a static with a linkage specified, then we need to handle
@@ -470,7 +470,7 @@ pub fn trans_native_call<'a>(
 pub fn trans_foreign_mod(ccx: &CrateContext, foreign_mod: &ast::ForeignMod) {
     let _icx = push_ctxt("foreign::trans_foreign_mod");
     for foreign_item in foreign_mod.items.iter() {
-        let lname = link_name(&**foreign_item);
+        let lname = link_name(ccx, &**foreign_item);
 
         match foreign_item.node {
             ast::ForeignItemFn(..) => {
@@ -823,10 +823,10 @@ pub fn trans_rust_fn_with_foreign_abi(ccx: &CrateContext,
 // This code is kind of a confused mess and needs to be reworked given
 // the massive simplifications that have occurred.
 
-pub fn link_name(i: &ast::ForeignItem) -> InternedString {
+pub fn link_name(ccx: &CrateContext, i: &ast::ForeignItem) -> InternedString {
     match attr::first_attr_value_str_by_name(i.attrs.as_slice(), "link_name") {
         Some(ln) => ln.clone(),
-        None => match weak_lang_items::link_name(i.attrs.as_slice()) {
+        None => match weak_lang_items::declared_link_name(ccx.sess(), i.attrs.as_slice()) {
             Some(name) => name,
             None => token::get_ident(i.ident),
         }
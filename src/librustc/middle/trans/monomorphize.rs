@@ -20,6 +20,7 @@ use middle::trans::base;
 use middle::trans::common::*;
 use middle::ty;
 use middle::typeck;
+use util::ppaux;
 use util::ppaux::Repr;
 
 use syntax::abi;
@@ -132,6 +133,15 @@ pub fn monomorphic_fn(ccx: &CrateContext,
     });
     debug!("monomorphize_fn mangled to {}", s);
 
+    if ccx.sess().llvm_fn_stats() {
+        let args: Vec<String> = real_substs.types.iter().map(|t| {
+            ppaux::ty_to_string(ccx.tcx(), *t)
+        }).collect();
+        let item = format!("{}<{}>", ty::item_path_str(ccx.tcx(), fn_id),
+                           args.connect(", "));
+        ccx.stats.fn_items.borrow_mut().insert(s.clone(), item);
+    }
+
     // This shouldn't need to option dance.
     let mut hash_id = Some(hash_id);
     let mk_lldecl = || {
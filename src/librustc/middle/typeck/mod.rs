@@ -450,6 +450,52 @@ fn check_start_fn_ty(ccx: &CrateCtxt,
     }
 }
 
+/// The `abort` lang item stands in for `begin_unwind`, so it must have the
+/// type `fn(&fmt::Arguments, &'static str, uint) -> !`.
+fn check_abort_fn_ty(ccx: &CrateCtxt) {
+    let tcx = ccx.tcx;
+    let abort_id = match tcx.lang_items.abort() {
+        Some(def_id) if def_id.krate == ast::LOCAL_CRATE => def_id.node,
+        _ => return,
+    };
+    let is_fmt_arguments_ref = |t: ty::t| match ty::get(t).sty {
+        ty::ty_rptr(_, ty::mt { ty: inner, mutbl: ast::MutImmutable }) => {
+            match ty::get(inner).sty {
+                ty::ty_struct(did, _) => {
+                    ty::item_path_str(tcx, did).as_slice().ends_with("fmt::Arguments")
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    };
+    let is_static_str = |t: ty::t| match ty::get(t).sty {
+        ty::ty_rptr(ty::ReStatic, ty::mt { ty: inner, mutbl: ast::MutImmutable }) => {
+            ty::get(inner).sty == ty::ty_str
+        }
+        _ => false,
+    };
+
+    let abort_t = ty::node_id_to_type(tcx, abort_id);
+    let ok = match ty::get(abort_t).sty {
+        ty::ty_bare_fn(ref f) => {
+            f.abi == abi::Rust && !f.sig.variadic && f.sig.inputs.len() == 3 &&
+                is_fmt_arguments_ref(*f.sig.inputs.get(0)) &&
+                is_static_str(*f.sig.inputs.get(1)) &&
+                ty::get(*f.sig.inputs.get(2)).sty == ty::ty_uint(ast::TyU) &&
+                ty::type_is_bot(f.sig.output)
+        }
+        _ => false,
+    };
+    if !ok {
+        tcx.sess.span_err(tcx.map.span(abort_id),
+                          format!("the `abort` lang item must have the type \
+                                   `fn(&fmt::Arguments, &'static str, uint) -> !`, \
+                                   found `{}`",
+                                  ppaux::ty_to_string(tcx, abort_t)).as_slice());
+    }
+}
+
 fn check_for_entry_fn(ccx: &CrateCtxt) {
     let tcx = ccx.tcx;
     match *tcx.sess.entry_fn.borrow() {
@@ -489,5 +535,6 @@ pub fn check_crate(tcx: &ty::ctxt,
         check::check_item_types(&ccx, krate));
 
     check_for_entry_fn(&ccx);
+    check_abort_fn_ty(&ccx);
     tcx.sess.abort_if_errors();
 }
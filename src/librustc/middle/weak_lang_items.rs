@@ -41,7 +41,8 @@ pub fn check_crate(krate: &ast::Crate,
     if items.stack_exhausted().is_none() {
         items.missing.push(lang_items::StackExhaustedLangItem);
     }
-    if items.eh_personality().is_none() {
+    // Without landing pads nothing needs a personality routine.
+    if items.eh_personality().is_none() && !sess.no_landing_pads() {
         items.missing.push(lang_items::EhPersonalityLangItem);
     }
 
@@ -52,6 +53,21 @@ pub fn check_crate(krate: &ast::Crate,
     verify(sess, items);
 }
 
+/// The symbol a declaration of a weak lang item links to. With the abort
+/// fail strategy, declarations of `begin_unwind` link to the `abort` lang
+/// item instead, so that all failure ends up there.
+pub fn declared_link_name(sess: &Session,
+                          attrs: &[ast::Attribute]) -> Option<InternedString> {
+    let is_begin_unwind = lang_items::extract(attrs).map_or(false, |name| {
+        name.get() == "begin_unwind"
+    });
+    if is_begin_unwind && sess.opts.cg.fail_strategy == config::FailAbort {
+        Some(InternedString::new("rust_abort"))
+    } else {
+        link_name(attrs)
+    }
+}
+
 pub fn link_name(attrs: &[ast::Attribute]) -> Option<InternedString> {
     lang_items::extract(attrs).and_then(|name| {
         $(if name.get() == stringify!($name) {
@@ -68,6 +84,7 @@ fn verify(sess: &Session, items: &lang_items::LanguageItems) {
     let needs_check = sess.crate_types.borrow().iter().any(|kind| {
         match *kind {
             config::CrateTypeDylib |
+            config::CrateTypeCdylib |
             config::CrateTypeExecutable |
             config::CrateTypeStaticlib => true,
             config::CrateTypeRlib => false,
@@ -81,6 +98,17 @@ fn verify(sess: &Session, items: &lang_items::LanguageItems) {
             missing.insert(*item);
         }
     });
+    if sess.opts.cg.fail_strategy == config::FailAbort {
+        // See `declared_link_name`. Crates built with the unwind strategy
+        // call `rust_begin_unwind` and their landing pads refer to
+        // `rust_eh_personality`, which `trans` then defines in terms of
+        // `abort`.
+        let begin_unwind = missing.remove(&lang_items::BeginUnwindLangItem);
+        let personality = missing.remove(&lang_items::EhPersonalityLangItem);
+        if begin_unwind || personality {
+            missing.insert(lang_items::AbortLangItem);
+        }
+    }
 
     $(
         if missing.contains(&lang_items::$item) && items.$name().is_none() {
@@ -119,6 +147,7 @@ impl<'a> Visitor<()> for Context<'a> {
 
 weak_lang_items!(
     begin_unwind,       BeginUnwindLangItem,        rust_begin_unwind;
+    abort,              AbortLangItem,              rust_abort;
     stack_exhausted,    StackExhaustedLangItem,     rust_stack_exhausted;
     eh_personality,     EhPersonalityLangItem,      rust_eh_personality;
 )
@@ -10,7 +10,7 @@
 
 //! Used by plugin crates to tell `rustc` about the plugins they provide.
 
-use lint::LintPassObject;
+use lint::{LintPassObject, LintId, Lint};
 
 use syntax::ext::base::{SyntaxExtension, NamedSyntaxExtension, NormalTT};
 use syntax::ext::base::{IdentTT, LetSyntaxTT, ItemDecorator, ItemModifier, BasicMacroExpander};
@@ -36,6 +36,9 @@ pub struct Registry {
 
     #[doc(hidden)]
     pub lint_passes: Vec<LintPassObject>,
+
+    #[doc(hidden)]
+    pub lint_groups: Vec<(&'static str, Vec<LintId>)>,
 }
 
 impl Registry {
@@ -45,6 +48,7 @@ impl Registry {
             krate_span: krate.span,
             syntax_exts: vec!(),
             lint_passes: vec!(),
+            lint_groups: vec!(),
         }
     }
 
@@ -80,4 +84,10 @@ impl Registry {
     pub fn register_lint_pass(&mut self, lint_pass: LintPassObject) {
         self.lint_passes.push(lint_pass);
     }
+
+    /// Register a lint group, which sets the level of all the lints in `to`
+    /// when `name` is used in place of a lint name.
+    pub fn register_lint_group(&mut self, name: &'static str, to: Vec<&'static Lint>) {
+        self.lint_groups.push((name, to.move_iter().map(|lint| LintId::of(lint)).collect()));
+    }
 }
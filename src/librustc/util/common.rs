@@ -15,24 +15,38 @@ use syntax::visit;
 use syntax::visit::Visitor;
 
 use time;
+use util::time_report;
+
+local_data_key!(depth: uint)
 
 pub fn time<T, U>(do_it: bool, what: &str, u: U, f: |U| -> T) -> T {
-    local_data_key!(depth: uint);
-    if !do_it { return f(u); }
+    let report = time_report::is_enabled();
+    if !do_it && !report { return f(u); }
 
-    let old = depth.get().map(|d| *d).unwrap_or(0);
+    let old = time_depth();
     depth.replace(Some(old + 1));
 
+    let allocations = if report { time_report::allocations_before() } else { None };
     let start = time::precise_time_s();
     let rv = f(u);
     let end = time::precise_time_s();
 
-    println!("{}time: {:3.3f} s\t{}", "  ".repeat(old), end - start, what);
+    if do_it {
+        println!("{}time: {:3.3f} s\t{}", "  ".repeat(old), end - start, what);
+    }
+    if report {
+        time_report::record(what, old, end - start, allocations);
+    }
     depth.replace(Some(old));
 
     rv
 }
 
+/// The number of enclosing `time` calls.
+pub fn time_depth() -> uint {
+    depth.get().map(|d| *d).unwrap_or(0)
+}
+
 pub fn indent<R>(op: || -> R) -> R {
     // Use in conjunction with the log post-processor like `src/etc/indenter`
     // to make debug output more readable.
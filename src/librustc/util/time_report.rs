@@ -0,0 +1,239 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Structured reports of the time and memory used by each compiler pass,
+//! written with `-C time-report=FILE`.
+//!
+//! While a report is active every pass run through `util::common::time` is
+//! recorded, whether or not `-Z time-passes` is given, with:
+//!
+//! * `wall_time`: the wall clock time of the pass in seconds,
+//! * `peak_rss`: the peak resident set size of the process in bytes when the
+//!   pass finished, where the platform exposes it,
+//! * `allocations`: the number of heap allocations made during the pass,
+//!   where the allocator keeps count.
+//!
+//! Passes are listed in the order they finish, so nested passes come before
+//! the pass containing them; `depth` gives the nesting level. LLVM's own
+//! passes are included with an `llvm: ` prefix. LLVM only reports their
+//! times, so their memory fields are always empty.
+//!
+//! The report is JSON if FILE ends in `.json`:
+//!
+//! ```ignore
+//! {"version":1,
+//!  "crates":[{"name":"foo","passes":[{"name":"parsing","depth":0,
+//!             "wall_time":0.01,"peak_rss":10485760,"allocations":1234},...]}]}
+//! ```
+//!
+//! and CSV otherwise, with the columns `crate,pass,depth,wall_time,peak_rss,
+//! allocations`.
+//!
+//! With `-C time-report-append`, a crate's report is appended to FILE, so
+//! that it collects the reports of every crate in a build. The file is only
+//! ever appended to, with a single write per crate, so crates compiled in
+//! parallel can share it. In this mode the CSV rows are written without a
+//! header, and a JSON report is written as one line per crate:
+//!
+//! ```ignore
+//! {"version":1,"name":"foo","passes":[...]}
+//! ```
+
+use std::cell::RefCell;
+use std::io;
+use std::io::{File, IoResult, MemWriter};
+use std::rt::heap;
+
+use serialize::json;
+
+pub static REPORT_VERSION: uint = 1;
+
+#[deriving(Clone, Encodable, Decodable)]
+pub struct PassTiming {
+    pub name: String,
+    pub depth: uint,
+    pub wall_time: f64,
+    pub peak_rss: Option<u64>,
+    pub allocations: Option<u64>,
+}
+
+#[deriving(Clone, Encodable, Decodable)]
+pub struct CrateTimings {
+    pub name: String,
+    pub passes: Vec<PassTiming>,
+}
+
+#[deriving(Encodable)]
+struct Report {
+    version: uint,
+    crates: Vec<CrateTimings>,
+}
+
+/// A line of a JSON report written with `-C time-report-append`.
+#[deriving(Encodable)]
+struct AppendedReport {
+    version: uint,
+    name: String,
+    passes: Vec<PassTiming>,
+}
+
+local_data_key!(active_report: RefCell<CrateTimings>)
+
+/// Start recording the passes run by this task.
+pub fn start() {
+    active_report.replace(Some(RefCell::new(CrateTimings {
+        name: String::new(),
+        passes: Vec::new(),
+    })));
+}
+
+pub fn is_enabled() -> bool {
+    active_report.get().is_some()
+}
+
+/// Name the crate the active report is for, if there is one.
+pub fn set_crate_name(name: &str) {
+    match active_report.get() {
+        Some(report) => report.borrow_mut().name = name.to_string(),
+        None => {}
+    }
+}
+
+/// The allocation count to later pass to `record`.
+pub fn allocations_before() -> Option<u64> {
+    heap::allocation_count()
+}
+
+/// Record a pass which has just finished in the active report.
+pub fn record(name: &str, depth: uint, wall_time: f64, allocations_before: Option<u64>) {
+    let allocations = match (allocations_before, heap::allocation_count()) {
+        (Some(before), Some(after)) if after >= before => Some(after - before),
+        _ => None,
+    };
+    push(PassTiming {
+        name: name.to_string(),
+        depth: depth,
+        wall_time: wall_time,
+        peak_rss: peak_rss(),
+        allocations: allocations,
+    });
+}
+
+/// Record the pass timings printed by LLVM for `-time-passes`, as passes
+/// nested at `depth`.
+pub fn record_llvm_passes(timings: &str, depth: uint) {
+    // Each pass is a line of columns like `0.0123 ( 45.6%)`, ending with the
+    // wall time column, followed by the pass name.
+    for line in timings.lines() {
+        let close = match line.rfind(')') {
+            Some(i) => i,
+            None => continue,
+        };
+        let name = line.slice_from(close + 1).trim();
+        let columns = line.slice_to(close);
+        if name.is_empty() || name == "Total" || !columns.ends_with("%") {
+            continue
+        }
+        let wall_time = columns.rfind('(').and_then(|open| {
+            columns.slice_to(open).words().last().and_then(|w| from_str::<f64>(w))
+        });
+        match wall_time {
+            Some(wall_time) => push(PassTiming {
+                name: format!("llvm: {}", name),
+                depth: depth,
+                wall_time: wall_time,
+                peak_rss: None,
+                allocations: None,
+            }),
+            None => {}
+        }
+    }
+}
+
+fn push(pass: PassTiming) {
+    match active_report.get() {
+        Some(report) => report.borrow_mut().passes.push(pass),
+        None => {}
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn peak_rss() -> Option<u64> {
+    let status = match File::open(&Path::new("/proc/self/status")).read_to_string() {
+        Ok(status) => status,
+        Err(..) => return None,
+    };
+    status.as_slice().lines().find(|line| line.starts_with("VmHWM:")).and_then(|line| {
+        line.slice_from("VmHWM:".len()).words().next()
+            .and_then(|kb| from_str::<u64>(kb))
+            .map(|kb| kb * 1024)
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn peak_rss() -> Option<u64> {
+    None
+}
+
+/// Stop recording and write the report to `path`. If `append` is set, the
+/// report is appended to `path` rather than replacing it.
+pub fn finish(path: &Path, append: bool) -> IoResult<()> {
+    let current = match active_report.replace(None) {
+        Some(report) => report.borrow().clone(),
+        None => return Ok(()),
+    };
+    let is_json = path.extension_str() == Some("json");
+
+    if !append {
+        let mut file = try!(File::create(path));
+        if is_json {
+            let report = Report {
+                version: REPORT_VERSION,
+                crates: vec!(current),
+            };
+            return file.write_str(json::encode(&report).as_slice());
+        }
+        try!(file.write_line("crate,pass,depth,wall_time,peak_rss,allocations"));
+        for pass in current.passes.iter() {
+            try!(write_csv_row(&mut file, current.name.as_slice(), pass));
+        }
+        return Ok(());
+    }
+
+    // Build the whole report first, so that it is appended in one write and
+    // can't be interleaved with the report of a crate compiled in parallel.
+    let mut out = MemWriter::new();
+    if is_json {
+        let report = AppendedReport {
+            version: REPORT_VERSION,
+            name: current.name.clone(),
+            passes: current.passes.clone(),
+        };
+        try!(out.write_line(json::encode(&report).as_slice()));
+    } else {
+        for pass in current.passes.iter() {
+            try!(write_csv_row(&mut out, current.name.as_slice(), pass));
+        }
+    }
+    let mut file = try!(File::open_mode(path, io::Append, io::Write));
+    file.write(out.get_ref())
+}
+
+fn write_csv_row(out: &mut Writer, krate: &str, pass: &PassTiming) -> IoResult<()> {
+    fn quote(s: &str) -> String {
+        format!("\"{}\"", s.replace("\"", "\"\""))
+    }
+    fn number(n: Option<u64>) -> String {
+        n.map(|n| n.to_string()).unwrap_or(String::new())
+    }
+    writeln!(out, "{},{},{},{:.6f},{},{}",
+             quote(krate), quote(pass.name.as_slice()), pass.depth,
+             pass.wall_time, number(pass.peak_rss), number(pass.allocations))
+}
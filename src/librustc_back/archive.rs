@@ -15,7 +15,6 @@ use std::io::{fs, TempDir};
 use std::io;
 use std::os;
 use std::str;
-use syntax::abi;
 use ErrorHandler = syntax::diagnostic::Handler;
 
 pub static METADATA_FILENAME: &'static str = "rust.metadata.bin";
@@ -24,7 +23,9 @@ pub struct ArchiveConfig<'a> {
     pub handler: &'a ErrorHandler,
     pub dst: Path,
     pub lib_search_paths: Vec<Path>,
-    pub os: abi::Os,
+    pub slib_prefix: String,
+    pub slib_suffix: String,
+    pub is_like_osx: bool,
     pub maybe_ar_prog: Option<String>
 }
 
@@ -32,11 +33,13 @@ pub struct Archive<'a> {
     handler: &'a ErrorHandler,
     dst: Path,
     lib_search_paths: Vec<Path>,
-    os: abi::Os,
+    slib_prefix: String,
+    slib_suffix: String,
+    is_like_osx: bool,
     maybe_ar_prog: Option<String>
 }
 
-fn run_ar(handler: &ErrorHandler, maybe_ar_prog: &Option<String>,
+fn run_ar(handler: &ErrorHandler, maybe_ar_prog: &Option<String>, is_like_osx: bool,
           args: &str, cwd: Option<&Path>,
           paths: &[&Path]) -> ProcessOutput {
     let ar = match *maybe_ar_prog {
@@ -45,7 +48,19 @@ fn run_ar(handler: &ErrorHandler, maybe_ar_prog: &Option<String>,
     };
     let mut cmd = Command::new(ar);
 
-    cmd.arg(args).args(paths);
+    // Archive members record their modification time, owner and mode, which
+    // would make two builds of the same crate differ. Apple's ar leaves them
+    // out when ZERO_AR_DATE is set, and GNU ar in its deterministic mode.
+    let modifies = args.chars().any(|c| "rdqs".contains_char(c));
+    if is_like_osx {
+        cmd.env("ZERO_AR_DATE", "1");
+        cmd.arg(args);
+    } else if modifies {
+        cmd.arg(format!("{}D", args));
+    } else {
+        cmd.arg(args);
+    }
+    cmd.args(paths);
     debug!("{}", cmd);
 
     match cwd {
@@ -87,26 +102,32 @@ fn run_ar(handler: &ErrorHandler, maybe_ar_prog: &Option<String>,
 impl<'a> Archive<'a> {
     /// Initializes a new static archive with the given object file
     pub fn create<'b>(config: ArchiveConfig<'a>, initial_object: &'b Path) -> Archive<'a> {
-        let ArchiveConfig { handler, dst, lib_search_paths, os, maybe_ar_prog } = config;
-        run_ar(handler, &maybe_ar_prog, "crus", None, [&dst, initial_object]);
+        let ArchiveConfig { handler, dst, lib_search_paths, slib_prefix, slib_suffix,
+                            is_like_osx, maybe_ar_prog } = config;
+        run_ar(handler, &maybe_ar_prog, is_like_osx, "crs", None, [&dst, initial_object]);
         Archive {
             handler: handler,
             dst: dst,
             lib_search_paths: lib_search_paths,
-            os: os,
+            slib_prefix: slib_prefix,
+            slib_suffix: slib_suffix,
+            is_like_osx: is_like_osx,
             maybe_ar_prog: maybe_ar_prog
         }
     }
 
     /// Opens an existing static archive
     pub fn open(config: ArchiveConfig<'a>) -> Archive<'a> {
-        let ArchiveConfig { handler, dst, lib_search_paths, os, maybe_ar_prog } = config;
+        let ArchiveConfig { handler, dst, lib_search_paths, slib_prefix, slib_suffix,
+                            is_like_osx, maybe_ar_prog } = config;
         assert!(dst.exists());
         Archive {
             handler: handler,
             dst: dst,
             lib_search_paths: lib_search_paths,
-            os: os,
+            slib_prefix: slib_prefix,
+            slib_suffix: slib_suffix,
+            is_like_osx: is_like_osx,
             maybe_ar_prog: maybe_ar_prog
         }
     }
@@ -137,22 +158,24 @@ impl<'a> Archive<'a> {
     /// Adds an arbitrary file to this archive
     pub fn add_file(&mut self, file: &Path, has_symbols: bool) {
         let cmd = if has_symbols {"r"} else {"rS"};
-        run_ar(self.handler, &self.maybe_ar_prog, cmd, None, [&self.dst, file]);
+        run_ar(self.handler, &self.maybe_ar_prog, self.is_like_osx, cmd, None, [&self.dst, file]);
     }
 
     /// Removes a file from this archive
     pub fn remove_file(&mut self, file: &str) {
-        run_ar(self.handler, &self.maybe_ar_prog, "d", None, [&self.dst, &Path::new(file)]);
+        run_ar(self.handler, &self.maybe_ar_prog, self.is_like_osx, "d", None,
+               [&self.dst, &Path::new(file)]);
     }
 
     /// Updates all symbols in the archive (runs 'ar s' over it)
     pub fn update_symbols(&mut self) {
-        run_ar(self.handler, &self.maybe_ar_prog, "s", None, [&self.dst]);
+        run_ar(self.handler, &self.maybe_ar_prog, self.is_like_osx, "s", None, [&self.dst]);
     }
 
     /// Lists all files in an archive
     pub fn files(&self) -> Vec<String> {
-        let output = run_ar(self.handler, &self.maybe_ar_prog, "t", None, [&self.dst]);
+        let output = run_ar(self.handler, &self.maybe_ar_prog, self.is_like_osx, "t", None,
+                            [&self.dst]);
         let output = str::from_utf8(output.output.as_slice()).unwrap();
         // use lines_any because windows delimits output with `\r\n` instead of
         // just `\n`
@@ -165,7 +188,8 @@ impl<'a> Archive<'a> {
 
         // First, extract the contents of the archive to a temporary directory
         let archive = os::make_absolute(archive);
-        run_ar(self.handler, &self.maybe_ar_prog, "x", Some(loc.path()), [&archive]);
+        run_ar(self.handler, &self.maybe_ar_prog, self.is_like_osx, "x", Some(loc.path()),
+               [&archive]);
 
         // Next, we must rename all of the inputs to "guaranteed unique names".
         // The reason for this is that archives are keyed off the name of the
@@ -175,7 +199,11 @@ impl<'a> Archive<'a> {
         // We skip any files explicitly desired for skipping, and we also skip
         // all SYMDEF files as these are just magical placeholders which get
         // re-created when we make a new archive anyway.
-        let files = try!(fs::readdir(loc.path()));
+        //
+        // The files are added in a fixed order, rather than the order the
+        // directory happens to list them in, to keep the archive deterministic.
+        let mut files = try!(fs::readdir(loc.path()));
+        files.sort();
         let mut inputs = Vec::new();
         for file in files.iter() {
             let filename = file.filename_str().unwrap();
@@ -201,17 +229,14 @@ impl<'a> Archive<'a> {
         // Finally, add all the renamed files to this archive
         let mut args = vec!(&self.dst);
         args.extend(inputs.iter());
-        run_ar(self.handler, &self.maybe_ar_prog, "r", None, args.as_slice());
+        run_ar(self.handler, &self.maybe_ar_prog, self.is_like_osx, "r", None, args.as_slice());
         Ok(())
     }
 
     fn find_library(&self, name: &str) -> Path {
-        let (osprefix, osext) = match self.os {
-            abi::OsWin32 => ("", "lib"), _ => ("lib", "a"),
-        };
         // On Windows, static libraries sometimes show up as libfoo.a and other
         // times show up as foo.lib
-        let oslibname = format!("{}{}.{}", osprefix, name, osext);
+        let oslibname = format!("{}{}{}", self.slib_prefix, name, self.slib_suffix);
         let unixlibname = format!("lib{}.a", name);
 
         for path in self.lib_search_paths.iter() {
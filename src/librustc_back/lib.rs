@@ -51,6 +51,7 @@ pub mod mipsel;
 pub mod rpath;
 pub mod sha2;
 pub mod svh;
+pub mod target;
 pub mod target_strs;
 pub mod x86;
 pub mod x86_64;
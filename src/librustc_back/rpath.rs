@@ -12,11 +12,11 @@
 use std::collections::HashSet;
 use std::os;
 use std::io::IoError;
-use syntax::abi;
 use syntax::ast;
 
 pub struct RPathConfig<'a> {
-    pub os: abi::Os,
+    pub has_rpath: bool,
+    pub is_like_osx: bool,
     pub used_crates: Vec<(ast::CrateNum, Option<Path>)>,
     pub out_filename: Path,
     pub get_install_prefix_lib_path: ||:'a -> Path,
@@ -25,19 +25,11 @@ pub struct RPathConfig<'a> {
 
 pub fn get_rpath_flags(config: RPathConfig) -> Vec<String> {
 
-    // No rpath on windows
-    if config.os == abi::OsWin32 {
+    // Some targets, such as windows, have no rpath
+    if !config.has_rpath {
         return Vec::new();
     }
 
-    let mut flags = Vec::new();
-
-    if config.os == abi::OsFreebsd {
-        flags.push_all(["-Wl,-rpath,/usr/local/lib/gcc46".to_string(),
-                        "-Wl,-rpath,/usr/local/lib/gcc44".to_string(),
-                        "-Wl,-z,origin".to_string()]);
-    }
-
     debug!("preparing the RPATH!");
 
     let libs = config.used_crates.clone();
@@ -46,8 +38,7 @@ pub fn get_rpath_flags(config: RPathConfig) -> Vec<String> {
     }).collect::<Vec<_>>();
 
     let rpaths = get_rpaths(config, libs.as_slice());
-    flags.push_all(rpaths_to_flags(rpaths.as_slice()).as_slice());
-    flags
+    rpaths_to_flags(rpaths.as_slice())
 }
 
 fn rpaths_to_flags(rpaths: &[String]) -> Vec<String> {
@@ -101,14 +92,13 @@ fn get_rpath_relative_to_output(config: &mut RPathConfig,
                                 lib: &Path) -> String {
     use std::os;
 
-    assert!(config.os != abi::OsWin32);
+    assert!(config.has_rpath);
 
     // Mac doesn't appear to support $ORIGIN
-    let prefix = match config.os {
-        abi::OsAndroid | abi::OsLinux | abi::OsFreebsd
-                          => "$ORIGIN",
-        abi::OsMacos => "@loader_path",
-        abi::OsWin32 | abi::OsiOS => unreachable!()
+    let prefix = if config.is_like_osx {
+        "@loader_path"
+    } else {
+        "$ORIGIN"
     };
 
     let mut lib = (config.realpath)(&os::make_absolute(lib)).unwrap();
@@ -145,7 +135,6 @@ fn minimize_rpaths(rpaths: &[String]) -> Vec<String> {
 mod test {
     use super::{RPathConfig};
     use super::{minimize_rpaths, rpaths_to_flags, get_rpath_relative_to_output};
-    use syntax::abi;
 
     #[test]
     fn test_rpaths_to_flags() {
@@ -198,7 +187,8 @@ mod test {
     #[cfg(target_os = "android")]
     fn test_rpath_relative() {
         let config = &mut RPathConfig {
-            os: abi::OsLinux,
+            has_rpath: true,
+            is_like_osx: false,
             used_crates: Vec::new(),
             out_filename: Path::new("bin/rustc"),
             get_install_prefix_lib_path: || fail!(),
@@ -212,7 +202,8 @@ mod test {
     #[cfg(target_os = "freebsd")]
     fn test_rpath_relative() {
         let config = &mut RPathConfig {
-            os: abi::OsFreebsd,
+            has_rpath: true,
+            is_like_osx: false,
             used_crates: Vec::new(),
             out_filename: Path::new("bin/rustc"),
             get_install_prefix_lib_path: || fail!(),
@@ -226,7 +217,8 @@ mod test {
     #[cfg(target_os = "macos")]
     fn test_rpath_relative() {
         let config = &mut RPathConfig {
-            os: abi::OsMacos,
+            has_rpath: true,
+            is_like_osx: true,
             used_crates: Vec::new(),
             out_filename: Path::new("bin/rustc"),
             get_install_prefix_lib_path: || fail!(),
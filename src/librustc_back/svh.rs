@@ -52,6 +52,7 @@ use std::hash::sip::SipState;
 use std::iter::range_step;
 use syntax::ast;
 use syntax::visit;
+use syntax::visit::Visitor;
 
 #[deriving(Clone, PartialEq)]
 pub struct Svh {
@@ -118,6 +119,28 @@ impl Svh {
     }
 }
 
+/// Hash the contents of a single item in the same way as `Svh::calculate`
+/// hashes the whole crate. This is what incremental compilation uses to tell
+/// which items changed between two builds.
+pub fn hash_item(item: &ast::Item) -> u64 {
+    let mut state = SipState::new();
+    {
+        let mut visit = svh_visitor::make(&mut state);
+        visit.visit_item(item, ());
+    }
+    state.result()
+}
+
+/// Hash a single view item in the same way as `Svh::calculate` does.
+pub fn hash_view_item(view_item: &ast::ViewItem) -> u64 {
+    let mut state = SipState::new();
+    {
+        let mut visit = svh_visitor::make(&mut state);
+        visit.visit_view_item(view_item, ());
+    }
+    state.result()
+}
+
 impl fmt::Show for Svh {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.pad(self.as_str())
@@ -0,0 +1,499 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Descriptions of the platforms rustc can generate code for.
+//!
+//! A `Target` holds everything the compiler needs to know about the target
+//! platform. `--target` names either one of the built-in targets by its
+//! triple, or a JSON file describing any other target:
+//!
+//! ```ignore
+//! {
+//!     "llvm-target": "arm-none-eabi",
+//!     "data-layout": "e-p:32:32:32-i1:8:8-i8:8:8-i16:16:16-i32:32:32-i64:64:64-...",
+//!     "target-endian": "little",
+//!     "target-word-size": "32",
+//!     "arch": "arm",
+//!     "target-os": "linux",
+//!     "linker": "arm-none-eabi-gcc",
+//!     "pre-link-args": ["-mcpu=cortex-m3", "-mthumb"],
+//!     "post-link-args": ["-lmyos"],
+//!     "exe-suffix": ".elf",
+//!     "dynamic-linking": false,
+//!     "unwinding": false
+//! }
+//! ```
+//!
+//! Only the first six fields are required. The others, along with the rest
+//! of the fields of `TargetOptions` spelled with dashes, default to the values
+//! of `TargetOptions::default()`. `arch` must be an architecture rustc can
+//! generate code for, since the calling conventions depend on it. `target-os`
+//! can be anything: it is only the `target_os` cfg value, and the conventions
+//! of the platform are described by fields such as `is-like-osx`.
+//!
+//! Crates record the `name` of the target they were built for, and are only
+//! linked with crates built for the same target. For a JSON specification
+//! this includes a hash of its contents, so that two specifications for the
+//! same LLVM target are still told apart.
+
+use std::hash;
+use std::io::File;
+use serialize::json;
+use syntax::abi;
+
+use arm;
+use mips;
+use mipsel;
+use x86;
+use x86_64;
+
+#[deriving(Clone)]
+pub struct Target {
+    /// The name recorded in the metadata of crates built for this target:
+    /// the triple of a built-in target, or the LLVM triple and a hash of the
+    /// specification.
+    pub name: String,
+    /// The triple LLVM generates code for.
+    pub llvm_target: String,
+    pub data_layout: String,
+    /// The `target_endian` cfg value, `little` or `big`.
+    pub target_endian: String,
+    /// The `target_word_size` cfg value, `32` or `64`. This is also the
+    /// width of pointers and of `int` and `uint`.
+    pub target_word_size: String,
+    /// The `target_arch` cfg value.
+    pub arch: String,
+    /// The `target_os` cfg value.
+    pub target_os: String,
+    pub options: TargetOptions,
+}
+
+#[deriving(Clone)]
+pub struct TargetOptions {
+    /// The `target_family` cfg value, also set as a cfg word. Default: none.
+    pub target_family: Option<String>,
+    /// The program used to link. Default: `cc`.
+    pub linker: String,
+    /// Arguments passed to the linker before any others. Default: none.
+    pub pre_link_args: Vec<String>,
+    /// Arguments passed to the linker after all others. Default: none.
+    pub post_link_args: Vec<String>,
+    /// Appended to the names of executables. Default: nothing.
+    pub exe_suffix: String,
+    /// Default: `lib`.
+    pub dll_prefix: String,
+    /// Default: `.so`.
+    pub dll_suffix: String,
+    /// Whether dynamic libraries can be built. Default: true.
+    pub dynamic_linking: bool,
+    /// Whether the target can unwind the stack. Without it, landing pads
+    /// are never emitted. Default: true.
+    pub unwinding: bool,
+    /// Default: `lib`.
+    pub staticlib_prefix: String,
+    /// Default: `.a`.
+    pub staticlib_suffix: String,
+    /// Whether executables are the default crate type. Otherwise a static
+    /// library is, as on iOS where executables can't be run without
+    /// jailbreaking. Default: true.
+    pub executables: bool,
+    /// The LLVM features enabled when `-C target-feature` isn't given.
+    /// Default: none.
+    pub features: String,
+    /// Whether functions and data get their own sections, which lets the
+    /// linker drop the unused ones. Default: true.
+    pub function_sections: bool,
+    /// Whether the frame pointer may be omitted when optimizing. Default:
+    /// true.
+    pub eliminate_frame_pointer: bool,
+    /// Whether stacks are split, with `__morestack` growing them. Default:
+    /// true.
+    pub morestack: bool,
+    /// Whether the target follows the conventions of OSX and iOS: Mach-O
+    /// objects, `ld64` and its flags, `dsymutil`, and `@loader_path` in
+    /// rpaths. Default: false.
+    pub is_like_osx: bool,
+    /// Whether the target follows the conventions of Windows: COFF objects,
+    /// no rpaths, stdcall for `extern "system"` on x86, and no control over
+    /// the symbols a dynamic library exports. Default: false.
+    pub is_like_windows: bool,
+    /// Whether the linker understands the flags of GNU ld, such as
+    /// `--as-needed`. Default: false.
+    pub linker_is_gnu: bool,
+    /// Whether `-C rpath` can be honoured. Default: false.
+    pub has_rpath: bool,
+}
+
+impl TargetOptions {
+    pub fn default() -> TargetOptions {
+        TargetOptions {
+            target_family: None,
+            linker: "cc".to_string(),
+            pre_link_args: Vec::new(),
+            post_link_args: Vec::new(),
+            exe_suffix: String::new(),
+            dll_prefix: "lib".to_string(),
+            dll_suffix: ".so".to_string(),
+            dynamic_linking: true,
+            unwinding: true,
+            staticlib_prefix: "lib".to_string(),
+            staticlib_suffix: ".a".to_string(),
+            executables: true,
+            features: String::new(),
+            function_sections: true,
+            eliminate_frame_pointer: true,
+            morestack: true,
+            is_like_osx: false,
+            is_like_windows: false,
+            linker_is_gnu: false,
+            has_rpath: false,
+        }
+    }
+}
+
+impl Target {
+    /// Find the target named by `--target`: the path to a JSON target
+    /// specification, or the triple of a built-in target.
+    pub fn search(target: &str) -> Result<Target, String> {
+        let path = Path::new(target);
+        if path.extension_str() != Some("json") && !path.is_file() {
+            return Target::from_triple(target);
+        }
+
+        let contents = match File::open(&path).read_to_string() {
+            Ok(contents) => contents,
+            Err(e) => {
+                return Err(format!("could not read target specification `{}`: {}",
+                                   path.display(), e))
+            }
+        };
+        let spec = match json::from_str(contents.as_slice()) {
+            Ok(spec) => spec,
+            Err(e) => {
+                return Err(format!("could not parse target specification `{}`: {}",
+                                   path.display(), e))
+            }
+        };
+        Target::from_json(&spec).map_err(|e| {
+            format!("invalid target specification `{}`: {}", path.display(), e)
+        })
+    }
+
+    /// Build a target from the contents of a JSON target specification.
+    pub fn from_json(spec: &json::Json) -> Result<Target, String> {
+        if spec.as_object().is_none() {
+            return Err("the specification must be a JSON object".to_string());
+        }
+
+        let default = TargetOptions::default();
+        let llvm_target = try!(required_string(spec, "llvm-target"));
+        let target = Target {
+            // The keys of a JSON object are kept sorted, so equivalent
+            // specifications hash the same.
+            name: format!("{}-{:016x}", llvm_target, hash::hash(&spec.to_string())),
+            llvm_target: llvm_target,
+            data_layout: try!(required_string(spec, "data-layout")),
+            target_endian: try!(required_string(spec, "target-endian")),
+            target_word_size: try!(required_string(spec, "target-word-size")),
+            arch: try!(required_string(spec, "arch")),
+            target_os: try!(required_string(spec, "target-os")),
+            options: TargetOptions {
+                target_family: match spec.find(&"target-family".to_string()) {
+                    Some(_) => Some(try!(required_string(spec, "target-family"))),
+                    None => None,
+                },
+                linker: try!(string_or(spec, "linker", default.linker)),
+                pre_link_args: try!(list_or(spec, "pre-link-args", default.pre_link_args)),
+                post_link_args: try!(list_or(spec, "post-link-args", default.post_link_args)),
+                exe_suffix: try!(string_or(spec, "exe-suffix", default.exe_suffix)),
+                dll_prefix: try!(string_or(spec, "dll-prefix", default.dll_prefix)),
+                dll_suffix: try!(string_or(spec, "dll-suffix", default.dll_suffix)),
+                dynamic_linking: try!(bool_or(spec, "dynamic-linking",
+                                              default.dynamic_linking)),
+                unwinding: try!(bool_or(spec, "unwinding", default.unwinding)),
+                staticlib_prefix: try!(string_or(spec, "staticlib-prefix",
+                                                 default.staticlib_prefix)),
+                staticlib_suffix: try!(string_or(spec, "staticlib-suffix",
+                                                 default.staticlib_suffix)),
+                executables: try!(bool_or(spec, "executables", default.executables)),
+                features: try!(string_or(spec, "features", default.features)),
+                function_sections: try!(bool_or(spec, "function-sections",
+                                                default.function_sections)),
+                eliminate_frame_pointer: try!(bool_or(spec, "eliminate-frame-pointer",
+                                                      default.eliminate_frame_pointer)),
+                morestack: try!(bool_or(spec, "morestack", default.morestack)),
+                is_like_osx: try!(bool_or(spec, "is-like-osx", default.is_like_osx)),
+                is_like_windows: try!(bool_or(spec, "is-like-windows",
+                                              default.is_like_windows)),
+                linker_is_gnu: try!(bool_or(spec, "linker-is-gnu", default.linker_is_gnu)),
+                has_rpath: try!(bool_or(spec, "has-rpath", default.has_rpath)),
+            },
+        };
+
+        match target.target_endian.as_slice() {
+            "little" | "big" => {}
+            _ => return Err("`target-endian` must be `little` or `big`".to_string()),
+        }
+        match target.target_word_size.as_slice() {
+            "32" | "64" => {}
+            _ => return Err("`target-word-size` must be `32` or `64`".to_string()),
+        }
+        if arch_from_name(target.arch.as_slice()).is_none() {
+            return Err(format!("unsupported architecture `{}`", target.arch));
+        }
+        Ok(target)
+    }
+
+    /// The triples of the built-in targets which the build system knows how
+    /// to build the standard library for, as listed by `--print
+    /// target-list`. Only those which `from_triple` can describe are
+    /// returned.
+    pub fn builtin_triples() -> Vec<&'static str> {
+        builtin_targets.iter().map(|&t| t).filter(|&t| {
+            Target::from_triple(t).is_ok()
+        }).collect()
+    }
+
+    /// Look up a built-in target by its triple.
+    pub fn from_triple(triple: &str) -> Result<Target, String> {
+        let os = match get_os(triple) {
+            Some(os) => os,
+            None => return Err("unknown operating system".to_string()),
+        };
+        let arch = match get_arch(triple) {
+            Some(arch) => arch,
+            None => return Err(format!("unknown architecture: {}", triple)),
+        };
+
+        // ARM is bi-endian, however using NDK seems to default
+        // to little-endian unless a flag is provided.
+        let (endian, arch_name, word_size) = match arch {
+            abi::X86 =>    ("little", "x86",    "32"),
+            abi::X86_64 => ("little", "x86_64", "64"),
+            abi::Arm =>    ("little", "arm",    "32"),
+            abi::Mips =>   ("big",    "mips",   "32"),
+            abi::Mipsel => ("little", "mipsel", "32")
+        };
+        let strs = match arch {
+            abi::X86 => x86::get_target_strs(triple.to_string(), os),
+            abi::X86_64 => x86_64::get_target_strs(triple.to_string(), os),
+            abi::Arm => arm::get_target_strs(triple.to_string(), os),
+            abi::Mips => mips::get_target_strs(triple.to_string(), os),
+            abi::Mipsel => mipsel::get_target_strs(triple.to_string(), os)
+        };
+
+        let mut options = TargetOptions::default();
+        options.pre_link_args = strs.cc_args;
+        match os {
+            abi::OsWin32 => {
+                options.target_family = Some("windows".to_string());
+                // For win32, there is no cc command, so we use gcc. It adds
+                // the `.exe` suffix itself.
+                options.linker = "gcc".to_string();
+                options.dll_prefix = String::new();
+                options.dll_suffix = ".dll".to_string();
+                options.staticlib_prefix = String::new();
+                options.staticlib_suffix = ".lib".to_string();
+                // FIXME(#13846) function sections should be enabled for
+                // windows
+                options.function_sections = false;
+                options.is_like_windows = true;
+            }
+            abi::OsMacos | abi::OsiOS => {
+                options.target_family = Some("unix".to_string());
+                options.dll_suffix = ".dylib".to_string();
+                // OSX has -dead_strip, which doesn't rely on function
+                // sections.
+                options.function_sections = false;
+                options.is_like_osx = true;
+                if os == abi::OsMacos {
+                    options.has_rpath = true;
+                    // FIXME: #11954: mac64 unwinding may not work with fp
+                    // elim
+                    options.eliminate_frame_pointer = arch != abi::X86_64;
+                } else {
+                    options.dynamic_linking = false;
+                    options.executables = false;
+                    if arch == abi::Arm {
+                        // Only armv7 and newer are supported on iOS, and
+                        // without VFP3 and NEON some intrinsics turn into
+                        // calls to functions nobody provides.
+                        options.features = "+v7,+thumb2,+vfp3,+neon".to_string();
+                        // Although there is an experimental implementation
+                        // of LLVM which supports split stacks on armv7, it
+                        // wasn't approved by Apple.
+                        options.morestack = false;
+                    }
+                }
+            }
+            abi::OsLinux | abi::OsAndroid | abi::OsFreebsd => {
+                options.target_family = Some("unix".to_string());
+                options.has_rpath = true;
+                options.linker_is_gnu = os == abi::OsLinux;
+            }
+        }
+        match os {
+            abi::OsAndroid => {
+                options.features = "+v7".to_string();
+                // Many of the symbols defined in compiler-rt are also
+                // defined in libgcc. Android linker doesn't like that by
+                // default.
+                options.pre_link_args.push("-Wl,--allow-multiple-definition".to_string());
+            }
+            abi::OsFreebsd => {
+                options.pre_link_args.push_all(["-L/usr/local/lib".to_string(),
+                                                "-L/usr/local/lib/gcc46".to_string(),
+                                                "-L/usr/local/lib/gcc44".to_string(),
+                                                "-Wl,-rpath,/usr/local/lib/gcc46".to_string(),
+                                                "-Wl,-rpath,/usr/local/lib/gcc44".to_string(),
+                                                "-Wl,-z,origin".to_string()]);
+            }
+            _ => {}
+        }
+
+        Ok(Target {
+            name: triple.to_string(),
+            llvm_target: strs.target_triple,
+            data_layout: strs.data_layout,
+            target_endian: endian.to_string(),
+            target_word_size: word_size.to_string(),
+            arch: arch_name.to_string(),
+            target_os: os.to_string(),
+            options: options,
+        })
+    }
+
+    pub fn architecture(&self) -> abi::Architecture {
+        arch_from_name(self.arch.as_slice()).expect("target with an unsupported architecture")
+    }
+}
+
+fn arch_from_name(name: &str) -> Option<abi::Architecture> {
+    match name {
+        "x86" => Some(abi::X86),
+        "x86_64" => Some(abi::X86_64),
+        "arm" => Some(abi::Arm),
+        "mips" => Some(abi::Mips),
+        "mipsel" => Some(abi::Mipsel),
+        _ => None,
+    }
+}
+
+fn required_string(spec: &json::Json, name: &str) -> Result<String, String> {
+    match spec.find(&name.to_string()).and_then(|value| value.as_string()) {
+        Some(value) => Ok(value.to_string()),
+        None => Err(format!("`{}` is required and must be a string", name)),
+    }
+}
+
+fn string_or(spec: &json::Json, name: &str, default: String) -> Result<String, String> {
+    match spec.find(&name.to_string()) {
+        Some(_) => required_string(spec, name),
+        None => Ok(default),
+    }
+}
+
+fn bool_or(spec: &json::Json, name: &str, default: bool) -> Result<bool, String> {
+    match spec.find(&name.to_string()) {
+        Some(value) => match value.as_boolean() {
+            Some(value) => Ok(value),
+            None => Err(format!("`{}` must be a boolean", name)),
+        },
+        None => Ok(default),
+    }
+}
+
+fn list_or(spec: &json::Json, name: &str,
+           default: Vec<String>) -> Result<Vec<String>, String> {
+    let list = match spec.find(&name.to_string()) {
+        Some(value) => match value.as_list() {
+            Some(list) => list,
+            None => return Err(format!("`{}` must be a list of strings", name)),
+        },
+        None => return Ok(default),
+    };
+    let mut strings = Vec::new();
+    for value in list.iter() {
+        match value.as_string() {
+            Some(s) => strings.push(s.to_string()),
+            None => return Err(format!("`{}` must be a list of strings", name)),
+        }
+    }
+    Ok(strings)
+}
+
+// The triples configured in `mk/platform.mk`.
+static builtin_targets: &'static [&'static str] = &[
+    "arm-apple-ios",
+    "arm-linux-androideabi",
+    "arm-unknown-linux-gnueabi",
+    "arm-unknown-linux-gnueabihf",
+    "i386-apple-ios",
+    "i586-mingw32msvc",
+    "i686-apple-darwin",
+    "i686-pc-mingw32",
+    "i686-unknown-linux-gnu",
+    "i686-w64-mingw32",
+    "mips-unknown-linux-gnu",
+    "mipsel-linux",
+    "x86_64-apple-darwin",
+    "x86_64-unknown-freebsd",
+    "x86_64-unknown-linux-gnu",
+    "x86_64-w64-mingw32"];
+
+pub fn get_os(triple: &str) -> Option<abi::Os> {
+    for &(name, os) in os_names.iter() {
+        if triple.contains(name) { return Some(os) }
+    }
+    None
+}
+static os_names : &'static [(&'static str, abi::Os)] = &[
+    ("mingw32", abi::OsWin32),
+    ("win32",   abi::OsWin32),
+    ("darwin",  abi::OsMacos),
+    ("android", abi::OsAndroid),
+    ("linux",   abi::OsLinux),
+    ("freebsd", abi::OsFreebsd),
+    ("ios",     abi::OsiOS)];
+
+pub fn get_arch(triple: &str) -> Option<abi::Architecture> {
+    for &(arch, abi) in architecture_abis.iter() {
+        if triple.contains(arch) { return Some(abi) }
+    }
+    None
+}
+static architecture_abis : &'static [(&'static str, abi::Architecture)] = &[
+    ("i386",   abi::X86),
+    ("i486",   abi::X86),
+    ("i586",   abi::X86),
+    ("i686",   abi::X86),
+    ("i786",   abi::X86),
+
+    ("x86_64", abi::X86_64),
+
+    ("arm",    abi::Arm),
+    ("xscale", abi::Arm),
+    ("thumb",  abi::Arm),
+
+    ("mipsel", abi::Mipsel),
+    ("mips",   abi::Mips)];
+
+#[cfg(test)]
+mod test {
+    use super::{Target, builtin_targets};
+
+    #[test]
+    fn builtin_targets_resolve() {
+        for &triple in builtin_targets.iter() {
+            assert!(Target::from_triple(triple).is_ok(), "{}", triple);
+        }
+        assert_eq!(Target::builtin_triples().len(), builtin_targets.len());
+    }
+}
@@ -244,7 +244,7 @@ pub enum AsmDialect {
     AD_Intel = 1
 }
 
-#[deriving(PartialEq)]
+#[deriving(Clone, PartialEq)]
 #[repr(C)]
 pub enum CodeGenOptLevel {
     CodeGenLevelNone = 0,
@@ -253,6 +253,7 @@ pub enum CodeGenOptLevel {
     CodeGenLevelAggressive = 3,
 }
 
+#[deriving(Clone)]
 #[repr(C)]
 pub enum RelocMode {
     RelocDefault = 0,
@@ -261,6 +262,7 @@ pub enum RelocMode {
     RelocDynamicNoPic = 3,
 }
 
+#[deriving(Clone)]
 #[repr(C)]
 pub enum CodeGenModel {
     CodeModelDefault = 0,
@@ -1499,6 +1501,9 @@ extern {
     /// Print the pass timings since static dtors aren't picking them up.
     pub fn LLVMRustPrintPassTimings();
 
+    /// Return the pass timings as a string, which must be freed with `free`.
+    pub fn LLVMRustPassTimingsToString() -> *const c_char;
+
     pub fn LLVMStructCreateNamed(C: ContextRef, Name: *const c_char) -> TypeRef;
 
     pub fn LLVMStructSetBody(StructTy: TypeRef,
@@ -1810,6 +1815,7 @@ extern {
                                       syms: *const *const c_char,
                                       len: size_t);
     pub fn LLVMRustMarkAllFunctionsNounwind(M: ModuleRef);
+    pub fn LLVMRustDeleteFunctionBody(F: ValueRef);
 
     pub fn LLVMRustOpenArchive(path: *const c_char) -> ArchiveRef;
     pub fn LLVMRustArchiveReadSection(AR: ArchiveRef, name: *const c_char,
@@ -111,7 +111,9 @@ impl Abi {
         self.data().name
     }
 
-    pub fn for_target(&self, os: Os, arch: Architecture) -> Option<Abi> {
+    /// The ABI this one stands for on a target with the given architecture,
+    /// which follows the conventions of Windows if `is_like_windows`.
+    pub fn for_target(&self, is_like_windows: bool, arch: Architecture) -> Option<Abi> {
         // If this ABI isn't actually for the specified architecture, then we
         // short circuit early
         match self.data().abi_arch {
@@ -120,8 +122,8 @@ impl Abi {
         }
         // Transform this ABI as appropriate for the requested os/arch
         // combination.
-        Some(match (*self, os, arch) {
-            (System, OsWin32, X86) => Stdcall,
+        Some(match (*self, is_like_windows, arch) {
+            (System, true, X86) => Stdcall,
             (System, _, _) => C,
             (me, _, _) => me,
         })
@@ -188,12 +190,12 @@ fn indices_are_correct() {
 
 #[test]
 fn pick_uniplatform() {
-    assert_eq!(Stdcall.for_target(OsLinux, X86), Some(Stdcall));
-    assert_eq!(Stdcall.for_target(OsLinux, Arm), None);
-    assert_eq!(System.for_target(OsLinux, X86), Some(C));
-    assert_eq!(System.for_target(OsWin32, X86), Some(Stdcall));
-    assert_eq!(System.for_target(OsWin32, X86_64), Some(C));
-    assert_eq!(System.for_target(OsWin32, Arm), Some(C));
-    assert_eq!(Stdcall.for_target(OsWin32, X86), Some(Stdcall));
-    assert_eq!(Stdcall.for_target(OsWin32, X86_64), Some(Stdcall));
+    assert_eq!(Stdcall.for_target(false, X86), Some(Stdcall));
+    assert_eq!(Stdcall.for_target(false, Arm), None);
+    assert_eq!(System.for_target(false, X86), Some(C));
+    assert_eq!(System.for_target(true, X86), Some(Stdcall));
+    assert_eq!(System.for_target(true, X86_64), Some(C));
+    assert_eq!(System.for_target(true, Arm), Some(C));
+    assert_eq!(Stdcall.for_target(true, X86), Some(Stdcall));
+    assert_eq!(Stdcall.for_target(true, X86_64), Some(Stdcall));
 }
@@ -152,6 +152,18 @@ pub fn original_sp(sp: Span, enclosing_sp: Span) -> Span {
     }
 }
 
+/// Rewrite the start of `path` according to the `(from, to)` prefix
+/// mappings given with `--remap-path-prefix`. The last matching mapping wins,
+/// and a path matching none of them is returned as is.
+pub fn remap_path_prefix(mappings: &[(String, String)], path: &str) -> String {
+    for &(ref from, ref to) in mappings.iter().rev() {
+        if path.starts_with(from.as_slice()) {
+            return format!("{}{}", to, path.slice_from(from.len()));
+        }
+    }
+    path.to_string()
+}
+
 /// A source code location used for error reporting
 pub struct Loc {
     /// Information about the original source
@@ -690,4 +702,17 @@ mod test {
 
         assert_eq!(sstr, "blork.rs:2:1: 2:12".to_string());
     }
+
+    #[test]
+    fn t10() {
+        // Test remap_path_prefix, where the last matching mapping wins
+        let mappings = vec!(("/home/user".to_string(), "/src".to_string()),
+                            ("/home/user/foo".to_string(), "foo".to_string()));
+        assert_eq!(remap_path_prefix(mappings.as_slice(), "/home/user/bar/lib.rs"),
+                   "/src/bar/lib.rs".to_string());
+        assert_eq!(remap_path_prefix(mappings.as_slice(), "/home/user/foo/lib.rs"),
+                   "foo/lib.rs".to_string());
+        assert_eq!(remap_path_prefix(mappings.as_slice(), "/tmp/lib.rs"),
+                   "/tmp/lib.rs".to_string());
+    }
 }
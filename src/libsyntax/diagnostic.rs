@@ -15,10 +15,14 @@ use codemap;
 use diagnostics;
 
 use std::cell::{RefCell, Cell};
+use std::collections::HashMap;
 use std::fmt;
 use std::io;
 use std::iter::range;
+use std::mem;
+use std::str;
 use std::string::String;
+use serialize::json;
 use term::WriterWrapper;
 use term;
 
@@ -35,22 +39,49 @@ pub enum RenderSpan {
     /// A FileLine renders with just a line for the message prefixed
     /// by file:linenum.
     FileLine(Span),
+
+    /// A Suggestion renders with a line for the message prefixed by
+    /// file:linenum, followed by the source code covered by the span
+    /// with the given text substituted for it.
+    Suggestion(Span, String),
 }
 
 impl RenderSpan {
-    fn span(self) -> Span {
-        match self {
-            FullSpan(s) | FileLine(s) => s
+    fn span(&self) -> Span {
+        match *self {
+            FullSpan(s) | FileLine(s) | Suggestion(s, _) => s
         }
     }
     fn is_full_span(&self) -> bool {
         match self {
             &FullSpan(..) => true,
-            &FileLine(..) => false,
+            &FileLine(..) | &Suggestion(..) => false,
         }
     }
 }
 
+/// A replacement for the source text covered by `span`, as suggested by a
+/// diagnostic through `SpanHandler::span_suggestion`.
+#[deriving(Clone)]
+pub struct CodeSuggestion {
+    pub span: Span,
+    pub msg: String,
+    pub replacement: String,
+    pub applicability: Applicability,
+}
+
+/// Whether a suggestion can be applied without a human looking at it.
+#[deriving(Clone, PartialEq, Show)]
+pub enum Applicability {
+    /// The replacement is certainly what was meant and keeps the code
+    /// compiling, e.g. removing an unused import. Only these suggestions
+    /// are applied by `SpanHandler::apply_suggestions`.
+    MachineApplicable,
+    /// The replacement is a guess, such as a similarly named item, or may
+    /// change what the code does. It is only shown.
+    MaybeIncorrect,
+}
+
 #[deriving(Clone)]
 pub enum ColorConfig {
     Auto,
@@ -80,6 +111,7 @@ pub struct ExplicitBug;
 pub struct SpanHandler {
     pub handler: Handler,
     pub cm: codemap::CodeMap,
+    suggestions: RefCell<Vec<CodeSuggestion>>,
 }
 
 impl SpanHandler {
@@ -110,6 +142,31 @@ impl SpanHandler {
     pub fn fileline_note(&self, sp: Span, msg: &str) {
         self.handler.custom_emit(&self.cm, FileLine(sp), msg, Note);
     }
+    pub fn span_help(&self, sp: Span, msg: &str) {
+        self.handler.emit(Some((&self.cm, sp)), msg, Help);
+    }
+    /// Suggest replacing the source text covered by `sp` with `suggestion`.
+    ///
+    /// Suggestions are recorded so that `apply_suggestions` can later rewrite
+    /// the source files with those which are `MachineApplicable`.
+    pub fn span_suggestion(&self, sp: Span, msg: &str, suggestion: String,
+                           applicability: Applicability) {
+        self.handler.custom_emit(&self.cm, Suggestion(sp, suggestion.clone()), msg, Help);
+        self.suggestions.borrow_mut().push(CodeSuggestion {
+            span: sp,
+            msg: msg.to_string(),
+            replacement: suggestion,
+            applicability: applicability,
+        });
+    }
+    pub fn span_suggestions(&self, suggestions: &[CodeSuggestion]) {
+        for suggestion in suggestions.iter() {
+            self.span_suggestion(suggestion.span,
+                                 suggestion.msg.as_slice(),
+                                 suggestion.replacement.clone(),
+                                 suggestion.applicability);
+        }
+    }
     pub fn span_bug(&self, sp: Span, msg: &str) -> ! {
         self.handler.emit(Some((&self.cm, sp)), msg, Bug);
         fail!(ExplicitBug);
@@ -120,6 +177,73 @@ impl SpanHandler {
     pub fn handler<'a>(&'a self) -> &'a Handler {
         &self.handler
     }
+
+    /// Rewrite the source files on disk, replacing the text covered by each
+    /// `MachineApplicable` suggestion made so far with its replacement.
+    /// Returns the number of suggestions applied.
+    ///
+    /// Suggestions within macro expansions or in sources which did not come
+    /// from a file are ignored, as are suggestions overlapping one which was
+    /// already applied. The files are read again, so that a byte order mark
+    /// or missing final newline is kept, and left alone if they have changed
+    /// since they were parsed.
+    pub fn apply_suggestions(&self) -> io::IoResult<uint> {
+        let suggestions = mem::replace(&mut *self.suggestions.borrow_mut(), Vec::new());
+
+        let mut by_file: HashMap<String, Vec<(uint, uint, String)>> = HashMap::new();
+        for suggestion in suggestions.move_iter() {
+            let sp = suggestion.span;
+            if suggestion.applicability != MachineApplicable { continue }
+            if sp.expn_info.is_some() { continue }
+            let lo = self.cm.lookup_byte_offset(sp.lo);
+            let hi = self.cm.lookup_byte_offset(sp.hi);
+            if lo.fm.start_pos != hi.fm.start_pos || !lo.fm.is_real_file() {
+                continue
+            }
+            by_file.find_or_insert(lo.fm.name.clone(), Vec::new())
+                   .push((lo.pos.to_uint(), hi.pos.to_uint(), suggestion.replacement));
+        }
+
+        let mut applied = 0u;
+        for (name, replacements) in by_file.move_iter() {
+            let mut replacements = replacements;
+            replacements.sort_by(|&(a, _, _), &(b, _, _)| a.cmp(&b));
+            replacements.dedup();
+
+            // The offsets are into the `FileMap`'s copy of the source, which
+            // has the byte order mark removed and a final newline added.
+            let path = Path::new(name.as_slice());
+            let original = try!(io::File::open(&path).read_to_end());
+            let src = match str::from_utf8(original.as_slice()) {
+                Some(src) => src,
+                None => continue,
+            };
+            let (bom, src) = if src.starts_with("\ufeff") {
+                (src.slice_to(3), src.slice_from(3))
+            } else {
+                ("", src)
+            };
+            let fm = self.cm.get_filemap(name.as_slice());
+            let parsed = fm.src.as_slice();
+            if src != parsed && (src.len() + 1 != parsed.len() || !parsed.starts_with(src)) {
+                continue
+            }
+
+            let mut fixed = String::from_str(bom);
+            let mut last = 0u;
+            for &(lo, hi, ref replacement) in replacements.iter() {
+                if lo < last || hi > src.len() { continue }
+                fixed.push_str(src.slice(last, lo));
+                fixed.push_str(replacement.as_slice());
+                last = hi;
+                applied += 1;
+            }
+            fixed.push_str(src.slice_from(last));
+
+            try!(io::File::create(&path).write_str(fixed.as_slice()));
+        }
+        Ok(applied)
+    }
 }
 
 /// A handler deals with errors; certain errors
@@ -196,6 +320,7 @@ pub fn mk_span_handler(handler: Handler, cm: codemap::CodeMap) -> SpanHandler {
     SpanHandler {
         handler: handler,
         cm: cm,
+        suggestions: RefCell::new(Vec::new()),
     }
 }
 
@@ -218,6 +343,7 @@ pub enum Level {
     Error,
     Warning,
     Note,
+    Help,
 }
 
 impl fmt::Show for Level {
@@ -229,6 +355,7 @@ impl fmt::Show for Level {
             Fatal | Error => "error".fmt(f),
             Warning => "warning".fmt(f),
             Note => "note".fmt(f),
+            Help => "help".fmt(f),
         }
     }
 }
@@ -238,7 +365,8 @@ impl Level {
         match self {
             Bug | Fatal | Error => term::color::BRIGHT_RED,
             Warning => term::color::BRIGHT_YELLOW,
-            Note => term::color::BRIGHT_GREEN
+            Note => term::color::BRIGHT_GREEN,
+            Help => term::color::BRIGHT_CYAN,
         }
     }
 }
@@ -382,11 +510,224 @@ impl Emitter for EmitterWriter {
     }
 }
 
+/// An emitter which writes every diagnostic as a single line of JSON, for
+/// tools which would otherwise have to scrape the output of `EmitterWriter`.
+///
+/// Notes are attached to the diagnostic they follow, so each diagnostic is
+/// buffered until the next error or warning starts, a fatal error or bug is
+/// reported, or the emitter is dropped.
+pub struct JsonEmitter {
+    dst: Box<Writer + Send>,
+    registry: Option<diagnostics::registry::Registry>,
+    pending: Option<JsonDiagnostic>,
+}
+
+#[deriving(Encodable)]
+struct JsonDiagnostic {
+    message: String,
+    code: Option<JsonDiagnosticCode>,
+    /// One of "error", "warning", "note", "help", "fatal" or "bug".
+    level: &'static str,
+    span: Option<JsonSpan>,
+    /// The text suggested to replace `span` with, if this is a suggestion.
+    suggested_replacement: Option<String>,
+    /// Notes attached to this diagnostic.
+    children: Vec<JsonDiagnostic>,
+    /// The macro backtrace of `span`, innermost expansion first.
+    expansion: Vec<JsonExpansion>,
+}
+
+#[deriving(Encodable)]
+struct JsonDiagnosticCode {
+    code: String,
+    /// The text `--explain` would print for this code, if there is one.
+    explanation: Option<&'static str>,
+}
+
+#[deriving(Encodable)]
+struct JsonSpan {
+    file_name: String,
+    /// Byte offsets into the file, `byte_end` being exclusive.
+    byte_start: uint,
+    byte_end: uint,
+    /// 1-based line and column numbers, counted in characters.
+    line_start: uint,
+    line_end: uint,
+    column_start: uint,
+    column_end: uint,
+}
+
+#[deriving(Encodable)]
+struct JsonExpansion {
+    /// The name of the macro, e.g. `format!` or `#[deriving]`.
+    macro_decl_name: String,
+    def_site_span: Option<JsonSpan>,
+    call_site_span: JsonSpan,
+}
+
+impl JsonEmitter {
+    pub fn stderr(registry: Option<diagnostics::registry::Registry>) -> JsonEmitter {
+        JsonEmitter::new(box io::stderr(), registry)
+    }
+
+    pub fn new(dst: Box<Writer + Send>,
+               registry: Option<diagnostics::registry::Registry>) -> JsonEmitter {
+        JsonEmitter { dst: dst, registry: registry, pending: None }
+    }
+
+    fn diagnostic(&self, cmsp: Option<(&codemap::CodeMap, Span)>,
+                  msg: &str, code: Option<&str>, lvl: Level) -> JsonDiagnostic {
+        let code = code.map(|code| {
+            JsonDiagnosticCode {
+                code: code.to_string(),
+                explanation: self.registry.as_ref()
+                                 .and_then(|registry| registry.find_description(code)),
+            }
+        });
+        let (span, expansion) = match cmsp {
+            Some((cm, sp)) => (Some(JsonSpan::from_span(cm, sp)),
+                               JsonExpansion::backtrace(cm, sp)),
+            None => (None, Vec::new()),
+        };
+        JsonDiagnostic {
+            message: msg.to_string(),
+            code: code,
+            level: match lvl {
+                Bug => "bug",
+                Fatal => "fatal",
+                Error => "error",
+                Warning => "warning",
+                Note => "note",
+                Help => "help",
+            },
+            span: span,
+            suggested_replacement: None,
+            children: Vec::new(),
+            expansion: expansion,
+        }
+    }
+
+    fn push(&mut self, diagnostic: JsonDiagnostic, lvl: Level) -> io::IoResult<()> {
+        match (lvl, self.pending.as_mut()) {
+            (Note, Some(parent)) | (Help, Some(parent)) => {
+                parent.children.push(diagnostic);
+                return Ok(());
+            }
+            _ => {}
+        }
+        try!(self.flush());
+        match lvl {
+            // The handler fails right after reporting these, so there is no
+            // chance for notes to follow.
+            Bug | Fatal => self.write(&diagnostic),
+            Error | Warning | Note | Help => {
+                self.pending = Some(diagnostic);
+                Ok(())
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::IoResult<()> {
+        match self.pending.take() {
+            Some(diagnostic) => self.write(&diagnostic),
+            None => Ok(()),
+        }
+    }
+
+    fn write(&mut self, diagnostic: &JsonDiagnostic) -> io::IoResult<()> {
+        let line = json::encode(diagnostic);
+        try!(self.dst.write_str(line.as_slice()));
+        self.dst.write_char('\n')
+    }
+}
+
+impl Emitter for JsonEmitter {
+    fn emit(&mut self,
+            cmsp: Option<(&codemap::CodeMap, Span)>,
+            msg: &str, code: Option<&str>, lvl: Level) {
+        let diagnostic = self.diagnostic(cmsp, msg, code, lvl);
+        match self.push(diagnostic, lvl) {
+            Ok(()) => {}
+            Err(e) => fail!("failed to print diagnostics: {}", e),
+        }
+    }
+
+    fn custom_emit(&mut self, cm: &codemap::CodeMap,
+                   sp: RenderSpan, msg: &str, lvl: Level) {
+        let mut diagnostic = self.diagnostic(Some((cm, sp.span())), msg, None, lvl);
+        match sp {
+            Suggestion(_, suggestion) => diagnostic.suggested_replacement = Some(suggestion),
+            FullSpan(..) | FileLine(..) => {}
+        }
+        match self.push(diagnostic, lvl) {
+            Ok(()) => {}
+            Err(e) => fail!("failed to print diagnostics: {}", e),
+        }
+    }
+}
+
+impl Drop for JsonEmitter {
+    fn drop(&mut self) {
+        // We may be unwinding from a fatal error here, so don't fail again.
+        let _ = self.flush();
+    }
+}
+
+impl JsonSpan {
+    fn from_span(cm: &codemap::CodeMap, sp: Span) -> JsonSpan {
+        let lo = cm.lookup_char_pos(sp.lo);
+        let hi = cm.lookup_char_pos(sp.hi);
+        JsonSpan {
+            file_name: lo.file.name.clone(),
+            byte_start: cm.lookup_byte_offset(sp.lo).pos.to_uint(),
+            byte_end: cm.lookup_byte_offset(sp.hi).pos.to_uint(),
+            line_start: lo.line,
+            line_end: hi.line,
+            column_start: lo.col.to_uint() + 1,
+            column_end: hi.col.to_uint() + 1,
+        }
+    }
+}
+
+impl JsonExpansion {
+    fn backtrace(cm: &codemap::CodeMap, sp: Span) -> Vec<JsonExpansion> {
+        let mut expansions = Vec::new();
+        JsonExpansion::push_backtrace(&mut expansions, cm, sp);
+        expansions
+    }
+
+    fn push_backtrace(expansions: &mut Vec<JsonExpansion>,
+                      cm: &codemap::CodeMap,
+                      sp: Span) {
+        for ei in sp.expn_info.iter() {
+            let (pre, post) = match ei.callee.format {
+                codemap::MacroAttribute => ("#[", "]"),
+                codemap::MacroBang => ("", "!")
+            };
+            expansions.push(JsonExpansion {
+                macro_decl_name: format!("{}{}{}", pre, ei.callee.name, post),
+                def_site_span: ei.callee.span.as_ref()
+                                 .map(|span| JsonSpan::from_span(cm, *span)),
+                call_site_span: JsonSpan::from_span(cm, ei.call_site),
+            });
+            JsonExpansion::push_backtrace(expansions, cm, ei.call_site);
+        }
+    }
+}
+
 fn emit(dst: &mut EmitterWriter, cm: &codemap::CodeMap, rsp: RenderSpan,
         msg: &str, code: Option<&str>, lvl: Level, custom: bool) -> io::IoResult<()> {
     let sp = rsp.span();
     let ss = cm.span_to_string(sp);
     let lines = cm.span_to_lines(sp);
+    match rsp {
+        Suggestion(_, ref suggestion) => {
+            try!(print_diagnostic(dst, ss.as_slice(), lvl, msg, code));
+            try!(show_suggestion(dst, cm, sp, suggestion.as_slice()));
+            return print_macro_backtrace(dst, cm, sp);
+        }
+        FullSpan(..) | FileLine(..) => {}
+    }
     if custom {
         // we want to tell compiletest/runtest to look at the last line of the
         // span (since `custom_highlight_lines` displays an arrow to the end of
@@ -478,6 +819,35 @@ fn highlight_lines(err: &mut EmitterWriter,
     Ok(())
 }
 
+/// Print the lines covered by `sp` as they would read with `suggestion`
+/// substituted for the text of the span.
+fn show_suggestion(w: &mut EmitterWriter,
+                   cm: &codemap::CodeMap,
+                   sp: Span,
+                   suggestion: &str) -> io::IoResult<()> {
+    let lo = cm.lookup_byte_offset(sp.lo);
+    let hi = cm.lookup_byte_offset(sp.hi);
+    let fm = &*lo.fm;
+    let src = fm.src.as_slice();
+    let (lo, hi) = (lo.pos.to_uint(), hi.pos.to_uint());
+
+    // Extend the replaced text to whole lines on either side.
+    let line_start = src.slice_to(lo).rfind('\n').map_or(0, |i| i + 1);
+    let line_end = src.slice_from(hi).find('\n').map_or(src.len(), |i| hi + i);
+    let fixed = format!("{}{}{}", src.slice(line_start, lo), suggestion,
+                        src.slice(hi, line_end));
+
+    let first_line = cm.lookup_char_pos(sp.lo).line;
+    let mut lines = fixed.as_slice().lines().enumerate();
+    for (i, line) in lines.by_ref().take(MAX_LINES) {
+        try!(write!(&mut w.dst, "{}:{} {}\n", fm.name, first_line + i, line));
+    }
+    if lines.next().is_some() {
+        try!(write!(&mut w.dst, "...\n"));
+    }
+    Ok(())
+}
+
 /// Here are the differences between this and the normal `highlight_lines`:
 /// `custom_highlight_lines` will always put arrow on the last byte of the
 /// span (instead of the first byte). Also, when the span is too long (more
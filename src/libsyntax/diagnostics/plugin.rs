@@ -52,11 +52,21 @@ pub fn expand_diagnostic_used(ecx: &mut ExtCtxt, span: Span,
         _ => unreachable!()
     };
     with_registered_diagnostics(|diagnostics| {
-        if !diagnostics.contains_key(&code.name) {
-            ecx.span_err(span, format!(
-                "unknown diagnostic code {}; add to librustc/diagnostics.rs",
-                token::get_ident(code).get()
-            ).as_slice());
+        match diagnostics.find(&code.name) {
+            Some(&Some(_)) => (),
+            Some(&None) => {
+                ecx.span_err(span, format!(
+                    "diagnostic code {} has no extended description; \
+                     add one to librustc/diagnostics.rs",
+                    token::get_ident(code).get()
+                ).as_slice());
+            }
+            None => {
+                ecx.span_err(span, format!(
+                    "unknown diagnostic code {}; add to librustc/diagnostics.rs",
+                    token::get_ident(code).get()
+                ).as_slice());
+            }
         }
         ()
     });
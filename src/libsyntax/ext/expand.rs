@@ -1021,6 +1021,8 @@ fn new_span(cx: &ExtCtxt, sp: Span) -> Span {
 pub struct ExpansionConfig {
     pub deriving_hash_type_parameter: bool,
     pub crate_name: String,
+    /// The `(from, to)` prefixes to rewrite in paths expanded by `file!()`.
+    pub remap_path_prefix: Vec<(String, String)>,
 }
 
 pub struct ExportedMacros {
@@ -1248,6 +1250,7 @@ mod test {
         let cfg = ::syntax::ext::expand::ExpansionConfig {
             deriving_hash_type_parameter: false,
             crate_name: "test".to_string(),
+            remap_path_prefix: Vec::new(),
         };
         expand_crate(&sess,cfg,vec!(),vec!(),crate_ast);
     }
@@ -1265,6 +1268,7 @@ mod test {
         let cfg = ::syntax::ext::expand::ExpansionConfig {
             deriving_hash_type_parameter: false,
             crate_name: "test".to_string(),
+            remap_path_prefix: Vec::new(),
         };
         expand_crate(&sess,cfg,vec!(),vec!(),crate_ast);
     }
@@ -1281,6 +1285,7 @@ mod test {
         let cfg = ::syntax::ext::expand::ExpansionConfig {
             deriving_hash_type_parameter: false,
             crate_name: "test".to_string(),
+            remap_path_prefix: Vec::new(),
         };
         expand_crate(&sess, cfg, vec!(), vec!(), crate_ast);
     }
@@ -1318,6 +1323,7 @@ mod test {
         let cfg = ::syntax::ext::expand::ExpansionConfig {
             deriving_hash_type_parameter: false,
             crate_name: "test".to_string(),
+            remap_path_prefix: Vec::new(),
         };
         expand_crate(&ps,cfg,vec!(),vec!(),crate_ast)
     }
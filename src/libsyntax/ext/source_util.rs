@@ -57,7 +57,9 @@ pub fn expand_file(cx: &mut ExtCtxt, sp: Span, tts: &[ast::TokenTree])
 
     let topmost = topmost_expn_info(cx.backtrace().unwrap());
     let loc = cx.codemap().lookup_char_pos(topmost.call_site.lo);
-    let filename = token::intern_and_get_ident(loc.file.name.as_slice());
+    let filename = codemap::remap_path_prefix(cx.ecfg.remap_path_prefix.as_slice(),
+                                              loc.file.name.as_slice());
+    let filename = token::intern_and_get_ident(filename.as_slice());
     base::MacExpr::new(cx.expr_str(topmost.call_site, filename))
 }
 
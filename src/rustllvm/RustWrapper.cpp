@@ -78,6 +78,13 @@ extern "C" void LLVMRustPrintPassTimings() {
   TimerGroup::printAll(OS);
 }
 
+extern "C" char *LLVMRustPassTimingsToString() {
+  std::string s;
+  raw_string_ostream OS(s);
+  TimerGroup::printAll(OS);
+  return strdup(OS.str().data());
+}
+
 extern "C" LLVMValueRef LLVMGetOrInsertFunction(LLVMModuleRef M,
                                                 const char* Name,
                                                 LLVMTypeRef FunctionTy) {
@@ -776,3 +783,10 @@ extern "C" LLVMTypeRef
 LLVMRustArrayType(LLVMTypeRef ElementType, uint64_t ElementCount) {
     return wrap(ArrayType::get(unwrap(ElementType), ElementCount));
 }
+
+// Used to turn the definitions which belong to other codegen units into
+// declarations.
+extern "C" void
+LLVMRustDeleteFunctionBody(LLVMValueRef F) {
+    unwrap<Function>(F)->deleteBody();
+}
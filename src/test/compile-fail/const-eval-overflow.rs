@@ -0,0 +1,16 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+static A: u8 = 255 + 1; //~ ERROR attempted to add with overflow
+static B: i8 = -128 - 1; //~ ERROR attempted to subtract with overflow
+static C: u32 = 65536 * 65536; //~ ERROR attempted to multiply with overflow
+static D: uint = 1 - 2; //~ ERROR attempted to subtract with overflow
+
+fn main() {}
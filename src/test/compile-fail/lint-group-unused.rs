@@ -0,0 +1,25 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -D unused
+
+use std::mem::swap; //~ ERROR unused import
+
+fn unused_function() {} //~ ERROR code is never used
+
+#[allow(unused)]
+fn allowed() {
+    let x = 1u;
+}
+
+fn main() {
+    let mut y = 1u; //~ ERROR variable does not need to be mutable
+    y;  //~ ERROR path statement with no effect
+}
@@ -0,0 +1,40 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![deny(lossy_cast)]
+#![allow(dead_code)]
+
+static SMALL: u32 = 200;
+
+fn narrowing(a: u32, b: i64, c: i32) {
+    let _ = a as u8; //~ ERROR cast from `u32` to `u8` may truncate the value
+    let _ = b as i16; //~ ERROR cast from `i64` to `i16` may truncate the value
+    let _ = c as u32; //~ ERROR cast from `i32` to `u32` may change the sign of the value
+    let _ = a as i32; //~ ERROR cast from `u32` to `i32` may change the sign of the value
+    let _ = (a + 1) as u16; //~ ERROR cast from `u32` to `u16` may truncate the value
+}
+
+fn widening(a: u8, b: i16, c: u32) {
+    let _ = a as u32;
+    let _ = b as i64;
+    let _ = c as i64;
+    let _ = a as u8;
+}
+
+fn constants() {
+    let _ = 300u as u16;
+    let _ = -1i as i8;
+    let _ = SMALL as u8;
+    let _ = (1u32 << 4) as u8;
+    let _ = 300u as u8; //~ ERROR cast from `uint` to `u8` may truncate the value
+    let _ = -1i as uint; //~ ERROR cast from `int` to `uint` may change the sign of the value
+}
+
+fn main() {}
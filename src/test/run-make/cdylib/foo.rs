@@ -0,0 +1,23 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![crate_type = "cdylib"]
+
+extern crate dep;
+
+pub fn not_exported() -> i32 {
+    dep::add(1, 2)
+}
+
+#[no_mangle]
+pub extern "C" fn foo_add(a: i32, b: i32) -> i32 {
+    dep::add(a, b)
+}
@@ -0,0 +1,10 @@
+#include <assert.h>
+
+extern int foo_add(int a, int b);
+extern int dep_mul(int a, int b);
+
+int main() {
+  assert(foo_add(2, 3) == 5);
+  assert(dep_mul(2, 3) == 6);
+  return 0;
+}
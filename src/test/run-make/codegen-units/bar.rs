@@ -0,0 +1,23 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate foo;
+
+// Same name as the private function in `foo`; the two must not clash when
+// both crates are split into codegen units.
+fn helper(x: int) -> int {
+    x + 100
+}
+
+fn main() {
+    assert_eq!(foo::double_plus_one(20), 41);
+    assert_eq!(helper(1), 101);
+    assert_eq!(foo::greeting(), "hello");
+}
@@ -0,0 +1,25 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "rlib"]
+
+static GREETING: &'static str = "hello";
+
+fn helper(x: int) -> int {
+    x * 2
+}
+
+pub fn double_plus_one(x: int) -> int {
+    helper(x) + 1
+}
+
+pub fn greeting() -> &'static str {
+    GREETING
+}
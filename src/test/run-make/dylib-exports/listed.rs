@@ -0,0 +1,44 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![crate_type = "dylib"]
+#![export = "api"]
+
+pub mod api {
+    pub fn kept() {}
+
+    pub trait Shape {
+        fn sides(&self) -> uint;
+    }
+
+    pub struct Point;
+}
+
+// Exported along with `api::Point`, although it is declared outside `api`.
+impl Clone for api::Point {
+    fn clone(&self) -> api::Point { api::Point }
+}
+
+pub fn dropped() {}
+
+pub static DROPPED_STATIC: uint = 3;
+
+pub struct DroppedSquare;
+
+impl api::Shape for DroppedSquare {
+    fn sides(&self) -> uint { 4 }
+}
+
+// These are translated by the crates using them, so they need no symbol.
+#[inline]
+pub fn dropped_inlined() -> uint { 1 }
+
+pub fn dropped_generic<T>(t: T) -> T { t }
@@ -0,0 +1,29 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![crate_type = "dylib"]
+
+pub fn visible() -> int {
+    generic(1i)
+}
+
+#[inline]
+pub fn inlined() -> int {
+    helper()
+}
+
+fn helper() -> int {
+    42
+}
+
+fn generic<T>(t: T) -> T {
+    t
+}
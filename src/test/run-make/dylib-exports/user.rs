@@ -0,0 +1,39 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+extern crate listed;
+
+fn main() {
+    listed::api::kept();
+    let _point = listed::api::Point.clone();
+    assert_eq!(listed::dropped_inlined(), 1);
+    assert_eq!(listed::dropped_generic(2u), 2);
+    also();
+}
+
+#[cfg(not(bad), not(bad_static), not(bad_object))]
+fn also() {}
+
+#[cfg(bad)]
+fn also() {
+    listed::dropped();
+}
+
+#[cfg(bad_static)]
+fn also() {
+    assert_eq!(listed::DROPPED_STATIC, 3);
+}
+
+#[cfg(bad_object)]
+fn also() {
+    let shape = &listed::DroppedSquare as &listed::api::Shape;
+    assert_eq!(shape.sides(), 4);
+}
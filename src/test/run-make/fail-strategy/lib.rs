@@ -0,0 +1,39 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![no_std]
+#![feature(lang_items)]
+#![crate_type = "rlib"]
+
+extern crate core;
+
+use core::fmt;
+
+#[allow(ctypes)]
+extern {
+    #[lang = "begin_unwind"]
+    fn begin_unwind(fmt: &fmt::Arguments, file: &'static str, line: uint) -> !;
+}
+
+fn fail_fmt(fmt: &fmt::Arguments) -> ! {
+    unsafe { begin_unwind(fmt, "lib.rs", 30) }
+}
+
+pub fn check(x: uint) -> uint {
+    if x > 10 {
+        format_args!(fail_fmt, "{} is too big", x)
+    }
+    x
+}
+
+// `format_args!` refers to `::std::fmt`.
+mod std {
+    pub use core::fmt;
+}
@@ -0,0 +1,32 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+struct Point {
+    x: int,
+    y: int,
+}
+
+impl Point {
+    fn norm(&self) -> int {
+        self.x * self.x + self.y * self.y
+    }
+}
+
+mod shapes {
+    pub fn square(side: int) -> int { side * side }
+    pub fn cube(side: int) -> int { side * side * side }
+}
+
+fn main() {
+    let origin = Point { x: 3, y: 4 };
+    let area = shapes::square(origin.norm());
+    let total = origin.x + area;
+    assert!(total == 628);
+}
@@ -0,0 +1,75 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate rustc;
+extern crate syntax;
+
+use rustc::DIAGNOSTICS;
+use rustc::driver::config;
+use rustc::driver::driver::FileInput;
+use rustc::driver::session;
+use rustc::middle::save::query::{Analysis, Completion, CompletionKind};
+use rustc::middle::save::query::{FieldCompletion, ItemCompletion, MethodCompletion};
+use syntax::diagnostics::registry::Registry;
+
+use std::io::File;
+use std::os;
+
+fn names(completions: Vec<Completion>) -> Vec<(String, CompletionKind)> {
+    completions.move_iter().map(|c| (c.name, c.kind)).collect()
+}
+
+fn main() {
+    let args = os::args();
+    let sysroot = Path::new(args.get(1).as_slice());
+    let file = args.get(2).as_slice();
+    let src = File::open(&Path::new(file)).read_to_string().unwrap();
+    let at = |needle: &str| src.as_slice().find_str(needle).unwrap();
+
+    let opts = config::Options {
+        maybe_sysroot: Some(sysroot),
+        ..config::basic_options()
+    };
+    let sess = session::build_session(opts, Some(Path::new(file)), Registry::new(DIAGNOSTICS));
+    let cfg = config::build_configuration(&sess);
+    let analysis = Analysis::new(sess, cfg, &FileInput(Path::new(file)));
+
+    // The method call resolves to the method in the impl.
+    let norm_call = at("norm())");
+    let def = analysis.find_definition(file, norm_call + 1).unwrap();
+    let location = def.location.unwrap();
+    assert_eq!(location.lo, at("norm(&self)"));
+    assert_eq!(location.hi, at("norm(&self)") + 4);
+
+    let area = at("area =");
+    assert_eq!(analysis.type_at(file, area), Some("int".to_string()));
+
+    // The declaration of `origin` and its two uses.
+    let refs = analysis.find_references(file, at("origin ="));
+    assert_eq!(refs.len(), 3);
+    assert_eq!(refs.get(0).lo, at("origin ="));
+    assert_eq!(refs.get(1).lo, at("origin.norm"));
+    assert_eq!(refs.get(2).lo, at("origin.x"));
+
+    // Members of `Point`, filtered by what has been typed.
+    assert_eq!(names(analysis.completions(file, at("norm())") + 2)),
+               vec!(("norm".to_string(), MethodCompletion)));
+    assert_eq!(names(analysis.completions(file, at("x + area"))),
+               vec!(("norm".to_string(), MethodCompletion),
+                    ("x".to_string(), FieldCompletion),
+                    ("y".to_string(), FieldCompletion)));
+
+    // Siblings of `square` in `shapes`.
+    assert_eq!(names(analysis.completions(file, at("square(origin"))),
+               vec!(("cube".to_string(), ItemCompletion),
+                    ("square".to_string(), ItemCompletion)));
+    assert_eq!(names(analysis.completions(file, at("square(origin") + 1)),
+               vec!(("square".to_string(), ItemCompletion)));
+}
@@ -0,0 +1,27 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![crate_type = "rlib"]
+#![feature(macro_rules)]
+
+// Exported macros are kept in the metadata as source text
+#[macro_export]
+macro_rules! located(
+    ($e:expr) => (($e, file!()))
+)
+
+pub fn here() -> &'static str {
+    file!()
+}
+
+pub fn get(xs: &[int], i: uint) -> int {
+    xs[i]
+}
@@ -0,0 +1,23 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// The standard library isn't instrumented and `Vec` allocates through
+// jemalloc rather than `malloc`, so the buffer comes from `malloc`, which the
+// runtime intercepts.
+
+extern crate libc;
+
+fn main() {
+    unsafe {
+        let xs = libc::malloc(4) as *const u8;
+        let x = *xs.offset(4);
+        println!("{}", x);
+    }
+}
@@ -0,0 +1,47 @@
+// Copyright 2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "rlib"]
+
+pub struct Point {
+    pub x: int,
+    pub y: int,
+}
+
+impl Point {
+    pub fn new(x: int, y: int) -> Point {
+        Point { x: x, y: y }
+    }
+
+    pub fn norm(&self) -> int {
+        self.x * self.x + self.y * self.y
+    }
+}
+
+pub static ORIGIN: Point = Point { x: 0, y: 0 };
+
+pub static UNIT: Point = Point { x: 1, y: 1 };
+
+pub fn used() -> int {
+    1
+}
+
+pub fn unused() -> int {
+    2
+}
+
+#[allow(unused_pub_items)]
+pub fn allowed() -> int {
+    3
+}
+
+pub mod nested {
+    pub fn helper() {}
+}